@@ -56,6 +56,18 @@ type ScryfallCard struct {
 		Eur_foil string `json:"eur_foil"`
 		Tix      string `json:"tix"`
 	} `json:"prices"`
+	// Card details shown in the card detail view, omitted if Scryfall has no value for them
+	Id               string            `json:"id,omitempty"`
+	Mtgo_foil_id     int32             `json:"mtgo_foil_id,omitempty"`
+	Set              string            `json:"set,omitempty"`
+	Set_name         string            `json:"set_name,omitempty"`
+	Collector_number string            `json:"collector_number,omitempty"`
+	Type_line        string            `json:"type_line,omitempty"`
+	Mana_cost        string            `json:"mana_cost,omitempty"`
+	Cmc              float64           `json:"cmc,omitempty"`
+	Colors           []string          `json:"colors,omitempty"`
+	Oracle_text      string            `json:"oracle_text,omitempty"`
+	Legalities       map[string]string `json:"legalities,omitempty"`
 }
 
 func ScryfallCardsFromFile(fname string) ([]ScryfallCard, error) {
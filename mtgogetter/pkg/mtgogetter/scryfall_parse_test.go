@@ -35,6 +35,18 @@ func TestScryfallJsonDeserialize(t *testing.T) {
 		t.Errorf("Expected 0.47 got %s", bulk_data[0].Prices.Usd)
 	}
 
+	if first_card.Type_line != "Creature — Sliver" {
+		t.Errorf("Expected Creature — Sliver got %s", first_card.Type_line)
+	}
+
+	if first_card.Set_name != "Time Spiral" {
+		t.Errorf("Expected Time Spiral got %s", first_card.Set_name)
+	}
+
+	if first_card.Legalities["modern"] != "legal" {
+		t.Errorf("Expected legal in modern got %s", first_card.Legalities["modern"])
+	}
+
 	second_card := &bulk_data[1]
 
 	if second_card.Mtgo_id != 31745 {
@@ -137,7 +137,8 @@ fn format_include_binaries_rs(
 ///
 /// Creates the `bin` directory in the directory where the binary `MTGO GUI` is located.
 ///
-/// Writes the binaries to the `bin` directory if they don't already exist.
+/// Writes the binaries to the `bin` directory if they don't exist or their hash doesn't match the embedded binaries,
+/// e.g. if they're left over from another release.
 const WRITE_TO_BIN_DIR_FN: &str = r#"
 #[cfg(not(debug_assertions))]
 fn write_binaries_out() -> std::io::Result<()> {
@@ -156,44 +157,19 @@ fn write_binaries_out() -> std::io::Result<()> {
     path.pop();
     path.push("bin");
 
-    if !path.exists() {
-        std::fs::create_dir(&path)?;
-    }
-
-    let mtgogetter_bin = path.join(MTGOGETTER_BIN);
-
-    if !mtgogetter_bin.exists() {
-        std::fs::write(&mtgogetter_bin, MTGOGETTER)?;
-        if !cfg!(windows) {
-            let exit_status = std::process::Command::new("chmod")
-                    .arg("+x")
-                    .arg(format!("{}", mtgogetter_bin.display()))
-                    .status()
-                    .expect("Failed execute chmod to make mtgogetter executable");
-
-            assert!(
-                exit_status.success(),
-                "failed to make MTGO Getter executable"
-            );
-        }
-    }
-
-    let mtgo_preprocessor_bin = path.join(MTGO_PREPROCESSOR_BIN);
-
-    if !mtgo_preprocessor_bin.exists() {
-        std::fs::write(&mtgo_preprocessor_bin, MTGO_PREPROCESSOR)?;
-        if !cfg!(windows) {
-
-            let exit_status = std::process::Command::new("chmod")
-            .arg("+x")
-            .arg(format!("{}", mtgo_preprocessor_bin.display()))
-            .status()
-            .expect("Failed to execute chmod to make mtgo_preprocessor executable");
-
-            assert!(
-                exit_status.success(),
-                "failed to make MTGO Preprocessor executable"
-            );
+    for (bin, embedded) in [
+        (MTGOGETTER_BIN, MTGOGETTER as &[u8]),
+        (MTGO_PREPROCESSOR_BIN, MTGO_PREPROCESSOR),
+    ] {
+        let bin_path = path.join(bin);
+        match mtgoupdater::bin_check::extract_binary(&bin_path, embedded)? {
+            mtgoupdater::bin_check::Extraction::Unchanged => {
+                log::info!("{bin_path:?} matches the embedded binary")
+            }
+            mtgoupdater::bin_check::Extraction::Written => log::info!("Extracted {bin_path:?}"),
+            mtgoupdater::bin_check::Extraction::Replaced => {
+                log::warn!("{bin_path:?} didn't match the embedded binary and was re-extracted")
+            }
         }
     }
 
@@ -0,0 +1,92 @@
+//! The application core: the collection model and the logic that changes it, independent of the GUI.
+//!
+//! Views send [Command]s to the [AppCore] and subscribe to the [Event]s describing the changes,
+//! so the same logic can be driven by the GUI, the CLI, and tests.
+
+pub mod command;
+pub mod event;
+pub mod model;
+pub mod pipeline;
+
+pub use command::Command;
+pub use event::Event;
+pub use model::CollectionModel;
+
+/// Called with each [Event] the [AppCore] emits
+pub type Subscriber = Box<dyn FnMut(&Event)>;
+
+/// Owns the [CollectionModel] and notifies the subscribers of the changes to it
+#[derive(Default)]
+pub struct AppCore {
+    model: CollectionModel,
+    subscribers: Vec<Subscriber>,
+}
+
+impl std::fmt::Debug for AppCore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppCore")
+            .field("model", &self.model)
+            .field("subscribers", &self.subscribers.len())
+            .finish()
+    }
+}
+
+impl AppCore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn model(&self) -> &CollectionModel {
+        &self.model
+    }
+
+    /// Call the subscriber with every [Event] from now on
+    pub fn subscribe(&mut self, subscriber: impl FnMut(&Event) + 'static) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    /// Apply the command to the model and notify the subscribers of the resulting events
+    ///
+    /// # Returns
+    ///
+    /// The events, in the order the subscribers were notified of them
+    pub fn dispatch(&mut self, command: Command) -> Vec<Event> {
+        let events = self.model.apply(command);
+        for ev in &events {
+            for subscriber in &mut self.subscribers {
+                subscriber(ev);
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mtgoupdater::mtgo_card::MtgoCard;
+    use pretty_assertions::assert_eq;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn test_dispatch_notifies_subscribers() {
+        let mut core = AppCore::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        core.subscribe({
+            let seen = Rc::clone(&seen);
+            move |ev| seen.borrow_mut().push(format!("{ev:?}"))
+        });
+
+        let events = core.dispatch(Command::Search("island".into()));
+        core.dispatch(Command::SetCards(vec![MtgoCard {
+            name: "Island".into(),
+            quantity: 1,
+            ..Default::default()
+        }]));
+
+        assert!(matches!(events.as_slice(), [Event::RowsChanged]));
+        assert_eq!(seen.borrow()[0], "RowsChanged");
+        assert_eq!(seen.borrow().len(), 3);
+        assert_eq!(core.model().rows(), [0]);
+    }
+}
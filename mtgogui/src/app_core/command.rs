@@ -0,0 +1,22 @@
+use mtgoupdater::mtgo_card::{ItemCategory, MtgoCard};
+
+use crate::appdata::currency::Money;
+
+use super::model::Column;
+
+/// A request to change the [CollectionModel](super::CollectionModel)
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Replace the cards, e.g. with a newly processed trade list
+    SetCards(Vec<MtgoCard>),
+    /// Sort by the column, toggling the order if it's already sorted by it
+    SortBy(Column),
+    /// Only show the cards whose name contains the text, ignoring case
+    Search(Box<str>),
+    /// Only show the items in the category, or all items if [None]
+    FilterCategory(Option<ItemCategory>),
+    /// Only show the cards playable in the format, e.g. `modern`, or all items if [None]
+    FilterFormat(Option<Box<str>>),
+    /// Show the values in the currency and exchange rate
+    SetMoney(Money),
+}
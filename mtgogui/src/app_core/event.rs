@@ -0,0 +1,22 @@
+use crate::{appdata::currency::Money, collection::stats::container::CollectionStats};
+
+use super::model::{Column, Ordering};
+
+/// A change in the application core that views may need to reflect
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The additional price sources changed, see [CollectionModel::price_sources](super::CollectionModel::price_sources)
+    PriceSourcesChanged,
+    /// The cards matching the filter or their order changed, see [CollectionModel::rows](super::CollectionModel::rows)
+    RowsChanged,
+    /// The cards were sorted by the column in the order
+    Sorted { column: Column, ordering: Ordering },
+    /// The values are now shown in the currency and exchange rate
+    MoneyChanged(Money),
+    /// The stats of the collection were recalculated
+    StatsChanged(Box<CollectionStats>),
+    /// Processing a trade list progressed to the percentage
+    Progress { percent: f64, label: Box<str> },
+    /// Downloading the card data failed, so the cached data was used instead
+    OfflineModeDetected,
+}
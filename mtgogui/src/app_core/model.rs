@@ -0,0 +1,405 @@
+//! The collection model: the cards, how they're sorted and filtered, and the currency they're valued in.
+
+use std::{cmp, collections::HashMap};
+
+use mtgoupdater::{
+    mtgo_card::{ItemCategory, MtgoCard},
+    price_source,
+};
+
+use crate::{appdata::currency::Money, collection::stats::container::CollectionStats};
+
+use super::{command::Command, event::Event};
+
+/// A column the cards can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Name,
+    Quantity,
+    Foil,
+    Goatbots,
+    Scryfall,
+    Set,
+    Rarity,
+    /// An additional price source, by its index in [CollectionModel::price_sources]
+    Source(usize),
+}
+
+/// The sort order of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ordering {
+    Ascending,
+    Descending,
+}
+
+impl Ordering {
+    /// The other order
+    pub fn toggled(self) -> Self {
+        match self {
+            Ordering::Ascending => Ordering::Descending,
+            Ordering::Descending => Ordering::Ascending,
+        }
+    }
+}
+
+/// The cards in the collection and the state of the views of them
+#[derive(Debug, Clone)]
+pub struct CollectionModel {
+    cards: Vec<MtgoCard>,
+    /// Names of the price sources other than the built-in sources, in the order they're shown
+    price_sources: Vec<Box<str>>,
+    search: Box<str>,
+    category: Option<ItemCategory>,
+    /// Scryfall name of the format the cards must be playable in
+    format: Option<Box<str>>,
+    /// The order each column was last sorted in
+    sort_orders: HashMap<Column, Ordering>,
+    /// Index into `cards` of each card that matches the search and category filter
+    rows: Vec<usize>,
+    money: Money,
+}
+
+impl Default for CollectionModel {
+    fn default() -> Self {
+        Self {
+            cards: Vec::new(),
+            price_sources: Vec::new(),
+            search: "".into(),
+            category: None,
+            format: None,
+            sort_orders: HashMap::new(),
+            rows: Vec::new(),
+            money: Money::TIX,
+        }
+    }
+}
+
+impl CollectionModel {
+    /// All cards, in the current sort order
+    pub fn cards(&self) -> &[MtgoCard] {
+        &self.cards
+    }
+
+    /// Names of the price sources other than the built-in sources
+    pub fn price_sources(&self) -> &[Box<str>] {
+        &self.price_sources
+    }
+
+    /// Index into [CollectionModel::cards] of each card that matches the search and category filter
+    pub fn rows(&self) -> &[usize] {
+        &self.rows
+    }
+
+    /// The card in the row, if the row exists
+    pub fn card_in_row(&self, row: usize) -> Option<&MtgoCard> {
+        self.rows.get(row).and_then(|idx| self.cards.get(*idx))
+    }
+
+    /// The cards that match the search and category filter, in the current sort order
+    pub fn visible_cards(&self) -> impl Iterator<Item = &MtgoCard> {
+        self.rows.iter().map(|idx| &self.cards[*idx])
+    }
+
+    /// The order the column was last sorted in, [None] if it hasn't been sorted
+    pub fn sort_order(&self, column: Column) -> Option<Ordering> {
+        self.sort_orders.get(&column).copied()
+    }
+
+    /// The currency and exchange rate values are shown in
+    pub fn money(&self) -> Money {
+        self.money
+    }
+
+    /// The stats of all cards, valued in the current currency
+    pub fn stats(&self) -> CollectionStats {
+        CollectionStats::from_cards_in(&self.cards, self.money)
+    }
+
+    /// Apply the command to the model
+    ///
+    /// # Returns
+    ///
+    /// The events describing the changes, in the order they happened
+    pub fn apply(&mut self, command: Command) -> Vec<Event> {
+        match command {
+            Command::SetCards(cards) => self.set_cards(cards),
+            Command::SortBy(column) => self.sort_by(column),
+            Command::Search(search) => {
+                self.search = search;
+                self.refilter()
+            }
+            Command::FilterCategory(category) => {
+                self.category = category;
+                self.refilter()
+            }
+            Command::FilterFormat(format) => {
+                self.format = format;
+                self.refilter()
+            }
+            Command::SetMoney(money) => {
+                self.money = money;
+                let mut events = vec![Event::MoneyChanged(money)];
+                if !self.cards.is_empty() {
+                    events.push(self.stats_changed());
+                }
+                events
+            }
+        }
+    }
+
+    fn set_cards(&mut self, cards: Vec<MtgoCard>) -> Vec<Event> {
+        let mut events = Vec::new();
+        let price_sources: Vec<Box<str>> = price_source::source_names(&cards)
+            .into_iter()
+            .filter(|s| {
+                !matches!(
+                    s.as_ref(),
+                    price_source::GOATBOTS | price_source::CARDHOARDER
+                )
+            })
+            .collect();
+        if price_sources != self.price_sources {
+            self.price_sources = price_sources;
+            self.sort_orders
+                .retain(|column, _| !matches!(column, Column::Source(_)));
+            events.push(Event::PriceSourcesChanged);
+        }
+        self.cards = cards;
+        events.extend(self.refilter());
+        if !self.cards.is_empty() {
+            events.push(self.stats_changed());
+        }
+        events
+    }
+
+    /// Sort by the column, in the opposite order of the last time it was sorted or descending if it wasn't sorted yet
+    fn sort_by(&mut self, column: Column) -> Vec<Event> {
+        if let Column::Source(idx) = column {
+            if idx >= self.price_sources.len() {
+                return Vec::new();
+            }
+        }
+        let ordering = match self.sort_order(column) {
+            Some(Ordering::Descending) => Ordering::Ascending,
+            _ => Ordering::Descending,
+        };
+        sort_cards(&mut self.cards, column, ordering, &self.price_sources);
+        self.sort_orders.insert(column, ordering);
+        let mut events = vec![Event::Sorted { column, ordering }];
+        events.extend(self.refilter());
+        events
+    }
+
+    /// Find the rows matching the search, case-insensitive, the category and the format
+    fn refilter(&mut self) -> Vec<Event> {
+        let pattern = self.search.to_lowercase();
+        let category = self.category;
+        let format = self.format.as_deref();
+        self.rows = self
+            .cards
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                category.is_none_or(|cat| c.category() == cat)
+                    && format
+                        .is_none_or(|format| c.legality(format).is_some_and(|l| l.is_playable()))
+                    && (pattern.is_empty() || c.name.to_lowercase().contains(&pattern))
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        vec![Event::RowsChanged]
+    }
+
+    fn stats_changed(&self) -> Event {
+        Event::StatsChanged(Box::new(self.stats()))
+    }
+}
+
+/// Sort the cards by the column in the given order
+///
+/// Names and sets are sorted alphabetically when descending, the other columns from the highest value.
+/// `sources` are the names of the additional price sources, indexed by [Column::Source],
+/// cards without a price at the source sort as the cheapest.
+pub fn sort_cards(
+    cards: &mut [MtgoCard],
+    column: Column,
+    ordering: Ordering,
+    sources: &[Box<str>],
+) {
+    fn cmp_prices(a: Option<f32>, b: Option<f32>) -> cmp::Ordering {
+        a.partial_cmp(&b).unwrap_or(cmp::Ordering::Equal)
+    }
+    let descending = ordering == Ordering::Descending;
+    match column {
+        Column::Name if descending => cards.sort_by(|a, b| a.name.cmp(&b.name)),
+        Column::Name => cards.sort_by(|a, b| b.name.cmp(&a.name)),
+        Column::Set if descending => cards.sort_by(|a, b| a.set.cmp(&b.set)),
+        Column::Set => cards.sort_by(|a, b| b.set.cmp(&a.set)),
+        Column::Quantity if descending => cards.sort_by_key(|c| cmp::Reverse(c.quantity)),
+        Column::Quantity => cards.sort_by_key(|c| c.quantity),
+        Column::Foil if descending => cards.sort_by_key(|c| cmp::Reverse(c.foil)),
+        Column::Foil => cards.sort_by_key(|c| c.foil),
+        Column::Rarity if descending => cards.sort_by(|a, b| b.rarity.cmp(&a.rarity)),
+        Column::Rarity => cards.sort_by(|a, b| a.rarity.cmp(&b.rarity)),
+        Column::Goatbots | Column::Scryfall | Column::Source(_) => {
+            let source = match column {
+                Column::Goatbots => price_source::GOATBOTS,
+                Column::Scryfall => price_source::CARDHOARDER,
+                Column::Source(idx) => match sources.get(idx) {
+                    Some(source) => source,
+                    None => return,
+                },
+                _ => unreachable!(),
+            };
+            if descending {
+                cards.sort_by(|a, b| cmp_prices(b.price(source), a.price(source)));
+            } else {
+                cards.sort_by(|a, b| cmp_prices(a.price(source), b.price(source)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn names<'c>(cards: impl Iterator<Item = &'c MtgoCard>) -> Vec<&'c str> {
+        cards.map(|c| c.name.as_ref()).collect()
+    }
+
+    fn model_with_cards() -> CollectionModel {
+        let mut model = CollectionModel::default();
+        let mut ticket = MtgoCard {
+            id: MtgoCard::EVENT_TICKET_ID,
+            quantity: 10,
+            name: "Event Ticket".into(),
+            goatbots_price: 1.0,
+            ..Default::default()
+        };
+        ticket.prices.insert("MTGO Traders".into(), 1.0);
+        model.apply(Command::SetCards(vec![
+            MtgoCard {
+                id: 2,
+                quantity: 1,
+                name: "Ancestral Recall".into(),
+                goatbots_price: 3.0,
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 3,
+                quantity: 4,
+                name: "Counterspell".into(),
+                goatbots_price: 0.1,
+                ..Default::default()
+            },
+            ticket,
+        ]));
+        model
+    }
+
+    #[test]
+    fn test_set_cards_events_and_price_sources() {
+        let mut model = CollectionModel::default();
+
+        let events = model.apply(Command::SetCards(vec![MtgoCard {
+            id: 2,
+            quantity: 1,
+            name: "Island".into(),
+            ..Default::default()
+        }]));
+        assert!(matches!(
+            events.as_slice(),
+            [Event::RowsChanged, Event::StatsChanged(_)]
+        ));
+
+        let model = model_with_cards();
+        assert_eq!(model.price_sources(), [Box::from("MTGO Traders")]);
+        assert_eq!(model.rows(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_sort_toggles_order() {
+        let mut model = model_with_cards();
+
+        let events = model.apply(Command::SortBy(Column::Goatbots));
+        assert!(matches!(
+            events[0],
+            Event::Sorted {
+                column: Column::Goatbots,
+                ordering: Ordering::Descending
+            }
+        ));
+        assert_eq!(
+            names(model.visible_cards()),
+            ["Ancestral Recall", "Event Ticket", "Counterspell"]
+        );
+
+        model.apply(Command::SortBy(Column::Goatbots));
+        assert_eq!(
+            model.sort_order(Column::Goatbots),
+            Some(Ordering::Ascending)
+        );
+        assert_eq!(
+            names(model.visible_cards()),
+            ["Counterspell", "Event Ticket", "Ancestral Recall"]
+        );
+
+        // Cards without a price at the source sort as the cheapest
+        model.apply(Command::SortBy(Column::Source(0)));
+        assert_eq!(model.card_in_row(0).unwrap().name.as_ref(), "Event Ticket");
+        assert!(model.apply(Command::SortBy(Column::Source(1))).is_empty());
+    }
+
+    #[test]
+    fn test_search_and_category_filter() {
+        let mut model = model_with_cards();
+
+        model.apply(Command::Search("SPELL".into()));
+        assert_eq!(names(model.visible_cards()), ["Counterspell"]);
+
+        model.apply(Command::Search("".into()));
+        model.apply(Command::FilterCategory(Some(ItemCategory::EventTicket)));
+        assert_eq!(names(model.visible_cards()), ["Event Ticket"]);
+        assert_eq!(model.card_in_row(1), None);
+
+        // The filter is kept when the cards are replaced
+        model.apply(Command::SetCards(vec![MtgoCard {
+            id: 5,
+            quantity: 1,
+            name: "Island".into(),
+            ..Default::default()
+        }]));
+        assert_eq!(model.rows(), [] as [usize; 0]);
+        assert!(model.price_sources().is_empty());
+    }
+
+    #[test]
+    fn test_format_filter() {
+        use mtgoupdater::scryfall::Legality;
+
+        let mut model = model_with_cards();
+        let mut cards = model.cards().to_vec();
+        cards[0]
+            .legalities
+            .insert("vintage".into(), Legality::Restricted);
+        cards[1].legalities.extend([
+            ("vintage".into(), Legality::Legal),
+            ("modern".into(), Legality::Legal),
+        ]);
+        model.apply(Command::SetCards(cards));
+
+        model.apply(Command::FilterFormat(Some("vintage".into())));
+        assert_eq!(
+            names(model.visible_cards()),
+            ["Ancestral Recall", "Counterspell"]
+        );
+
+        model.apply(Command::FilterFormat(Some("modern".into())));
+        assert_eq!(names(model.visible_cards()), ["Counterspell"]);
+
+        model.apply(Command::FilterFormat(None));
+        assert_eq!(model.rows(), [0, 1, 2]);
+    }
+}
@@ -0,0 +1,324 @@
+//! Processing a Full Trade List into the cards of the collection.
+//!
+//! The card data is updated with `MTGO Getter`, the trade list is parsed by `MTGO Preprocessor`, and the card
+//! attributes from Scryfall and the prices from the additional price sources and custom price lists are added.
+//! Progress is reported as [Event]s.
+
+use std::{ffi::OsStr, fmt, path::Path};
+
+use mtgoupdater::{
+    mtgo_card::MtgoCard,
+    price_history::PriceHistoryStore,
+    price_source::{CsvPriceSource, PriceSource},
+    scryfall::ScryfallCards,
+};
+
+use crate::appdata::{
+    self,
+    state::PriceSourceConfig,
+    update::{AppData, OfflineMode},
+    COLLECTION_HISTORY_DIR, PRICE_HISTORY_MIGRATED, PRICE_HISTORY_STORE, SNAPSHOT_ARCHIVE,
+};
+
+use super::event::Event;
+
+/// The result of processing a trade list
+#[derive(Debug)]
+pub struct ProcessedTradeList {
+    pub cards: Vec<MtgoCard>,
+    /// The Scryfall data the card attributes were set from, [None] if it could not be loaded
+    pub scryfall: Option<ScryfallCards>,
+}
+
+/// The step of processing a trade list that failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessError {
+    /// The card data could not be updated or found in the appdata directory
+    UpdateCardData(String),
+    /// `MTGO Preprocessor` failed to parse the trade list
+    Preprocessor(String),
+}
+
+impl fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessError::UpdateCardData(e) => write!(f, "Failed to update the card data: {e}"),
+            ProcessError::Preprocessor(e) => write!(f, "MTGO Preprocessor error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ProcessError {}
+
+/// Process the trade list at the given path
+///
+/// Blocks until done, the GUI runs it in a background thread.
+///
+/// # Arguments
+///
+/// * `full_trade_list_path` - [Path] to the full trade list
+/// * `offline_mode` - Whether to download the newest card data first
+/// * `price_sources` - The additional price sources to fetch prices from
+/// * `on_event` - Called with the [Event::Progress] of each step and [Event::OfflineModeDetected]
+///
+/// # Errors
+///
+/// Returns the step that failed with a description of the problem
+pub fn process_trade_list(
+    full_trade_list_path: &Path,
+    offline_mode: OfflineMode,
+    price_sources: &[PriceSourceConfig],
+    mut on_event: impl FnMut(Event),
+) -> Result<ProcessedTradeList, ProcessError> {
+    on_event(progress(5., "Processing trade list"));
+    on_event(progress(10., "Updating card data..."));
+
+    // Find all the most recent files in the appdata directory, download and update them if necessary
+    let appdata_paths =
+        AppData::update(offline_mode).map_err(|e| ProcessError::UpdateCardData(e.to_string()))?;
+    if appdata_paths.offline_mode() == OfflineMode::Detected {
+        on_event(Event::OfflineModeDetected);
+    }
+    let appdata_dir = Path::new(appdata_paths.appdata_dir_path());
+    on_event(progress(70., "Update done"));
+
+    log::info!("Running MTGO Preprocessor");
+    log::info!("Scryfall path: {p:?}", p = appdata_paths.scryfall_path());
+    log::info!(
+        "Card definitions path: {p:?}",
+        p = appdata_paths.card_definitions_path()
+    );
+    log::info!(
+        "Price history path: {p:?}",
+        p = appdata_paths.price_history_path()
+    );
+    log::info!("Save to dir: {appdata_dir:?}");
+    on_event(progress(75., "Processing card data..."));
+
+    let mut cards = mtgoupdater::mtgo_preprocessor_api::run_mtgo_preprocessor_parse_full(
+        OsStr::new(full_trade_list_path),
+        appdata_paths.scryfall_path(),
+        appdata_paths.card_definitions_path(),
+        appdata_paths.price_history_path(),
+        Some(appdata_paths.appdata_dir_path()),
+    )
+    .map_err(|e| ProcessError::Preprocessor(e.to_string()))?;
+    log::info!("MTGO Preprocessor output: {} cards", cards.len());
+
+    let scryfall = match ScryfallCards::from_file(Path::new(appdata_paths.scryfall_path())) {
+        Ok(scryfall) => {
+            let applied = scryfall.apply(&mut cards);
+            log::info!("Applied Scryfall card attributes to {applied} cards");
+            Some(scryfall)
+        }
+        Err(e) => {
+            log::warn!("Failed to load Scryfall card details: {e}");
+            None
+        }
+    };
+
+    apply_additional_prices(
+        price_sources,
+        appdata_dir,
+        appdata_paths.offline_mode().is_offline(),
+        &mut cards,
+    );
+    appdata::price_lists::apply_price_lists(appdata_dir, &mut cards);
+    if let Err(e) = record_price_history(appdata_dir, &cards) {
+        log::warn!("Failed to record price history: {e}");
+    }
+    on_event(progress(95., "Processing complete..."));
+
+    Ok(ProcessedTradeList { cards, scryfall })
+}
+
+fn progress(percent: f64, label: &str) -> Event {
+    Event::Progress {
+        percent,
+        label: label.into(),
+    }
+}
+
+/// Fetch the prices from the additional price sources and add them to the cards
+///
+/// The price lists are saved to the appdata directory, and in offline mode the last saved price lists are used instead.
+/// A source that fails is logged and skipped, so it never blocks the collection from being shown.
+fn apply_additional_prices(
+    price_sources: &[PriceSourceConfig],
+    appdata_dir: &Path,
+    offline: bool,
+    cards: &mut [MtgoCard],
+) {
+    for config in price_sources {
+        let file_name = format!(
+            "price-source-{}.csv",
+            config
+                .name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                })
+                .collect::<String>()
+        );
+        let source = CsvPriceSource::new(&config.name, &config.url, appdata_dir.join(file_name));
+        let prices = if offline {
+            source.read_cached()
+        } else {
+            source.fetch().or_else(|e| {
+                log::warn!(
+                    "Failed to fetch {name} prices, using cached prices: {e}",
+                    name = config.name
+                );
+                source.read_cached()
+            })
+        };
+        match prices {
+            Ok(prices) => {
+                let priced = prices.apply(cards);
+                log::info!(
+                    "Applied {name} prices to {priced} cards",
+                    name = config.name
+                );
+            }
+            Err(e) => log::warn!("No {name} prices available: {e}", name = config.name),
+        }
+    }
+}
+
+/// Record today's quantities and prices in the [PriceHistoryStore] in the collection history directory
+///
+/// Until the collection snapshots have been migrated into the store, which is marked with the [PRICE_HISTORY_MIGRATED] file,
+/// they are migrated first, so a failed migration is retried the next time.
+/// If today is already stored, it's replaced with the newest values.
+///
+/// # Errors
+///
+/// Returns an error if the store cannot be opened, migrated or written to
+fn record_price_history(
+    appdata_dir: &Path,
+    cards: &[MtgoCard],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let history_dir = appdata_dir.join(COLLECTION_HISTORY_DIR);
+    std::fs::create_dir_all(&history_dir)?;
+    let store_path = history_dir.join(PRICE_HISTORY_STORE);
+    let migrated_marker = history_dir.join(PRICE_HISTORY_MIGRATED);
+
+    let mut store = if migrated_marker.exists() {
+        PriceHistoryStore::open(&store_path)?
+    } else {
+        let store = migrate_snapshots(&history_dir, &store_path)?;
+        std::fs::write(&migrated_marker, "")?;
+        store
+    };
+    if store.removed_bytes() > 0 {
+        log::warn!(
+            "Removed {} bytes of an incomplete record from the end of the price history {store_path:?}",
+            store.removed_bytes()
+        );
+    }
+
+    store.record_day(chrono::Utc::now().date_naive(), cards)?;
+    Ok(())
+}
+
+/// Build the price history from the snapshots in the [SNAPSHOT_ARCHIVE] and the collection history directory
+///
+/// The history is built in a separate file and replaces an existing store that was recorded before the migration,
+/// unless the store has days that are not in the snapshots. Then only the snapshots after its last day are added to it.
+///
+/// # Errors
+///
+/// Returns an error if a store cannot be opened or written to, or the snapshots cannot be read
+fn migrate_snapshots(
+    history_dir: &Path,
+    store_path: &Path,
+) -> Result<PriceHistoryStore, Box<dyn std::error::Error>> {
+    let archive_path = history_dir.join(SNAPSHOT_ARCHIVE);
+    let migrate = |store: &mut PriceHistoryStore| -> Result<usize, Box<dyn std::error::Error>> {
+        let mut migrated_days = 0;
+        if archive_path.exists() {
+            migrated_days += store.migrate_zip_archive(&archive_path)?;
+        }
+        migrated_days += store.migrate_snapshot_dir(history_dir)?;
+        Ok(migrated_days)
+    };
+
+    let rebuilt_path = store_path.with_extension("part");
+    if rebuilt_path.exists() {
+        std::fs::remove_file(&rebuilt_path)?;
+    }
+    let mut rebuilt = PriceHistoryStore::open(&rebuilt_path)?;
+    let migrated_days = migrate(&mut rebuilt)?;
+
+    if store_path.exists() {
+        let mut existing = PriceHistoryStore::open(store_path)?;
+        if !existing
+            .days()
+            .iter()
+            .all(|day| rebuilt.days().binary_search(day).is_ok())
+        {
+            std::fs::remove_file(&rebuilt_path)?;
+            let migrated_days = migrate(&mut existing)?;
+            log::info!("Migrated {migrated_days} days of price history into {store_path:?}");
+            return Ok(existing);
+        }
+    }
+    std::fs::rename(&rebuilt_path, store_path)?;
+    log::info!("Migrated {migrated_days} days of price history into {store_path:?}");
+    Ok(PriceHistoryStore::open(store_path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    fn card(id: u32, quantity: u32) -> MtgoCard {
+        MtgoCard {
+            id,
+            quantity,
+            goatbots_price: 1.,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_price_history_migrates_loose_snapshots() {
+        let tmp_dir = TempDir::new().unwrap();
+        let history_dir = tmp_dir.path().join(COLLECTION_HISTORY_DIR);
+        std::fs::create_dir_all(&history_dir).unwrap();
+        for name in [
+            "mtgo-cards_2023-11-01T083944Z.json",
+            "mtgo-cards_2023-11-02T083944Z.json",
+        ] {
+            std::fs::write(
+                history_dir.join(name),
+                serde_json::to_string(&[card(1, 1)]).unwrap(),
+            )
+            .unwrap();
+        }
+        // A store recorded before the migration, missing the older snapshot
+        PriceHistoryStore::open(history_dir.join(PRICE_HISTORY_STORE))
+            .unwrap()
+            .append_day(
+                chrono::NaiveDate::from_ymd_opt(2023, 11, 2).unwrap(),
+                &[card(1, 2)],
+            )
+            .unwrap();
+
+        record_price_history(tmp_dir.path(), &[card(1, 3)]).unwrap();
+        record_price_history(tmp_dir.path(), &[card(1, 4)]).unwrap();
+
+        assert!(history_dir.join(PRICE_HISTORY_MIGRATED).exists());
+        let store = PriceHistoryStore::open(history_dir.join(PRICE_HISTORY_STORE)).unwrap();
+        assert_eq!(store.days().len(), 3);
+        assert_eq!(
+            store.days()[0],
+            chrono::NaiveDate::from_ymd_opt(2023, 11, 1).unwrap()
+        );
+        assert_eq!(store.card_series(1).unwrap().last().unwrap().quantity, 4);
+    }
+}
@@ -1,5 +1,10 @@
+pub mod catalog;
+pub mod currency;
+pub mod diagnostics;
 pub mod metadata;
 pub mod paths;
+pub mod price_lists;
+pub mod retention;
 pub mod state;
 pub mod update;
 pub mod util;
@@ -12,3 +17,19 @@ pub const CURRENT_FULL_TRADE_LIST: &str = "current-full-trade-list.dek";
 pub const GUI_STATE: &str = "gui-state.toml";
 /// Name of the file that stores the state log for the MTGO getter
 pub const MTGO_GETTER_STATE_LOG: &str = "state_log.toml";
+/// Directory in the appdata directory where the MTGO Preprocessor saves collection snapshots
+pub const COLLECTION_HISTORY_DIR: &str = "collection-history";
+/// Name of the ZIP-file in the collection history directory that old collection snapshots are archived to
+pub const SNAPSHOT_ARCHIVE: &str = "snapshot-archive.zip";
+/// Name of the compact price history store in the collection history directory
+pub const PRICE_HISTORY_STORE: &str = "price-history.mcmh";
+/// Name of the file in the collection history directory that marks that the collection snapshots were migrated to the price history store
+pub const PRICE_HISTORY_MIGRATED: &str = "price-history.migrated";
+/// Name of the file that stores the history of exchange rates from tix to real money
+pub const EXCHANGE_RATES: &str = "exchange-rates.toml";
+/// Directory in the appdata directory where imported custom price lists are saved
+pub const CUSTOM_PRICE_LISTS_DIR: &str = "custom-price-lists";
+/// Directory in the appdata directory where the log files are written
+pub const LOG_FILES_DIR: &str = "log_files";
+/// Directory in the appdata directory where card images are cached
+pub const CARD_IMAGE_CACHE_DIR: &str = "card-images";
@@ -0,0 +1,315 @@
+//! Catalog of the card data files downloaded to the appdata directory.
+//!
+//! The catalog parses the timestamp out of each data file name (falling back to the last modified time),
+//! so the newest version of each data source is always selected, regardless of the order the files are listed in by the OS.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// A source of card data that is downloaded to the appdata directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CardDataSource {
+    /// Scryfall bulk data, e.g. `scryfall-bulk.json` or `default-cards-20231021091409.json`
+    Scryfall,
+    /// Goatbots card definitions, e.g. `card-definitions.json`
+    CardDefinitions,
+    /// Goatbots price history, e.g. `price-history.json`
+    PriceHistory,
+}
+
+impl CardDataSource {
+    /// All the card data sources
+    pub const ALL: [CardDataSource; 3] = [
+        CardDataSource::Scryfall,
+        CardDataSource::CardDefinitions,
+        CardDataSource::PriceHistory,
+    ];
+
+    /// Returns the name of the data source
+    pub fn name(&self) -> &'static str {
+        match self {
+            CardDataSource::Scryfall => "Scryfall data",
+            CardDataSource::CardDefinitions => "card definition",
+            CardDataSource::PriceHistory => "price history",
+        }
+    }
+
+    /// Returns true if the file name belongs to this data source
+    fn matches(&self, file_name: &str) -> bool {
+        if !file_name.ends_with(".json") {
+            return false;
+        }
+        match self {
+            // The Scryfall set list is also prefixed with `scryfall` but it is not bulk data
+            CardDataSource::Scryfall => {
+                (file_name.contains("scryfall") && !file_name.contains("scryfall-sets"))
+                    || file_name.starts_with("default-cards")
+            }
+            CardDataSource::CardDefinitions => file_name.contains("card-def"),
+            CardDataSource::PriceHistory => file_name.contains("price-his"),
+        }
+    }
+}
+
+/// The result of [DataFile::is_valid_json] with the modification time and size of the file when it was checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ValidJsonCheck {
+    modified: SystemTime,
+    len: u64,
+    valid: bool,
+}
+
+/// The [ValidJsonCheck]s by path
+///
+/// Parsing the Scryfall bulk data takes seconds, and an unchanged file doesn't have to be parsed again on every update.
+static VALID_JSON_CACHE: Lazy<Mutex<HashMap<PathBuf, ValidJsonCheck>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A version of a card data file with the time it was downloaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataFile {
+    path: PathBuf,
+    timestamp: DateTime<Utc>,
+}
+
+impl DataFile {
+    /// The path to the data file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The time the data file was downloaded, parsed from the file name or the last modified time of the file
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    /// Returns true if the file contains valid JSON
+    ///
+    /// The file is parsed in a streaming fashion without keeping the values, so it is cheap on memory even for the Scryfall bulk data.
+    /// The result is cached until the modification time or size of the file changes.
+    pub fn is_valid_json(&self) -> bool {
+        let stamp = self
+            .path
+            .metadata()
+            .and_then(|m| Ok((m.modified()?, m.len())))
+            .ok();
+        let mut cache = VALID_JSON_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let (Some((modified, len)), Some(check)) = (stamp, cache.get(&self.path)) {
+            if check.modified == modified && check.len == len {
+                return check.valid;
+            }
+        }
+        let valid = self.parse_json();
+        if let Some((modified, len)) = stamp {
+            cache.insert(
+                self.path.clone(),
+                ValidJsonCheck {
+                    modified,
+                    len,
+                    valid,
+                },
+            );
+        }
+        valid
+    }
+
+    fn parse_json(&self) -> bool {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) => {
+                log::warn!("Failed to open {path:?}: {e}", path = self.path);
+                return false;
+            }
+        };
+        match serde_json::from_reader::<_, serde::de::IgnoredAny>(BufReader::new(file)) {
+            Ok(_) => true,
+            Err(e) => {
+                log::warn!("Invalid JSON in {path:?}: {e}", path = self.path);
+                false
+            }
+        }
+    }
+}
+
+/// Catalog of all versions of the card data files in a directory, sorted from newest to oldest
+#[derive(Debug, Clone, Default)]
+pub struct DataFileCatalog {
+    files: HashMap<CardDataSource, Vec<DataFile>>,
+}
+
+impl DataFileCatalog {
+    /// Scan the given directory for card data files
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to scan, typically the appdata directory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or the metadata of a file in it cannot be read
+    pub fn scan(dir: &Path) -> io::Result<Self> {
+        let mut files: HashMap<CardDataSource, Vec<DataFile>> = HashMap::new();
+
+        for entry in dir.read_dir()? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let source = match CardDataSource::ALL.iter().find(|s| s.matches(&file_name)) {
+                Some(source) => *source,
+                None => continue,
+            };
+            let timestamp = match timestamp_from_file_name(&file_name) {
+                Some(timestamp) => timestamp,
+                None => metadata.modified()?.into(),
+            };
+            files.entry(source).or_default().push(DataFile {
+                path: entry.path(),
+                timestamp,
+            });
+        }
+
+        // Newest first, ties are broken by the path so the order never depends on the order of `read_dir`
+        for versions in files.values_mut() {
+            versions.sort_by(|a, b| {
+                b.timestamp
+                    .cmp(&a.timestamp)
+                    .then_with(|| b.path.cmp(&a.path))
+            });
+        }
+
+        Ok(Self { files })
+    }
+
+    /// All versions of the given data source, sorted from newest to oldest
+    pub fn versions(&self, source: CardDataSource) -> &[DataFile] {
+        self.files.get(&source).map_or(&[], |versions| versions)
+    }
+
+    /// The newest version of the given data source that contains valid JSON
+    ///
+    /// Invalid files, e.g. from an interrupted download, are skipped.
+    pub fn newest_valid(&self, source: CardDataSource) -> Option<&DataFile> {
+        self.versions(source).iter().find(|f| f.is_valid_json())
+    }
+}
+
+/// Parse a timestamp from a data file name
+///
+/// Supports the formats `YYYYMMDDHHMMSS` used by the Scryfall bulk data, `YYYY-MM-DDTHHMMSSZ` used by the MTGO Preprocessor, and `YYYY-MM-DD`.
+pub(crate) fn timestamp_from_file_name(file_name: &str) -> Option<DateTime<Utc>> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"(?<compact>\d{14})|(?<date>\d{4}-\d{2}-\d{2})(?<time>T\d{6}Z)?")
+            .expect("Failed to compile regex")
+    });
+
+    let caps = RE.captures(file_name)?;
+    let naive = if let Some(compact) = caps.name("compact") {
+        NaiveDateTime::parse_from_str(compact.as_str(), "%Y%m%d%H%M%S").ok()?
+    } else {
+        let date = NaiveDate::parse_from_str(caps.name("date")?.as_str(), "%Y-%m-%d").ok()?;
+        match caps.name("time") {
+            Some(time) => NaiveDateTime::parse_from_str(
+                &format!("{date}{}", time.as_str()),
+                "%Y-%m-%dT%H%M%SZ",
+            )
+            .ok()?,
+            None => date.and_hms_opt(0, 0, 0)?,
+        }
+    };
+    Some(naive.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    #[test]
+    fn test_timestamp_from_file_name() {
+        assert_eq!(
+            timestamp_from_file_name("default-cards-20231021091409.json"),
+            Some("2023-10-21T09:14:09Z".parse::<DateTime<Utc>>().unwrap())
+        );
+        assert_eq!(
+            timestamp_from_file_name("price-history_2023-10-14T152421Z.json"),
+            Some("2023-10-14T15:24:21Z".parse::<DateTime<Utc>>().unwrap())
+        );
+        assert_eq!(
+            timestamp_from_file_name("card-definitions_2023-10-14.json"),
+            Some("2023-10-14T00:00:00Z".parse::<DateTime<Utc>>().unwrap())
+        );
+        assert_eq!(timestamp_from_file_name("price-history.json"), None);
+    }
+
+    #[test]
+    fn test_catalog_picks_newest_valid() {
+        let tmp_dir = TempDir::new().unwrap();
+        let write = |name: &str, contents: &str| {
+            std::fs::write(tmp_dir.path().join(name), contents).unwrap();
+        };
+        write("default-cards-20231021091409.json", "[]");
+        write("default-cards-20231101091409.json", "[]");
+        // Newest but truncated download
+        write("default-cards-20231121091409.json", "[{\"mtgo_id\": 1");
+        write("scryfall-sets.json", "[]");
+        write("card-definitions.json", "{}");
+        write("not-card-data.json", "{}");
+
+        let catalog = DataFileCatalog::scan(tmp_dir.path()).unwrap();
+
+        let scryfall_versions: Vec<String> = catalog
+            .versions(CardDataSource::Scryfall)
+            .iter()
+            .map(|f| f.path().file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            scryfall_versions,
+            vec![
+                "default-cards-20231121091409.json",
+                "default-cards-20231101091409.json",
+                "default-cards-20231021091409.json",
+            ]
+        );
+        assert_eq!(
+            catalog
+                .newest_valid(CardDataSource::Scryfall)
+                .unwrap()
+                .path()
+                .file_name()
+                .unwrap(),
+            "default-cards-20231101091409.json"
+        );
+        assert_eq!(catalog.versions(CardDataSource::CardDefinitions).len(), 1);
+        assert!(catalog.newest_valid(CardDataSource::PriceHistory).is_none());
+    }
+
+    #[test]
+    fn test_is_valid_json_rechecks_changed_file() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("default-cards-20231121091409.json");
+        std::fs::write(&path, "[{\"mtgo_id\": 1").unwrap();
+        let catalog = DataFileCatalog::scan(tmp_dir.path()).unwrap();
+        let data_file = &catalog.versions(CardDataSource::Scryfall)[0];
+
+        assert!(!data_file.is_valid_json());
+        // Cached
+        assert!(!data_file.is_valid_json());
+        // The download is completed
+        std::fs::write(&path, "[{\"mtgo_id\": 1}]").unwrap();
+        assert!(data_file.is_valid_json());
+    }
+}
@@ -0,0 +1,235 @@
+//! Conversion of tix values to real money with user-defined exchange rates.
+//!
+//! The rates are saved with the time they took effect in [EXCHANGE_RATES], so historical values
+//! can be converted with the rate that was in effect at the time.
+
+use std::{io, path::Path};
+
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+
+use super::EXCHANGE_RATES;
+
+/// The currency values are displayed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Currency {
+    #[default]
+    Tix,
+    Usd,
+    Eur,
+}
+
+impl Currency {
+    /// All the currencies
+    pub const ALL: [Currency; 3] = [Currency::Tix, Currency::Usd, Currency::Eur];
+
+    /// The name of the currency, e.g. in menus
+    pub fn name(&self) -> &'static str {
+        match self {
+            Currency::Tix => "Tix",
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+        }
+    }
+
+    /// The code shown after a value in the currency, e.g. `12.34 USD`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Tix => "tix",
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+        }
+    }
+}
+
+/// An exchange rate from tix to a currency and the time it took effect
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRate {
+    pub effective_from: DateTime<Utc>,
+    /// The value of 1 tix in the currency
+    pub rate: f64,
+}
+
+/// The history of exchange rates from tix to each currency
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    #[serde(default)]
+    usd: Vec<ExchangeRate>,
+    #[serde(default)]
+    eur: Vec<ExchangeRate>,
+}
+
+impl ExchangeRates {
+    /// Load the exchange rates from the [EXCHANGE_RATES] file in the given directory, or no rates if there's no such file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let path = dir.join(EXCHANGE_RATES);
+        if !path.try_exists()? {
+            return Ok(Self::default());
+        }
+        let mut rates: Self = toml::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        rates.usd.sort_by_key(|r| r.effective_from);
+        rates.eur.sort_by_key(|r| r.effective_from);
+        Ok(rates)
+    }
+
+    /// Save the exchange rates to the [EXCHANGE_RATES] file in the given directory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        let toml = toml::to_string(&self).expect("Failed to serialize exchange rates");
+        std::fs::write(dir.join(EXCHANGE_RATES), toml)
+    }
+
+    /// The history of rates for the currency, oldest first, always empty for [Currency::Tix]
+    pub fn history(&self, currency: Currency) -> &[ExchangeRate] {
+        match currency {
+            Currency::Tix => &[],
+            Currency::Usd => &self.usd,
+            Currency::Eur => &self.eur,
+        }
+    }
+
+    /// Set a new rate for the currency, effective from the given time
+    ///
+    /// Rates for [Currency::Tix] are ignored, as 1 tix is always 1 tix.
+    pub fn set_rate(&mut self, currency: Currency, rate: f64, effective_from: DateTime<Utc>) {
+        let history = match currency {
+            Currency::Tix => return,
+            Currency::Usd => &mut self.usd,
+            Currency::Eur => &mut self.eur,
+        };
+        history.push(ExchangeRate {
+            effective_from,
+            rate,
+        });
+        history.sort_by_key(|r| r.effective_from);
+    }
+
+    /// The rate of the currency in effect at the given time
+    ///
+    /// Before the first rate took effect, the first rate is used. Returns [None] if no rate is set for the currency.
+    pub fn rate_at(&self, currency: Currency, at: DateTime<Utc>) -> Option<f64> {
+        if currency == Currency::Tix {
+            return Some(1.);
+        }
+        let history = self.history(currency);
+        history
+            .iter()
+            .rev()
+            .find(|r| r.effective_from <= at)
+            .or(history.first())
+            .map(|r| r.rate)
+    }
+
+    /// The [Money] to display values in the currency at the given time, falls back to tix if no rate is set
+    pub fn money_at(&self, currency: Currency, at: DateTime<Utc>) -> Money {
+        match self.rate_at(currency, at) {
+            Some(rate) => Money { currency, rate },
+            None => Money::TIX,
+        }
+    }
+}
+
+/// Converts and formats tix values in a [Currency] at a fixed rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Money {
+    currency: Currency,
+    rate: f64,
+}
+
+impl Default for Money {
+    fn default() -> Self {
+        Self::TIX
+    }
+}
+
+impl Money {
+    /// Values in tix, i.e. no conversion
+    pub const TIX: Money = Money {
+        currency: Currency::Tix,
+        rate: 1.,
+    };
+
+    /// The currency values are converted to
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Convert a value in tix to the currency
+    pub fn convert(&self, tix: f64) -> f64 {
+        tix * self.rate
+    }
+
+    /// Convert a value in tix to the currency and format it with the given number of decimals and the currency code
+    pub fn format(&self, tix: f64, decimals: usize) -> String {
+        format!(
+            "{value:.decimals$} {code}",
+            value = self.convert(tix),
+            code = self.currency.code()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_rate_at_uses_rate_in_effect() {
+        let mut rates = ExchangeRates::default();
+        rates.set_rate(Currency::Usd, 1.0, time("2023-10-01T00:00:00Z"));
+        rates.set_rate(Currency::Usd, 0.9, time("2023-11-01T00:00:00Z"));
+
+        assert_eq!(rates.rate_at(Currency::Tix, Utc::now()), Some(1.));
+        assert_eq!(rates.rate_at(Currency::Eur, Utc::now()), None);
+        // Before the first rate, the first rate is used
+        assert_eq!(
+            rates.rate_at(Currency::Usd, time("2023-09-01T00:00:00Z")),
+            Some(1.0)
+        );
+        assert_eq!(
+            rates.rate_at(Currency::Usd, time("2023-10-15T00:00:00Z")),
+            Some(1.0)
+        );
+        assert_eq!(
+            rates.rate_at(Currency::Usd, time("2023-11-15T00:00:00Z")),
+            Some(0.9)
+        );
+
+        assert_eq!(
+            rates
+                .money_at(Currency::Usd, time("2023-11-15T00:00:00Z"))
+                .format(10., 2),
+            "9.00 USD"
+        );
+        assert_eq!(rates.money_at(Currency::Eur, Utc::now()), Money::TIX);
+    }
+
+    #[test]
+    fn test_exchange_rates_load_save() {
+        let tmp_dir = TempDir::new().unwrap();
+        assert_eq!(
+            ExchangeRates::load(tmp_dir.path()).unwrap(),
+            ExchangeRates::default()
+        );
+
+        let mut rates = ExchangeRates::default();
+        rates.set_rate(Currency::Eur, 0.85, time("2023-10-01T00:00:00Z"));
+        rates.save(tmp_dir.path()).unwrap();
+
+        assert_eq!(ExchangeRates::load(tmp_dir.path()).unwrap(), rates);
+    }
+}
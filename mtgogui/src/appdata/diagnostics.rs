@@ -0,0 +1,308 @@
+//! Diagnostics bundle for bug reports.
+//!
+//! The bundle is a ZIP-file with the log files, the state files, the versions of all components,
+//! information about the OS and a listing of the appdata directory.
+//! The trade list is never included, only the number of cards in it.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use mtgoupdater::zip_util::Archive;
+
+use crate::collection::dek;
+
+use super::{
+    CARD_IMAGE_CACHE_DIR, CURRENT_FULL_TRADE_LIST, EXCHANGE_RATES, GUI_STATE, LOG_FILES_DIR,
+    MTGO_GETTER_STATE_LOG,
+};
+
+/// Name of the file with the versions and OS information in the bundle
+const SYSTEM_INFO_FILE: &str = "system-info.txt";
+/// Name of the file with the listing of the appdata directory in the bundle
+const APPDATA_LISTING_FILE: &str = "appdata-listing.txt";
+/// Name of the file with the summary of the trade list in the bundle
+const TRADE_LIST_SUMMARY_FILE: &str = "trade-list-summary.txt";
+
+/// The state files in the appdata directory that are included in the bundle
+const STATE_FILES: [&str; 3] = [GUI_STATE, MTGO_GETTER_STATE_LOG, EXCHANGE_RATES];
+
+/// Versions of the components and information about the system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemInfo {
+    pub app_version: String,
+    pub mtgogetter_version: String,
+    pub mtgo_preprocessor_version: String,
+    pub mtgoupdater_version: String,
+}
+
+impl SystemInfo {
+    /// The versions and information about the OS as text
+    pub fn to_text(&self, now: DateTime<Utc>) -> String {
+        format!(
+            "Created: {now}\n\
+            MTGO Collection Manager: {app}\n\
+            MTGO Getter: {getter}\n\
+            MTGO Preprocessor: {preproc}\n\
+            MTGO Updater: {updater}\n\
+            OS: {os} ({family})\n\
+            Architecture: {arch}\n",
+            now = now.to_rfc3339(),
+            app = self.app_version,
+            getter = self.mtgogetter_version,
+            preproc = self.mtgo_preprocessor_version,
+            updater = self.mtgoupdater_version,
+            os = std::env::consts::OS,
+            family = std::env::consts::FAMILY,
+            arch = std::env::consts::ARCH,
+        )
+    }
+}
+
+/// Create the diagnostics bundle
+///
+/// # Arguments
+///
+/// * `appdata_dir` - The appdata directory to collect the files from
+/// * `info` - The versions of the components
+/// * `dest` - Path of the ZIP-file to create
+///
+/// # Returns
+///
+/// The names of the files in the bundle
+///
+/// # Errors
+///
+/// Returns an error if the appdata directory cannot be read or the bundle cannot be written
+pub fn export_diagnostics(
+    appdata_dir: &Path,
+    info: &SystemInfo,
+    dest: &Path,
+) -> io::Result<Vec<String>> {
+    // The generated files are written to a new staging directory in the temp directory, and removed after archiving.
+    // Creating it fails if it already exists, so only a directory created here is removed
+    let staging_dir = std::env::temp_dir().join(format!(
+        "mcm-diagnostics-{}-{}",
+        std::process::id(),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    fs::create_dir(&staging_dir)?;
+    let result = build_bundle(appdata_dir, info, dest, &staging_dir);
+    if let Err(e) = fs::remove_dir_all(&staging_dir) {
+        log::warn!("Failed to remove {staging_dir:?}: {e}");
+    }
+    result
+}
+
+fn build_bundle(
+    appdata_dir: &Path,
+    info: &SystemInfo,
+    dest: &Path,
+    staging_dir: &Path,
+) -> io::Result<Vec<String>> {
+    let now = Utc::now();
+    let mut files: Vec<PathBuf> = Vec::new();
+
+    let system_info = staging_dir.join(SYSTEM_INFO_FILE);
+    fs::write(&system_info, info.to_text(now))?;
+    files.push(system_info);
+
+    let listing = staging_dir.join(APPDATA_LISTING_FILE);
+    fs::write(&listing, appdata_listing(appdata_dir)?)?;
+    files.push(listing);
+
+    let summary = staging_dir.join(TRADE_LIST_SUMMARY_FILE);
+    fs::write(&summary, trade_list_summary(appdata_dir))?;
+    files.push(summary);
+
+    for state_file in STATE_FILES {
+        let path = appdata_dir.join(state_file);
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+
+    let log_dir = appdata_dir.join(LOG_FILES_DIR);
+    if log_dir.is_dir() {
+        let mut logs: Vec<PathBuf> = log_dir
+            .read_dir()?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "log"))
+            .collect();
+        logs.sort();
+        files.extend(logs);
+    }
+
+    let names = files
+        .iter()
+        .filter_map(|f| f.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect();
+    let mut archive = Archive::new(dest);
+    for file in files {
+        archive.add_file(file);
+    }
+    archive.archive()?;
+    Ok(names)
+}
+
+/// The number of cards in the current trade list, its contents are left out as they're personal
+fn trade_list_summary(appdata_dir: &Path) -> String {
+    let path = appdata_dir.join(CURRENT_FULL_TRADE_LIST);
+    if !path.is_file() {
+        return "No trade list\n".to_string();
+    }
+    match dek::validate_dek_file(&path) {
+        Ok(summary) => format!(
+            "Trade list: {entries} entries, {quantity} cards\n",
+            entries = summary.entries,
+            quantity = summary.quantity
+        ),
+        Err(e) => format!("Invalid trade list: {e}\n"),
+    }
+}
+
+/// List the files in the appdata directory with their size, one file per line
+///
+/// The card image cache is only summarized, as it can contain thousands of images.
+fn appdata_listing(appdata_dir: &Path) -> io::Result<String> {
+    let mut listing = String::new();
+    list_dir(appdata_dir, appdata_dir, &mut listing)?;
+    Ok(listing)
+}
+
+fn list_dir(root: &Path, dir: &Path, listing: &mut String) -> io::Result<()> {
+    let mut entries: Vec<PathBuf> = dir
+        .read_dir()?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+    for path in entries {
+        let relative = path.strip_prefix(root).unwrap_or(&path).display();
+        if path.is_dir() {
+            if path
+                .file_name()
+                .is_some_and(|name| name == CARD_IMAGE_CACHE_DIR)
+            {
+                let (count, size) = dir_size(&path)?;
+                listing.push_str(&format!("{relative}/ ({count} files, {size} bytes)\n"));
+            } else {
+                listing.push_str(&format!("{relative}/\n"));
+                list_dir(root, &path, listing)?;
+            }
+        } else {
+            listing.push_str(&format!(
+                "{relative} ({size} bytes)\n",
+                size = path.metadata()?.len()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The number of files in the directory and their total size
+fn dir_size(dir: &Path) -> io::Result<(usize, u64)> {
+    let mut count = 0;
+    let mut size = 0;
+    for entry in dir.read_dir()? {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            count += 1;
+            size += metadata.len();
+        }
+    }
+    Ok((count, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    fn system_info() -> SystemInfo {
+        SystemInfo {
+            app_version: "0.1.0".to_string(),
+            mtgogetter_version: "0.2.0".to_string(),
+            mtgo_preprocessor_version: "0.3.0".to_string(),
+            mtgoupdater_version: "0.1.0".to_string(),
+        }
+    }
+
+    fn appdata_with_files() -> TempDir {
+        let appdata = TempDir::new().unwrap();
+        fs::write(appdata.child(GUI_STATE), "offline_mode = false\n").unwrap();
+        fs::write(appdata.child(MTGO_GETTER_STATE_LOG), "[goatbots]\n").unwrap();
+        fs::copy(
+            "../test/test-data/mtgo/Full Trade List-small-5cards.dek",
+            appdata.child(CURRENT_FULL_TRADE_LIST),
+        )
+        .unwrap();
+        fs::create_dir(appdata.child(LOG_FILES_DIR)).unwrap();
+        fs::write(
+            appdata.child(LOG_FILES_DIR).join("mcm_log_rCURRENT.log"),
+            "INFO [mtgogui] Starting GUI\n",
+        )
+        .unwrap();
+        fs::create_dir(appdata.child(CARD_IMAGE_CACHE_DIR)).unwrap();
+        for id in ["a", "b"] {
+            fs::write(
+                appdata
+                    .child(CARD_IMAGE_CACHE_DIR)
+                    .join(format!("{id}.jpg")),
+                [0u8; 10],
+            )
+            .unwrap();
+        }
+        appdata
+    }
+
+    #[test]
+    fn test_export_diagnostics() {
+        let appdata = appdata_with_files();
+        let out_dir = TempDir::new().unwrap();
+        let dest = out_dir.child("diagnostics.zip");
+        // A directory of the user's next to the bundle is left alone
+        let user_dir = out_dir.child("diagnostics.staging");
+        fs::create_dir(&user_dir).unwrap();
+        fs::write(user_dir.join("notes.txt"), "keep").unwrap();
+
+        let names = export_diagnostics(appdata.path(), &system_info(), &dest).unwrap();
+
+        assert_eq!(
+            names,
+            [
+                SYSTEM_INFO_FILE,
+                APPDATA_LISTING_FILE,
+                TRADE_LIST_SUMMARY_FILE,
+                GUI_STATE,
+                MTGO_GETTER_STATE_LOG,
+                "mcm_log_rCURRENT.log"
+            ]
+        );
+        assert!(dest.is_file());
+        assert_eq!(out_dir.path().read_dir().unwrap().count(), 2);
+        assert_eq!(
+            fs::read_to_string(user_dir.join("notes.txt")).unwrap(),
+            "keep"
+        );
+    }
+
+    #[test]
+    fn test_listing_and_trade_list_summary() {
+        let appdata = appdata_with_files();
+
+        let listing = appdata_listing(appdata.path()).unwrap();
+        let summary = trade_list_summary(appdata.path());
+
+        assert!(listing.contains("card-images/ (2 files, 20 bytes)\n"));
+        assert!(listing.contains("log_files/\n"));
+        assert!(!listing.contains("a.jpg"));
+        assert_eq!(summary, "Trade list: 5 entries, 457 cards\n");
+        assert!(system_info()
+            .to_text(Utc::now())
+            .contains("MTGO Preprocessor: 0.3.0\n"));
+    }
+}
@@ -81,6 +81,27 @@ impl MetaData {
     }
 }
 
+/// Describe how stale data updated at `updated_at` is at the time `now`, e.g. `3 days ago`
+///
+/// Returns `Never` if `updated_at` is the UNIX epoch, which is what the MTGO Getter state log contains before the first download.
+pub fn format_age(updated_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    if updated_at == DateTime::<Utc>::UNIX_EPOCH {
+        return "Never".to_string();
+    }
+    let age = now.signed_duration_since(updated_at);
+    let (amount, unit) = if age.num_days() > 0 {
+        (age.num_days(), "day")
+    } else if age.num_hours() > 0 {
+        (age.num_hours(), "hour")
+    } else if age.num_minutes() > 0 {
+        (age.num_minutes(), "minute")
+    } else {
+        return "Just now".to_string();
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{amount} {unit}{plural} ago")
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 struct GoatBots {
     card_definitions_updated_at: DateTime<Utc>,
@@ -347,6 +368,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_age() {
+        let now = "2023-10-21T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        assert_eq!(format_age(DateTime::<Utc>::UNIX_EPOCH, now), "Never");
+        assert_eq!(format_age(now, now), "Just now");
+        assert_eq!(
+            format_age(now - chrono::Duration::minutes(1), now),
+            "1 minute ago"
+        );
+        assert_eq!(
+            format_age(now - chrono::Duration::hours(5), now),
+            "5 hours ago"
+        );
+        assert_eq!(
+            format_age(
+                "2023-10-14T15:24:21Z".parse::<DateTime<Utc>>().unwrap(),
+                now
+            ),
+            "6 days ago"
+        );
+    }
+
     #[test]
     fn test_metadata_load_ok() {
         let src_dir = PathBuf::from("../test/test-data/mtgogetter-out");
@@ -3,7 +3,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::util::first_file_match_from_dir;
+use super::catalog::{CardDataSource, DataFileCatalog};
 
 /// [CardDataPaths] contains the paths to the card data files
 ///
@@ -19,12 +19,7 @@ pub struct CardDataPaths {
 }
 
 impl CardDataPaths {
-    // Strings used to locate the card data files in the appdata directory
-    const FIND_SCRYFALL: &'static str = "scryfall";
-    const FIND_CARD_DEFINITIONS: &'static str = "card-def";
-    const FIND_PRICE_HISTORY: &'static str = "price-his";
-
-    /// Find the paths to the card data files in the appdata directory
+    /// Find the paths to the newest valid card data files in the appdata directory
     ///
     /// # Arguments
     ///
@@ -38,50 +33,64 @@ impl CardDataPaths {
     ///
     /// If any of the card data files are not found, an error is returned describing which files were not found
     pub fn find(appdata_dir: &Path) -> Result<Self, Error> {
-        // Try to find the card data files in the appdata directory
+        let catalog = DataFileCatalog::scan(appdata_dir)?;
+        Self::from_catalog(&catalog, appdata_dir)
+    }
+
+    /// Select the newest valid card data files from a [DataFileCatalog]
+    ///
+    /// # Arguments
+    ///
+    /// * `catalog` - The catalog of the card data files in the appdata directory
+    /// * `appdata_dir` - The path to the appdata directory, used in the error description
+    ///
+    /// # Errors
+    ///
+    /// If there's no valid file for any of the card data sources, an error is returned describing which files were not found
+    pub fn from_catalog(catalog: &DataFileCatalog, appdata_dir: &Path) -> Result<Self, Error> {
         // If any of the files are not found, return an error
         let mut find_errs = Vec::new();
+        let mut newest_valid = |source: CardDataSource| -> Option<PathBuf> {
+            match catalog.newest_valid(source) {
+                Some(data_file) => {
+                    log::info!(
+                        "Using {name} JSON {path:?} from {timestamp}",
+                        name = source.name(),
+                        path = data_file.path(),
+                        timestamp = data_file.timestamp()
+                    );
+                    Some(data_file.path().to_path_buf())
+                }
+                None => {
+                    log::info!(
+                        "Could not locate valid {name} json in {appdata_dir:?}",
+                        name = source.name()
+                    );
+                    find_errs.push(format!(
+                        "Could not find {name} JSON in {appdata_dir:?}",
+                        name = source.name()
+                    ));
+                    None
+                }
+            }
+        };
 
-        let scryfall_path: Option<PathBuf> =
-            first_file_match_from_dir(Self::FIND_SCRYFALL, appdata_dir, None)?;
-        if scryfall_path.is_none() {
-            log::info!("Could not locate Scryfall data json in {appdata_dir:?}");
-            find_errs.push(format!(
-                "Could not find Scryfall data JSON in {appdata_dir:?}"
-            ));
-        }
-
-        let card_definitions_path: Option<PathBuf> =
-            first_file_match_from_dir(Self::FIND_CARD_DEFINITIONS, appdata_dir, None)?;
-        if card_definitions_path.is_none() {
-            log::info!("Could not locate card definition json in {appdata_dir:?}");
-            find_errs.push(format!(
-                "Could not find card definition JSON in {appdata_dir:?}"
-            ));
-        }
-
-        let price_history_path: Option<PathBuf> =
-            first_file_match_from_dir(Self::FIND_PRICE_HISTORY, appdata_dir, None)?;
-        if price_history_path.is_none() {
-            log::info!("Could not locate price history json in {appdata_dir:?}");
-            find_errs.push(format!(
-                "Could not find price history JSON in {appdata_dir:?}"
-            ));
-        }
+        let scryfall_path = newest_valid(CardDataSource::Scryfall);
+        let card_definitions_path = newest_valid(CardDataSource::CardDefinitions);
+        let price_history_path = newest_valid(CardDataSource::PriceHistory);
 
         // If any of the files were not found, return an error describing which files were not found
-        if !find_errs.is_empty() {
-            return Err(Error::new(
+        match (scryfall_path, card_definitions_path, price_history_path) {
+            (Some(scryfall), Some(card_definitions), Some(price_history)) => Ok(CardDataPaths {
+                scryfall,
+                card_definitions,
+                price_history,
+            }),
+            _ => Err(Error::new(
                 std::io::ErrorKind::NotFound,
                 find_errs.join("\n"),
-            ));
+            )),
         }
-
-        Ok(CardDataPaths {
-            scryfall: scryfall_path.unwrap(),
-            card_definitions: card_definitions_path.unwrap(),
-            price_history: price_history_path.unwrap(),
-        })
     }
 
     /// Get the path to the scryfall data JSON-file
@@ -0,0 +1,120 @@
+//! Custom price lists imported into the appdata directory.
+//!
+//! Imported lists are normalized to JSON and saved in the [CUSTOM_PRICE_LISTS_DIR],
+//! and every processed collection gets the buy and sell prices of all the saved lists.
+
+use std::{
+    error,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use mtgoupdater::{mtgo_card::MtgoCard, price_list::CustomPriceList};
+
+use super::CUSTOM_PRICE_LISTS_DIR;
+
+/// Import a CSV or JSON price list into the appdata directory, replacing any list with the same name
+///
+/// # Arguments
+///
+/// * `src` - Path to the `.csv` or `.json` price list, the file name is used as the name of the list
+/// * `appdata_dir` - The path to the appdata directory
+///
+/// # Errors
+///
+/// Returns an error if the price list is invalid or cannot be saved
+pub fn import_price_list(
+    src: &Path,
+    appdata_dir: &Path,
+) -> Result<CustomPriceList, Box<dyn error::Error>> {
+    let list = CustomPriceList::from_file(src)?;
+    let dir = appdata_dir.join(CUSTOM_PRICE_LISTS_DIR);
+    std::fs::create_dir_all(&dir)?;
+    list.save(dir.join(format!("{}.json", list.name())))?;
+    log::info!(
+        "Imported price list {name} with {count} entries from {src:?}",
+        name = list.name(),
+        count = list.entries().len()
+    );
+    Ok(list)
+}
+
+/// Add the buy and sell prices from all the price lists in the appdata directory to the cards
+///
+/// A list that cannot be loaded is logged and skipped.
+///
+/// # Returns
+///
+/// The names of the price lists that were applied
+pub fn apply_price_lists(appdata_dir: &Path, cards: &mut [MtgoCard]) -> Vec<Box<str>> {
+    let mut applied = Vec::new();
+    for path in price_list_files(&appdata_dir.join(CUSTOM_PRICE_LISTS_DIR)) {
+        let list = match CustomPriceList::load(&path) {
+            Ok(list) => list,
+            Err(e) => {
+                log::warn!("Failed to load price list {path:?}: {e}");
+                continue;
+            }
+        };
+        let updated_at: DateTime<Utc> = match path.metadata().and_then(|m| m.modified()) {
+            Ok(modified) => modified.into(),
+            Err(_) => Utc::now(),
+        };
+        let (buy, sell) = list.resolve(cards, updated_at);
+        buy.apply(cards);
+        sell.apply(cards);
+        log::info!(
+            "Applied price list {name}: {buy_count} buy and {sell_count} sell prices",
+            name = list.name(),
+            buy_count = buy.len(),
+            sell_count = sell.len()
+        );
+        applied.push(list.name().into());
+    }
+    applied
+}
+
+/// The saved price lists in the directory, sorted by name
+fn price_list_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match dir.read_dir() {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    #[test]
+    fn test_import_and_apply_price_lists() {
+        let tmp_dir = TempDir::new().unwrap();
+        let src = tmp_dir.child("Our Bot.csv");
+        std::fs::write(&src, "id,buy,sell\n235,0.001,0.003\n").unwrap();
+
+        let list = import_price_list(&src, tmp_dir.path()).unwrap();
+        assert_eq!(list.name(), "Our Bot");
+        assert!(tmp_dir
+            .path()
+            .join(CUSTOM_PRICE_LISTS_DIR)
+            .join("Our Bot.json")
+            .exists());
+
+        let mut cards = vec![MtgoCard {
+            id: 235,
+            ..Default::default()
+        }];
+        let applied = apply_price_lists(tmp_dir.path(), &mut cards);
+
+        assert_eq!(applied, vec!["Our Bot".into()]);
+        assert_eq!(cards[0].price("Our Bot buy"), Some(0.001));
+        assert_eq!(cards[0].price("Our Bot sell"), Some(0.003));
+    }
+}
@@ -0,0 +1,308 @@
+//! Retention policy for the appdata directory.
+//!
+//! Every update downloads new card data, and every processed trade list leaves a collection snapshot behind.
+//! The [RetentionPolicy] deletes old card data files and moves old snapshots into the [SNAPSHOT_ARCHIVE].
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use mtgoupdater::zip_util::{Archive, Archived, UnArchived};
+
+use super::{
+    catalog::{self, CardDataSource, DataFileCatalog},
+    COLLECTION_HISTORY_DIR, SNAPSHOT_ARCHIVE,
+};
+
+/// Prefix of the collection snapshots saved by the MTGO Preprocessor in the [COLLECTION_HISTORY_DIR], e.g. `mtgo-cards_2023-11-06T083944Z.json`
+const SNAPSHOT_PREFIX: &str = "mtgo-cards_";
+
+/// A rule for which files to remove from the appdata directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetentionRule {
+    /// Keep the newest `keep` valid versions of the card data source and delete the versions older than them
+    ///
+    /// The newest valid version is always kept, even if `keep` is 0. Versions that are newer but invalid,
+    /// e.g. from an interrupted download, don't count towards `keep` and are left to the next update to replace.
+    KeepNewest { source: CardDataSource, keep: usize },
+    /// Move collection snapshots older than the given number of days into the snapshot archive
+    ArchiveSnapshotsOlderThan { days: u32 },
+}
+
+/// A set of [RetentionRule]s to apply to the appdata directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    rules: Vec<RetentionRule>,
+}
+
+impl Default for RetentionPolicy {
+    /// Keep the two newest versions of each card data source and archive snapshots older than 30 days
+    fn default() -> Self {
+        let mut rules: Vec<RetentionRule> = CardDataSource::ALL
+            .iter()
+            .map(|source| RetentionRule::KeepNewest {
+                source: *source,
+                keep: 2,
+            })
+            .collect();
+        rules.push(RetentionRule::ArchiveSnapshotsOlderThan { days: 30 });
+        Self { rules }
+    }
+}
+
+impl RetentionPolicy {
+    /// Create a new [RetentionPolicy] from the given rules
+    pub fn new(rules: Vec<RetentionRule>) -> Self {
+        Self { rules }
+    }
+
+    /// The rules of the policy
+    pub fn rules(&self) -> &[RetentionRule] {
+        &self.rules
+    }
+
+    /// Apply the policy to the given appdata directory
+    ///
+    /// # Arguments
+    ///
+    /// * `appdata_dir` - The path to the appdata directory
+    /// * `now` - The current time, snapshot ages are calculated relative to it
+    ///
+    /// # Returns
+    ///
+    /// A [RetentionReport] with the files that were deleted and archived, and how much space was freed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read, or a file cannot be deleted or archived
+    pub fn apply(&self, appdata_dir: &Path, now: DateTime<Utc>) -> io::Result<RetentionReport> {
+        let mut report = RetentionReport::default();
+        let catalog = DataFileCatalog::scan(appdata_dir)?;
+
+        for rule in &self.rules {
+            match *rule {
+                RetentionRule::KeepNewest { source, keep } => {
+                    let versions = catalog.versions(source);
+                    let mut valid = 0;
+                    let oldest_kept = versions.iter().position(|version| {
+                        if version.is_valid_json() {
+                            valid += 1;
+                        }
+                        valid == keep.max(1)
+                    });
+                    let old_versions = oldest_kept.map_or(&[][..], |idx| &versions[idx + 1..]);
+                    for old_version in old_versions {
+                        let size = old_version.path().metadata()?.len();
+                        std::fs::remove_file(old_version.path())?;
+                        log::info!(
+                            "Deleted old {} file {:?}",
+                            source.name(),
+                            old_version.path()
+                        );
+                        report.freed_bytes += size;
+                        report.deleted_files.push(old_version.path().to_path_buf());
+                    }
+                }
+                RetentionRule::ArchiveSnapshotsOlderThan { days } => {
+                    let history_dir = appdata_dir.join(COLLECTION_HISTORY_DIR);
+                    if !history_dir.is_dir() {
+                        continue;
+                    }
+                    let snapshots = old_snapshots(&history_dir, now, days)?;
+                    if snapshots.is_empty() {
+                        continue;
+                    }
+                    let archive_path = history_dir.join(SNAPSHOT_ARCHIVE);
+                    let snapshots_size: u64 = snapshots
+                        .iter()
+                        .map(|s| s.metadata().map(|m| m.len()).unwrap_or_default())
+                        .sum();
+                    let archive_size_before = archive_path.metadata().map_or(0, |m| m.len());
+
+                    if archive_path.exists() {
+                        let mut archive = Archive::<Archived>::init(&archive_path);
+                        archive.move_to_archive(snapshots.iter().map(|p| p.as_path()))?;
+                    } else {
+                        let mut archive = Archive::<UnArchived>::new(&archive_path);
+                        snapshots
+                            .iter()
+                            .for_each(|s| archive.move_file(s.to_path_buf()));
+                        archive.archive()?;
+                    }
+                    log::info!(
+                        "Archived {count} snapshots to {archive_path:?}",
+                        count = snapshots.len()
+                    );
+
+                    let archive_growth = archive_path
+                        .metadata()?
+                        .len()
+                        .saturating_sub(archive_size_before);
+                    report.freed_bytes += snapshots_size.saturating_sub(archive_growth);
+                    report.archived_files.extend(snapshots);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Find the collection snapshots in the directory that are older than the given number of days
+fn old_snapshots(dir: &Path, now: DateTime<Utc>, days: u32) -> io::Result<Vec<PathBuf>> {
+    let mut snapshots = Vec::new();
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let file_name = match path.file_name().and_then(|f| f.to_str()) {
+            Some(file_name) if file_name.starts_with(SNAPSHOT_PREFIX) => file_name,
+            _ => continue,
+        };
+        if let Some(timestamp) = catalog::timestamp_from_file_name(file_name) {
+            if now.signed_duration_since(timestamp).num_days() > days as i64 {
+                snapshots.push(path);
+            }
+        }
+    }
+    snapshots.sort();
+    Ok(snapshots)
+}
+
+/// Summary of what a [RetentionPolicy] removed from the appdata directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionReport {
+    deleted_files: Vec<PathBuf>,
+    archived_files: Vec<PathBuf>,
+    freed_bytes: u64,
+}
+
+impl RetentionReport {
+    /// The files that were deleted
+    pub fn deleted_files(&self) -> &[PathBuf] {
+        &self.deleted_files
+    }
+
+    /// The files that were moved into the snapshot archive
+    pub fn archived_files(&self) -> &[PathBuf] {
+        &self.archived_files
+    }
+
+    /// The number of bytes freed on disk
+    pub fn freed_bytes(&self) -> u64 {
+        self.freed_bytes
+    }
+
+    /// A short human readable summary of the report
+    pub fn summary(&self) -> String {
+        format!(
+            "Freed {freed:.1} MiB: deleted {deleted} old data files and archived {archived} snapshots",
+            freed = self.freed_bytes as f64 / (1024. * 1024.),
+            deleted = self.deleted_files.len(),
+            archived = self.archived_files.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    #[test]
+    fn test_retention_policy_apply() {
+        let tmp_dir = TempDir::new().unwrap();
+        let write = |name: &str, contents: &str| {
+            std::fs::write(tmp_dir.path().join(name), contents).unwrap();
+        };
+        write("default-cards-20231001091409.json", "[]");
+        write("default-cards-20231011091409.json", "[]");
+        write("default-cards-20231021091409.json", "[]");
+        write("card-definitions.json", "{}");
+        let history_dir = tmp_dir.path().join(COLLECTION_HISTORY_DIR);
+        std::fs::create_dir(&history_dir).unwrap();
+        std::fs::write(
+            history_dir.join("mtgo-cards_2023-09-01T083944Z.json"),
+            "[]".repeat(1000),
+        )
+        .unwrap();
+        std::fs::write(history_dir.join("mtgo-cards_2023-10-20T083944Z.json"), "[]").unwrap();
+
+        let now = "2023-10-21T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let policy = RetentionPolicy::new(vec![
+            RetentionRule::KeepNewest {
+                source: CardDataSource::Scryfall,
+                keep: 2,
+            },
+            RetentionRule::KeepNewest {
+                source: CardDataSource::CardDefinitions,
+                keep: 0,
+            },
+            RetentionRule::ArchiveSnapshotsOlderThan { days: 30 },
+        ]);
+
+        let report = policy.apply(tmp_dir.path(), now).unwrap();
+
+        assert_eq!(
+            report.deleted_files(),
+            &[tmp_dir.path().join("default-cards-20231001091409.json")]
+        );
+        assert_eq!(
+            report.archived_files(),
+            &[history_dir.join("mtgo-cards_2023-09-01T083944Z.json")]
+        );
+        assert!(report.freed_bytes() > 0);
+        // The newest version is always kept
+        assert!(tmp_dir.path().join("card-definitions.json").exists());
+        assert!(history_dir
+            .join("mtgo-cards_2023-10-20T083944Z.json")
+            .exists());
+        assert!(!history_dir
+            .join("mtgo-cards_2023-09-01T083944Z.json")
+            .exists());
+        assert!(history_dir.join(SNAPSHOT_ARCHIVE).exists());
+    }
+
+    #[test]
+    fn test_keep_newest_skips_invalid_versions() {
+        let tmp_dir = TempDir::new().unwrap();
+        let write = |name: &str, contents: &str| {
+            std::fs::write(tmp_dir.path().join(name), contents).unwrap();
+        };
+        write("default-cards-20231001091409.json", "[]");
+        write("default-cards-20231011091409.json", "[]");
+        // Newer but truncated downloads
+        write("default-cards-20231021091409.json", "[{\"mtgo_id\": 1");
+        write("default-cards-20231031091409.json", "[{");
+        write("price-history_2023-10-14T152421Z.json", "{");
+
+        let policy = RetentionPolicy::new(vec![
+            RetentionRule::KeepNewest {
+                source: CardDataSource::Scryfall,
+                keep: 1,
+            },
+            RetentionRule::KeepNewest {
+                source: CardDataSource::PriceHistory,
+                keep: 1,
+            },
+        ]);
+        let report = policy.apply(tmp_dir.path(), Utc::now()).unwrap();
+
+        assert_eq!(
+            report.deleted_files(),
+            &[tmp_dir.path().join("default-cards-20231001091409.json")]
+        );
+        assert!(tmp_dir
+            .path()
+            .join("default-cards-20231011091409.json")
+            .exists());
+        assert!(tmp_dir
+            .path()
+            .join("price-history_2023-10-14T152421Z.json")
+            .exists());
+    }
+}
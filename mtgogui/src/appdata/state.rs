@@ -1,17 +1,62 @@
-use std::{ffi::OsString, io, path::PathBuf};
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+};
 
 use chrono::prelude::*;
+use mtgoupdater::image_cache::{ImageCache, ImageFetcher, LocalDirFetcher, ScryfallImageFetcher};
 use serde_derive::{Deserialize, Serialize};
 use toml::Table;
 
-use super::GUI_STATE;
+use super::{currency::Currency, CARD_IMAGE_CACHE_DIR, GUI_STATE};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
 pub struct GuiState {
     tradelist_added_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    offline_mode: bool,
+    #[serde(default)]
+    display_currency: Currency,
+    /// The first-run onboarding wizard was completed or skipped
+    #[serde(default)]
+    onboarding_completed: bool,
+    /// Process the current trade list once a day while the app is running, recording a daily collection snapshot
+    #[serde(default)]
+    daily_snapshots: bool,
+    /// Size limit of the card image cache in MiB, [GuiState::DEFAULT_CARD_IMAGE_CACHE_MB] if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    card_image_cache_mb: Option<u64>,
+    /// A local directory or mirror to copy card images from instead of downloading them from Scryfall,
+    /// the images are named by Scryfall ID, e.g. `<scryfall id>.jpg`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    card_image_mirror: Option<PathBuf>,
+    /// A folder that is watched for new Full Trade List exports, e.g. MTGO's export location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    watch_folder: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    price_sources: Vec<PriceSourceConfig>,
+}
+
+/// An additional CSV price list to fetch prices from, configured in the [GUI_STATE] file, e.g.
+///
+/// ```toml
+/// [[price_sources]]
+/// name = "Manatraders"
+/// url = "https://example.com/prices.csv"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PriceSourceConfig {
+    /// The name of the source, shown as column title
+    pub name: Box<str>,
+    /// The URL of the CSV price list
+    pub url: Box<str>,
 }
 
 impl GuiState {
+    /// Default size limit of the card image cache in MiB
+    pub const DEFAULT_CARD_IMAGE_CACHE_MB: u64 = 256;
+
     /// Create a new [GuiState] instance
     pub fn new() -> Self {
         Self::default()
@@ -72,6 +117,93 @@ impl GuiState {
     pub fn get_tradelist_added_date(&self) -> Option<&DateTime<Utc>> {
         self.tradelist_added_date.as_ref()
     }
+
+    /// Returns true if the user enabled offline mode in the settings
+    pub fn offline_mode(&self) -> bool {
+        self.offline_mode
+    }
+
+    /// Enable or disable offline mode in the settings
+    pub fn set_offline_mode(&mut self, offline_mode: bool) {
+        self.offline_mode = offline_mode;
+    }
+
+    /// The currency values are displayed in
+    pub fn display_currency(&self) -> Currency {
+        self.display_currency
+    }
+
+    /// Set the currency values are displayed in
+    pub fn set_display_currency(&mut self, currency: Currency) {
+        self.display_currency = currency;
+    }
+
+    /// Returns true if the onboarding wizard should be shown, i.e. on the first launch
+    ///
+    /// Users that added a trade list before the wizard existed are not onboarded.
+    pub fn needs_onboarding(&self) -> bool {
+        !self.onboarding_completed && self.tradelist_added_date.is_none()
+    }
+
+    /// Mark the onboarding wizard as completed, so it's not shown again on startup
+    pub fn complete_onboarding(&mut self) {
+        self.onboarding_completed = true;
+    }
+
+    /// Returns true if a collection snapshot is recorded every day
+    pub fn daily_snapshots(&self) -> bool {
+        self.daily_snapshots
+    }
+
+    /// Enable or disable daily collection snapshots
+    pub fn set_daily_snapshots(&mut self, daily_snapshots: bool) {
+        self.daily_snapshots = daily_snapshots;
+    }
+
+    /// The size limit of the card image cache in bytes
+    pub fn card_image_cache_bytes(&self) -> u64 {
+        self.card_image_cache_mb
+            .unwrap_or(Self::DEFAULT_CARD_IMAGE_CACHE_MB)
+            * 1024
+            * 1024
+    }
+
+    /// The local directory or mirror to copy card images from, if configured
+    pub fn card_image_mirror(&self) -> Option<&Path> {
+        self.card_image_mirror.as_deref()
+    }
+
+    /// Create the card image cache in the appdata directory with the configured size limit and image source
+    ///
+    /// Images are copied from the configured mirror, or downloaded from Scryfall unless `offline`,
+    /// in which case only the already cached images are shown.
+    pub fn card_image_cache(&self, appdata_dir: &Path, offline: bool) -> ImageCache {
+        let fetcher: Option<Box<dyn ImageFetcher>> = match self.card_image_mirror() {
+            Some(mirror) => Some(Box::new(LocalDirFetcher::new(mirror))),
+            None if offline => None,
+            None => Some(Box::new(ScryfallImageFetcher)),
+        };
+        ImageCache::new(
+            appdata_dir.join(CARD_IMAGE_CACHE_DIR),
+            self.card_image_cache_bytes(),
+            fetcher,
+        )
+    }
+
+    /// The folder watched for new Full Trade List exports, if configured
+    pub fn watch_folder(&self) -> Option<&Path> {
+        self.watch_folder.as_deref()
+    }
+
+    /// Set or clear the folder watched for new Full Trade List exports
+    pub fn set_watch_folder(&mut self, watch_folder: Option<PathBuf>) {
+        self.watch_folder = watch_folder;
+    }
+
+    /// The additional price sources configured by the user
+    pub fn price_sources(&self) -> &[PriceSourceConfig] {
+        &self.price_sources
+    }
 }
 
 #[cfg(test)]
@@ -93,6 +225,98 @@ mod tests {
         assert_eq!(gui_state, gui_state_loaded);
     }
 
+    #[test]
+    fn test_gui_state_offline_mode_defaults_to_false() {
+        // State files saved before offline mode was added don't have the field
+        let gui_state: GuiState =
+            toml::from_str(r#"tradelist_added_date = "2023-10-14T15:24:21Z""#).unwrap();
+
+        assert!(!gui_state.offline_mode());
+        assert_eq!(gui_state.display_currency(), Currency::Tix);
+        assert_eq!(gui_state.card_image_cache_bytes(), 256 * 1024 * 1024);
+        assert_eq!(gui_state.card_image_mirror(), None);
+        assert_eq!(gui_state.watch_folder(), None);
+        assert!(gui_state.get_tradelist_added_date().is_some());
+        assert!(!gui_state.daily_snapshots());
+        assert!(!gui_state.needs_onboarding());
+    }
+
+    #[test]
+    fn test_gui_state_onboarding() {
+        let tmpdir = TempDir::new().unwrap();
+        let mut gui_state = GuiState::new();
+        assert!(gui_state.needs_onboarding());
+
+        gui_state.complete_onboarding();
+        gui_state.set_daily_snapshots(true);
+        gui_state.save(tmpdir.path().to_path_buf()).unwrap();
+        let gui_state_loaded = GuiState::load(tmpdir.path().to_path_buf()).unwrap();
+
+        assert!(!gui_state_loaded.needs_onboarding());
+        assert!(gui_state_loaded.daily_snapshots());
+    }
+
+    #[test]
+    fn test_card_image_cache_source() {
+        let appdata_dir = Path::new("appdata");
+        let mut gui_state = GuiState::default();
+
+        let online = gui_state.card_image_cache(appdata_dir, false);
+        assert_eq!(online.fetcher_name(), Some("Scryfall"));
+        assert_eq!(online.dir(), appdata_dir.join(CARD_IMAGE_CACHE_DIR));
+        assert_eq!(
+            gui_state.card_image_cache(appdata_dir, true).fetcher_name(),
+            None
+        );
+
+        gui_state.card_image_mirror = Some(PathBuf::from("/srv/card-images"));
+        assert_eq!(
+            gui_state.card_image_cache(appdata_dir, true).fetcher_name(),
+            Some("Local directory")
+        );
+    }
+
+    #[test]
+    fn test_gui_state_price_sources() {
+        let gui_state: GuiState = toml::from_str(
+            r#"
+            offline_mode = true
+            card_image_cache_mb = 10
+            card_image_mirror = "/srv/card-images"
+            watch_folder = "/home/user/mtgo-exports"
+
+            [[price_sources]]
+            name = "Manatraders"
+            url = "https://example.com/prices.csv"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            gui_state.price_sources(),
+            &[PriceSourceConfig {
+                name: "Manatraders".into(),
+                url: "https://example.com/prices.csv".into(),
+            }]
+        );
+        assert_eq!(gui_state.card_image_cache_bytes(), 10 * 1024 * 1024);
+        assert_eq!(
+            gui_state.card_image_mirror(),
+            Some(Path::new("/srv/card-images"))
+        );
+        assert_eq!(
+            gui_state.watch_folder(),
+            Some(Path::new("/home/user/mtgo-exports"))
+        );
+
+        let tmpdir = TempDir::new().unwrap();
+        gui_state.save(tmpdir.path().to_path_buf()).unwrap();
+        assert_eq!(
+            GuiState::load(tmpdir.path().to_path_buf()).unwrap(),
+            gui_state
+        );
+    }
+
     #[test]
     fn test_gui_state_tradelist_data() {
         let mut gui_state = GuiState::new();
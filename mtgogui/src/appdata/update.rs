@@ -8,40 +8,105 @@ use crate::util::{self, first_file_match_from_dir};
 
 use super::{paths::CardDataPaths, APP_DATA_DIR};
 
+/// Whether the card data is downloaded before processing or loaded from the cached data in the appdata directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OfflineMode {
+    /// Download the newest card data before processing
+    #[default]
+    Disabled,
+    /// Offline mode was requested with the [OfflineMode::CLI_FLAG] command-line flag
+    CliFlag,
+    /// Offline mode is enabled in the settings
+    Setting,
+    /// Downloading the card data failed, so the cached data was used instead
+    Detected,
+}
+
+impl OfflineMode {
+    /// Command-line flag that starts the application in offline mode
+    pub const CLI_FLAG: &'static str = "--offline";
+
+    /// Returns true if the card data should not be downloaded
+    pub fn is_offline(&self) -> bool {
+        *self != OfflineMode::Disabled
+    }
+
+    /// A short description of the mode and why it is active
+    pub fn description(&self) -> &'static str {
+        match self {
+            OfflineMode::Disabled => "Online",
+            OfflineMode::CliFlag => "Offline (--offline flag)",
+            OfflineMode::Setting => "Offline (setting)",
+            OfflineMode::Detected => "Offline (download failed)",
+        }
+    }
+}
+
 /// [AppData] contains the paths to the appdata directory and the card data files
 #[derive(Debug)]
 pub struct AppData {
     appdata_dir: PathBuf,
     card_data: CardDataPaths,
+    offline_mode: OfflineMode,
 }
 
 impl AppData {
-    /// Instantiate [AppData] from the path to the appdata directory
+    /// Instantiate [AppData] from the path to the appdata directory, downloading the newest card data first unless `offline_mode` is set.
+    ///
+    /// If downloading the card data fails, the cached card data is used and the returned [AppData] reports [OfflineMode::Detected].
+    ///
+    /// # Arguments
+    ///
+    /// * `offline_mode` - Whether to skip downloading the card data
     ///
     /// # Errors
     ///
-    /// Fails if not all the expected files can be located or the MTGO Getter fails to update the data
-    pub fn update() -> Result<Self, Error> {
+    /// Fails if not all the expected files can be located
+    pub fn update(offline_mode: OfflineMode) -> Result<Self, Error> {
         let appdata_dir = super::util::appdata_path()?;
 
-        // Get App Data
-        match mtgoupdater::mtgogetter_api::mtgogetter_update_all(appdata_dir.as_os_str()) {
-            Ok(output) => {
-                log::info!("MTGO Getter output: {}", output.status);
+        let offline_mode = if offline_mode.is_offline() {
+            log::info!(
+                "{}: skipping card data download",
+                offline_mode.description()
+            );
+            offline_mode
+        } else {
+            // Get App Data
+            match mtgoupdater::mtgogetter_api::mtgogetter_update_all(appdata_dir.as_os_str()) {
+                Ok(output) if output.status.success() => {
+                    log::info!("MTGO Getter output: {}", output.status);
+                    OfflineMode::Disabled
+                }
+                Ok(output) => {
+                    log::warn!(
+                        "MTGO Getter failed with {status}, using cached card data: {stderr}",
+                        status = output.status,
+                        stderr = String::from_utf8_lossy(&output.stderr)
+                    );
+                    OfflineMode::Detected
+                }
+                Err(e) => {
+                    log::warn!("MTGO Getter error, using cached card data: {e}");
+                    OfflineMode::Detected
+                }
             }
-            Err(e) => {
-                log::info!("MTGO Getter error: {e}");
-            }
-        }
+        };
 
         let card_data_paths = CardDataPaths::find(&appdata_dir)?;
 
         Ok(Self {
             appdata_dir,
             card_data: card_data_paths,
+            offline_mode,
         })
     }
 
+    /// Get the [OfflineMode] the card data was loaded with
+    pub fn offline_mode(&self) -> OfflineMode {
+        self.offline_mode
+    }
+
     /// Get the path to the appdata directory as an [OsStr]
     pub fn appdata_dir_path(&self) -> &OsStr {
         self.appdata_dir.as_os_str()
@@ -1,8 +1,14 @@
+pub mod arbitrage;
+pub mod card_detail;
+pub mod clipboard;
+pub mod dek;
 pub mod processor;
+pub mod sell_list;
 pub mod stats;
 pub mod view;
+pub mod watch_folder;
 
-use crate::Message;
+use crate::{app_core::Command, Message};
 use fltk::{
     app::{self, App},
     enums::{Align, Color, Event, FrameType},
@@ -11,9 +17,11 @@ use fltk::{
     prelude::{GroupExt, TableExt, WidgetExt},
 };
 use fltk_table::{SmartTable, TableOpts};
-use mtgoupdater::mtgo_card::MtgoCard;
+use mtgoupdater::mtgo_card::{ItemCategory, MtgoCard};
+use stats::group::GroupBy;
 use std::{
     fmt::Alignment,
+    path::Path,
     sync::{Arc, Mutex},
 };
 use view::table;
@@ -23,4 +31,33 @@ use view::table;
 pub enum TableMessage {
     SortBy(table::column::Column),
     Search(Box<str>),
+    /// Only show items in the given category, or all items if [None]
+    FilterCategory(Option<ItemCategory>),
+    /// Only show the cards playable in the given Scryfall format, or all items if [None]
+    FilterFormat(Option<Box<str>>),
+    GroupBy(GroupBy),
+    ShowArbitrage,
+    ShowSellList,
+    /// The row at the given index was clicked
+    SelectRow(i32),
+    /// Copy the selected rows to the clipboard
+    Copy(clipboard::CopyFormat),
+    /// The image of the card with the given Scryfall ID is cached at the path, or unavailable if [None]
+    CardImage {
+        scryfall_id: Box<str>,
+        path: Option<Box<Path>>,
+    },
+}
+
+impl TableMessage {
+    /// The [Command] for the messages that change the collection model, the message itself for the others
+    pub fn into_command(self) -> Result<Command, Self> {
+        match self {
+            TableMessage::SortBy(column) => Ok(Command::SortBy(column)),
+            TableMessage::Search(search) => Ok(Command::Search(search)),
+            TableMessage::FilterCategory(category) => Ok(Command::FilterCategory(category)),
+            TableMessage::FilterFormat(format) => Ok(Command::FilterFormat(format)),
+            msg => Err(msg),
+        }
+    }
 }
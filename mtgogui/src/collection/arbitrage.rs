@@ -0,0 +1,203 @@
+//! Finds cards where the price spread between vendors is large enough that it matters which vendor the card is sold to.
+//!
+//! Every price source with a price for a card is considered, i.e. Goatbots, Cardhoarder and any additional [PriceSource](mtgoupdater::price_source::PriceSource),
+//! except the sell prices of custom price lists, which are not prices a card can be sold for.
+
+use mtgoupdater::mtgo_card::MtgoCard;
+
+/// The minimum spread between the vendors for a card to be considered an arbitrage opportunity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpreadThreshold {
+    /// Spread in percent of the lowest price
+    Percent(f32),
+    /// Absolute spread in tix per card
+    Tix(f32),
+}
+
+impl Default for SpreadThreshold {
+    fn default() -> Self {
+        SpreadThreshold::Percent(20.)
+    }
+}
+
+/// A card with a price spread between the vendors that is above the [SpreadThreshold]
+#[derive(Debug, Clone)]
+pub struct ArbitrageItem {
+    card: MtgoCard,
+    better_vendor: Box<str>,
+    worse_vendor: Box<str>,
+    spread_tix: f32,
+    spread_percent: f32,
+}
+
+impl ArbitrageItem {
+    /// The card that has a price spread between the vendors
+    pub fn card(&self) -> &MtgoCard {
+        &self.card
+    }
+
+    /// The name of the vendor that pays the most for the card
+    pub fn better_vendor(&self) -> &str {
+        &self.better_vendor
+    }
+
+    /// The name of the vendor that pays the least for the card
+    pub fn worse_vendor(&self) -> &str {
+        &self.worse_vendor
+    }
+
+    /// The spread between the best and worst vendor in tix per card
+    pub fn spread_tix(&self) -> f32 {
+        self.spread_tix
+    }
+
+    /// The spread between the best and worst vendor in percent of the lowest price
+    pub fn spread_percent(&self) -> f32 {
+        self.spread_percent
+    }
+
+    /// The tix gained by selling all copies of the card at the better vendor instead of the worse vendor
+    pub fn tix_gained(&self) -> f32 {
+        self.spread_tix * self.card.quantity as f32
+    }
+
+    /// Price of the card at the given vendor, if the vendor has a price for it
+    pub fn price_at(&self, vendor: &str) -> Option<f32> {
+        self.card.price(vendor)
+    }
+}
+
+/// Find the cards where the spread between the highest and lowest vendor price is above the given threshold
+///
+/// Only prices above 0 at [PriceSide::Buy](mtgoupdater::price_source::PriceSide::Buy) sources are considered,
+/// and cards that don't have a price at two or more vendors are skipped.
+///
+/// # Arguments
+///
+/// * `cards` - A borrowed slice of the cards in the collection
+/// * `threshold` - The minimum spread for a card to be included
+///
+/// # Returns
+///
+/// The arbitrage opportunities sorted by the tix gained by selling at the better vendor in descending order
+pub fn find_arbitrage(cards: &[MtgoCard], threshold: SpreadThreshold) -> Vec<ArbitrageItem> {
+    let mut items: Vec<ArbitrageItem> = cards
+        .iter()
+        .filter_map(|card| {
+            let prices: Vec<(&str, f32)> = card.buy_prices().filter(|(_, p)| *p > 0.).collect();
+            if prices.len() < 2 {
+                return None;
+            }
+            // The first source wins ties, so the built-in sources are preferred
+            let (better_vendor, high) =
+                prices
+                    .iter()
+                    .fold(prices[0], |max, p| if p.1 > max.1 { *p } else { max });
+            let (worse_vendor, low) =
+                prices
+                    .iter()
+                    .fold(prices[0], |min, p| if p.1 < min.1 { *p } else { min });
+            let spread_tix = high - low;
+            let spread_percent = spread_tix / low * 100.;
+
+            let above_threshold = match threshold {
+                SpreadThreshold::Percent(min_percent) => spread_percent >= min_percent,
+                SpreadThreshold::Tix(min_tix) => spread_tix >= min_tix,
+            };
+            if above_threshold && spread_tix > 0. {
+                Some(ArbitrageItem {
+                    card: card.clone(),
+                    better_vendor: better_vendor.into(),
+                    worse_vendor: worse_vendor.into(),
+                    spread_tix,
+                    spread_percent,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    items.sort_by(|a, b| {
+        b.tix_gained()
+            .partial_cmp(&a.tix_gained())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mtgoupdater::price_source::{CARDHOARDER, GOATBOTS};
+    use pretty_assertions::assert_eq;
+
+    fn card(id: u32, quantity: u32, goatbots_price: f32, scryfall_price: Option<f32>) -> MtgoCard {
+        MtgoCard {
+            id,
+            quantity,
+            name: format!("Card {id}").into(),
+            goatbots_price,
+            scryfall_price,
+            ..Default::default()
+        }
+    }
+
+    fn test_cards() -> Vec<MtgoCard> {
+        vec![
+            // 100% spread, 1 tix per card, 2 tix gained in total
+            card(1, 2, 2.0, Some(1.0)),
+            // 50% spread, 0.5 tix per card, 4 tix gained in total
+            card(2, 8, 1.0, Some(1.5)),
+            // 10% spread
+            card(3, 1, 10.0, Some(11.0)),
+            // No Cardhoarder price
+            card(4, 1, 10.0, None),
+            // No Goatbots price
+            card(5, 1, 0.0, Some(1.0)),
+        ]
+    }
+
+    #[test]
+    fn test_find_arbitrage_percent() {
+        let items = find_arbitrage(&test_cards(), SpreadThreshold::Percent(20.));
+
+        let ids: Vec<u32> = items.iter().map(|i| i.card().id).collect();
+        // Sorted by tix gained
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(items[0].better_vendor(), CARDHOARDER);
+        assert!((items[0].tix_gained() - 4.0).abs() < 1e-6);
+        assert!((items[0].spread_percent() - 50.0).abs() < 1e-4);
+        assert_eq!(items[1].better_vendor(), GOATBOTS);
+        assert!((items[1].tix_gained() - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_find_arbitrage_tix() {
+        let items = find_arbitrage(&test_cards(), SpreadThreshold::Tix(0.9));
+
+        let ids: Vec<u32> = items.iter().map(|i| i.card().id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(items[1].better_vendor(), CARDHOARDER);
+        assert!((items[1].spread_tix() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_find_arbitrage_additional_source() {
+        let mut cards = test_cards();
+        // Card 4 only has a Goatbots price, but an additional vendor pays more
+        cards[3].prices.insert("Manatraders".into(), 15.0);
+
+        let items = find_arbitrage(&cards, SpreadThreshold::Percent(20.));
+
+        assert_eq!(items[0].card().id, 4);
+        assert_eq!(items[0].better_vendor(), "Manatraders");
+        assert_eq!(items[0].worse_vendor(), GOATBOTS);
+        assert!((items[0].tix_gained() - 5.0).abs() < 1e-6);
+
+        // The user's own sell prices are not a vendor to sell to
+        cards[3].prices.insert("Our Bot sell".into(), 30.0);
+        let items = find_arbitrage(&cards, SpreadThreshold::Percent(20.));
+        assert_eq!(items[0].better_vendor(), "Manatraders");
+    }
+}
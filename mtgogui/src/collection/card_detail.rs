@@ -0,0 +1,143 @@
+//! The details of a card shown when it's selected in the collection table.
+
+use mtgoupdater::{
+    mtgo_card::MtgoCard,
+    scryfall::{ScryfallCard, FORMATS},
+};
+
+/// All the printings of the card with the given name in the collection, sorted by set and with non-foils first
+pub fn owned_printings<'c>(cards: &'c [MtgoCard], name: &str) -> Vec<&'c MtgoCard> {
+    let mut printings: Vec<&MtgoCard> = cards.iter().filter(|c| *c.name == *name).collect();
+    printings.sort_by(|a, b| a.set.cmp(&b.set).then(a.foil.cmp(&b.foil)));
+    printings
+}
+
+/// Describe the card with its Scryfall oracle data, one line per property
+///
+/// # Arguments
+///
+/// * `card` - The selected card
+/// * `details` - The Scryfall data of the card, if any
+///
+/// # Returns
+///
+/// The description as text, it only has the set code and rarity if there's no Scryfall data for the card
+pub fn describe(card: &MtgoCard, details: Option<&ScryfallCard>) -> String {
+    let details = match details {
+        Some(details) => details,
+        None => {
+            return format!(
+                "Set: {set}\nRarity: {rarity}\n\nNo Scryfall data for this card",
+                set = card.set,
+                rarity = card.rarity.to_string()
+            )
+        }
+    };
+
+    let mut lines = Vec::new();
+    if !details.type_line.is_empty() {
+        lines.push(details.type_line.to_string());
+    }
+    if !details.mana_cost.is_empty() {
+        lines.push(format!("Mana cost: {}", details.mana_cost));
+    }
+    let set_name = if details.set_name.is_empty() {
+        &card.set
+    } else {
+        &details.set_name
+    };
+    lines.push(format!("Set: {set_name} ({set})", set = card.set));
+    if !details.released_at.is_empty() {
+        lines.push(format!("Released: {}", details.released_at));
+    }
+    lines.push(format!("Rarity: {}", card.rarity.to_string()));
+    if !details.oracle_text.is_empty() {
+        lines.push(String::new());
+        lines.push(details.oracle_text.to_string());
+    }
+    if !details.legalities.is_empty() {
+        lines.push(String::new());
+        for (format, name) in FORMATS {
+            if let Some(legality) = details.legality(format) {
+                lines.push(format!("{name}: {}", legality.description()));
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mtgoupdater::{mtgo_card::Rarity, scryfall::Legality};
+    use pretty_assertions::assert_eq;
+
+    fn card(id: u32, set: &str, foil: bool) -> MtgoCard {
+        MtgoCard {
+            id,
+            quantity: 1,
+            name: "Fury Sliver".into(),
+            set: set.into(),
+            rarity: Rarity::Uncommon,
+            foil,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_owned_printings() {
+        let cards = vec![
+            card(25528, "TSP", true),
+            MtgoCard {
+                name: "Swamp".into(),
+                ..card(235, "8ED", false)
+            },
+            card(25527, "TSP", false),
+            card(90000, "A25", false),
+        ];
+
+        let printings: Vec<u32> = owned_printings(&cards, "Fury Sliver")
+            .iter()
+            .map(|c| c.id)
+            .collect();
+
+        assert_eq!(printings, vec![90000, 25527, 25528]);
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut details = ScryfallCard {
+            mtgo_id: 25527,
+            name: "Fury Sliver".into(),
+            released_at: "2006-10-06".into(),
+            set_name: "Time Spiral".into(),
+            type_line: "Creature — Sliver".into(),
+            mana_cost: "{5}{R}".into(),
+            oracle_text: "All Sliver creatures have double strike.".into(),
+            ..Default::default()
+        };
+        details.legalities.insert("modern".into(), Legality::Legal);
+        details
+            .legalities
+            .insert("pauper".into(), Legality::NotLegal);
+        let card = card(25527, "TSP", false);
+
+        assert_eq!(
+            describe(&card, Some(&details)),
+            "Creature — Sliver\n\
+            Mana cost: {5}{R}\n\
+            Set: Time Spiral (TSP)\n\
+            Released: 2006-10-06\n\
+            Rarity: Uncommon\n\
+            \n\
+            All Sliver creatures have double strike.\n\
+            \n\
+            Modern: Legal\n\
+            Pauper: Not legal"
+        );
+        assert_eq!(
+            describe(&card, None),
+            "Set: TSP\nRarity: Uncommon\n\nNo Scryfall data for this card"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Formatting of the cards selected in the collection table for copying to the clipboard.
+
+use mtgoupdater::mtgo_card::MtgoCard;
+
+use crate::appdata::currency::Money;
+
+use super::dek;
+
+/// The format cards are copied to the clipboard in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// One line per card with quantity, name, set and price, e.g. for trade chats
+    Text,
+    /// MTGO `.dek` XML
+    Dek,
+    /// One line per card name with the total quantity, the format MTGO accepts when pasting a deck list
+    MtgoList,
+}
+
+impl CopyFormat {
+    /// Format the cards in the copy format
+    ///
+    /// # Arguments
+    ///
+    /// * `cards` - The cards to copy
+    /// * `money` - The currency and exchange rate to show the prices in, only used by [CopyFormat::Text]
+    pub fn format(&self, cards: &[&MtgoCard], money: Money) -> String {
+        match self {
+            CopyFormat::Text => to_text(cards, money),
+            CopyFormat::Dek => dek::to_dek_xml(cards.iter().map(|c| (*c, c.quantity))),
+            CopyFormat::MtgoList => to_mtgo_list(cards),
+        }
+    }
+}
+
+/// One line per card in the format `<quantity> <name> (<set>) <price>` with the Goatbots price
+fn to_text(cards: &[&MtgoCard], money: Money) -> String {
+    cards
+        .iter()
+        .map(|c| {
+            format!(
+                "{quantity} {name}{foil} ({set}) {price}\n",
+                quantity = c.quantity,
+                name = c.name,
+                foil = if c.foil { " (foil)" } else { "" },
+                set = c.set,
+                price = money.format(c.goatbots_price as f64, 3)
+            )
+        })
+        .collect()
+}
+
+/// One line per card name in the format `<quantity> <name>`, summing the quantity of all printings
+fn to_mtgo_list(cards: &[&MtgoCard]) -> String {
+    let mut names: Vec<(&str, u32)> = Vec::new();
+    for card in cards {
+        match names.iter_mut().find(|(name, _)| *name == &*card.name) {
+            Some((_, quantity)) => *quantity += card.quantity,
+            None => names.push((&card.name, card.quantity)),
+        }
+    }
+    names
+        .into_iter()
+        .map(|(name, quantity)| format!("{quantity} {name}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn cards() -> Vec<MtgoCard> {
+        vec![
+            MtgoCard {
+                id: 31745,
+                quantity: 4,
+                name: "Noble Hierarch".into(),
+                set: "CON".into(),
+                goatbots_price: 0.39,
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 235,
+                quantity: 10,
+                name: "Swamp".into(),
+                set: "8ED".into(),
+                goatbots_price: 0.001,
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 31746,
+                quantity: 1,
+                name: "Noble Hierarch".into(),
+                set: "CON".into(),
+                foil: true,
+                goatbots_price: 1.5,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_copy_as_text() {
+        let cards = cards();
+        let selected: Vec<&MtgoCard> = cards.iter().collect();
+
+        assert_eq!(
+            CopyFormat::Text.format(&selected, Money::TIX),
+            "4 Noble Hierarch (CON) 0.390 tix\n\
+            10 Swamp (8ED) 0.001 tix\n\
+            1 Noble Hierarch (foil) (CON) 1.500 tix\n"
+        );
+    }
+
+    #[test]
+    fn test_copy_as_mtgo_list() {
+        let cards = cards();
+        let selected: Vec<&MtgoCard> = cards.iter().collect();
+
+        assert_eq!(
+            CopyFormat::MtgoList.format(&selected, Money::TIX),
+            "5 Noble Hierarch\n10 Swamp\n"
+        );
+    }
+
+    #[test]
+    fn test_copy_as_dek() {
+        let cards = cards();
+        let selected: Vec<&MtgoCard> = cards.iter().take(1).collect();
+
+        let dek = CopyFormat::Dek.format(&selected, Money::TIX);
+
+        assert!(dek.contains(
+            r#"<Cards CatID="31745" Quantity="4" Sideboard="false" Name="Noble Hierarch" Annotation="0" />"#
+        ));
+    }
+}
@@ -0,0 +1,212 @@
+//! Export and validation of cards in the MTGO `.dek` XML format, the same format as the `Full Trade List.dek` exported from MTGO.
+//!
+//! A `.dek`-file can be imported into MTGO as a deck or a trade binder, and handed to a trading bot.
+
+use std::{io, path::Path};
+
+use mtgoupdater::mtgo_card::MtgoCard;
+
+/// Format the given cards and quantities as the contents of an MTGO `.dek`-file
+///
+/// # Arguments
+///
+/// * `cards` - Pairs of cards and the quantity of each card to include
+///
+/// # Returns
+///
+/// The `.dek` XML as a [String]
+pub fn to_dek_xml<'c, I>(cards: I) -> String
+where
+    I: IntoIterator<Item = (&'c MtgoCard, u32)>,
+{
+    let mut xml = String::from(concat!(
+        r#"<?xml version="1.0" encoding="utf-8"?>"#,
+        "\n",
+        r#"<Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">"#,
+        "\n",
+        "  <NetDeckID>0</NetDeckID>\n",
+        "  <PreconstructedDeckID>0</PreconstructedDeckID>\n",
+    ));
+
+    for (card, quantity) in cards {
+        if quantity == 0 {
+            continue;
+        }
+        xml.push_str(&format!(
+            "  <Cards CatID=\"{id}\" Quantity=\"{quantity}\" Sideboard=\"false\" Name=\"{name}\" Annotation=\"0\" />\n",
+            id = card.id,
+            name = escape_xml_attr(&card.name),
+        ));
+    }
+    xml.push_str("</Deck>\n");
+    xml
+}
+
+/// Write the given cards and quantities to an MTGO `.dek`-file at the given path
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written
+pub fn save_dek<'c, I>(path: &Path, cards: I) -> io::Result<()>
+where
+    I: IntoIterator<Item = (&'c MtgoCard, u32)>,
+{
+    std::fs::write(path, to_dek_xml(cards))
+}
+
+/// Summary of a valid `.dek`-file, see [validate_dek]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DekSummary {
+    /// Number of distinct card entries
+    pub entries: usize,
+    /// Total quantity of all entries
+    pub quantity: u64,
+}
+
+/// Check that the `.dek`-file at the given path looks like a trade list exported from MTGO
+///
+/// # Errors
+///
+/// Returns a description of the problem if the file cannot be read or is not a valid `.dek`-file
+pub fn validate_dek_file(path: &Path) -> Result<DekSummary, String> {
+    let xml = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+    validate_dek(&xml)
+}
+
+/// Check that the contents are a `.dek`-file with at least one card entry
+///
+/// Only the parts the MTGO Preprocessor relies on are checked: the `Deck` element and the
+/// `CatID` and `Quantity` attributes of each `Cards` element.
+///
+/// # Errors
+///
+/// Returns a description of the first problem found
+pub fn validate_dek(xml: &str) -> Result<DekSummary, String> {
+    if !xml.contains("<Deck") {
+        return Err("Not an MTGO .dek-file, it has no <Deck> element".to_string());
+    }
+    let mut summary = DekSummary {
+        entries: 0,
+        quantity: 0,
+    };
+    for (idx, line) in xml.lines().enumerate() {
+        let line = line.trim();
+        if !line.starts_with("<Cards ") {
+            continue;
+        }
+        let line_no = idx + 1;
+        xml_attr(line, "CatID")
+            .and_then(|id| id.parse::<u32>().ok())
+            .ok_or_else(|| format!("Invalid or missing CatID on line {line_no}"))?;
+        let quantity = xml_attr(line, "Quantity")
+            .and_then(|q| q.parse::<u64>().ok())
+            .ok_or_else(|| format!("Invalid or missing Quantity on line {line_no}"))?;
+        summary.entries += 1;
+        summary.quantity += quantity;
+    }
+    if summary.entries == 0 {
+        return Err("The .dek-file has no cards".to_string());
+    }
+    Ok(summary)
+}
+
+/// The value of the attribute with the given name in an XML element on a single line
+fn xml_attr<'l>(line: &'l str, name: &str) -> Option<&'l str> {
+    let start = line.find(&format!(" {name}=\""))? + name.len() + 3;
+    let len = line[start..].find('"')?;
+    Some(&line[start..start + len])
+}
+
+/// Escape the characters that are not allowed in an XML attribute value
+fn escape_xml_attr(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_to_dek_xml() {
+        let swamp = MtgoCard {
+            id: 235,
+            name: "Swamp".into(),
+            ..Default::default()
+        };
+        let fire_ice = MtgoCard {
+            id: 1234,
+            name: "Fire // Ice & \"Friends\"".into(),
+            ..Default::default()
+        };
+        let skipped = MtgoCard {
+            id: 1,
+            name: "Event Ticket".into(),
+            ..Default::default()
+        };
+
+        let dek = to_dek_xml([(&swamp, 4), (&fire_ice, 1), (&skipped, 0)]);
+
+        assert_eq!(
+            dek,
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <NetDeckID>0</NetDeckID>
+  <PreconstructedDeckID>0</PreconstructedDeckID>
+  <Cards CatID="235" Quantity="4" Sideboard="false" Name="Swamp" Annotation="0" />
+  <Cards CatID="1234" Quantity="1" Sideboard="false" Name="Fire // Ice &amp; &quot;Friends&quot;" Annotation="0" />
+</Deck>
+"#
+        );
+    }
+
+    #[test]
+    fn test_validate_dek() {
+        let swamp = MtgoCard {
+            id: 235,
+            name: "Swamp".into(),
+            ..Default::default()
+        };
+        let lotus = MtgoCard {
+            id: 53155,
+            name: "Black Lotus".into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate_dek(&to_dek_xml([(&swamp, 4), (&lotus, 1)])),
+            Ok(DekSummary {
+                entries: 2,
+                quantity: 5
+            })
+        );
+        assert!(validate_dek(&to_dek_xml([(&swamp, 0)])).is_err());
+        assert!(validate_dek("CatID,Quantity\n235,4").is_err());
+        assert_eq!(
+            validate_dek("<Deck>\n  <Cards CatID=\"235\" Sideboard=\"false\" />\n</Deck>"),
+            Err("Invalid or missing Quantity on line 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_dek_file() {
+        let summary = validate_dek_file(Path::new(
+            "../test/test-data/mtgo/Full Trade List-small-5cards.dek",
+        ))
+        .unwrap();
+
+        assert_eq!(summary.entries, 5);
+        assert!(validate_dek_file(Path::new("does-not-exist.dek")).is_err());
+    }
+}
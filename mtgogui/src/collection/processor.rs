@@ -1,18 +1,17 @@
-use std::{
-    ffi::OsStr,
-    io::Error,
-    path::{Path, PathBuf},
-    thread,
-};
+use std::{path::Path, sync::Arc, thread};
 
 use fltk::{app::Sender, enums::Color};
 
 use crate::{
-    appdata::{update::AppData, APP_DATA_DIR},
-    collection::stats::container::CollectionStats,
+    app_core::{
+        pipeline::{self, ProcessError, ProcessedTradeList},
+        Event,
+    },
+    appdata::{state::PriceSourceConfig, update::OfflineMode},
     menubar::util::ProgressUpdate,
     menubar::{McmMenuBar, MenubarMessage},
-    util::{first_file_match_from_dir, RelativeSize},
+    notification::{Notification, NotificationAction},
+    util::RelativeSize,
     Message, DEFAULT_APP_WIDTH, MENU_BAR_HEIGHT,
 };
 
@@ -20,6 +19,8 @@ use crate::{
 #[derive(Debug)]
 pub struct TradelistProcessor {
     event_sender: Sender<Message>,
+    offline_mode: OfflineMode,
+    price_sources: Vec<PriceSourceConfig>,
 }
 
 impl TradelistProcessor {
@@ -27,9 +28,21 @@ impl TradelistProcessor {
     pub fn new(ev_send: Sender<Message>) -> Self {
         Self {
             event_sender: ev_send,
+            offline_mode: OfflineMode::default(),
+            price_sources: Vec::new(),
         }
     }
 
+    /// Set the [OfflineMode] used when processing the next tradelist
+    pub fn set_offline_mode(&mut self, offline_mode: OfflineMode) {
+        self.offline_mode = offline_mode;
+    }
+
+    /// Set the additional price sources to fetch prices from when processing the next tradelist
+    pub fn set_price_sources(&mut self, price_sources: Vec<PriceSourceConfig>) {
+        self.price_sources = price_sources;
+    }
+
     /// Process the tradelist at the given path
     ///
     /// # Arguments
@@ -43,96 +56,52 @@ impl TradelistProcessor {
         let _handle = trade_list_processor_thread
             .spawn({
                 let sender = self.event_sender.clone();
+                let offline_mode = self.offline_mode;
+                let price_sources = self.price_sources.clone();
                 move || {
-                    sender.send(Message::MenuBar(MenubarMessage::ProgressBar(
-                        ProgressUpdate {
-                            show: true,
-                            progress: 5.,
-                            label: "Processing trade list".into(),
-                            ..Default::default()
-                        },
-                    )));
-
-                    // Invoke MTGO getter
-                    sender.send(Message::MenuBar(MenubarMessage::ProgressBar(
-                        ProgressUpdate {
-                            show: true,
-                            progress: 10.,
-                            label: "Updating card data...".into(),
-                            ..Default::default()
-                        },
-                    )));
-
-                    // Give the full trade list to the parser
-                    // Find all the most recent files in the appdata directory, download and update them if necessary
-                    let appdata_paths = match AppData::update() {
-                        Ok(paths) => paths,
-                        Err(err) => {
-                            log::info!("{err}");
-                            return;
-                        }
-                    };
-
-                    sender.send(Message::MenuBar(MenubarMessage::ProgressBar(
-                        ProgressUpdate {
-                            show: true,
-                            progress: 70.,
-                            label: "Update done".into(),
-                            ..Default::default()
+                    let processed = pipeline::process_trade_list(
+                        &full_trade_list_path,
+                        offline_mode,
+                        &price_sources,
+                        |ev| match ev {
+                            Event::Progress { percent, label } => sender.send(Message::MenuBar(
+                                MenubarMessage::ProgressBar(ProgressUpdate {
+                                    show: true,
+                                    progress: percent,
+                                    label,
+                                    ..Default::default()
+                                }),
+                            )),
+                            Event::OfflineModeDetected => sender.send(Message::OfflineModeDetected),
+                            _ => (),
                         },
-                    )));
-
-                    // Invoke MTGO preprocessor
-                    log::info!("Running MTGO Preprocessor");
-                    log::info!("Scryfall path: {p:?}", p = appdata_paths.scryfall_path());
-                    log::info!(
-                        "Card definitions path: {p:?}",
-                        p = appdata_paths.card_definitions_path()
-                    );
-                    log::info!(
-                        "Price history path: {p:?}",
-                        p = appdata_paths.price_history_path()
                     );
-                    log::info!("Save to dir: {p:?}", p = appdata_paths.appdata_dir_path());
-
-                    sender.send(Message::MenuBar(MenubarMessage::ProgressBar(
-                        ProgressUpdate {
-                            show: true,
-                            progress: 75.,
-                            label: "Processing card data...".into(),
-                            ..Default::default()
-                        },
-                    )));
-
-                    match mtgoupdater::mtgo_preprocessor_api::run_mtgo_preprocessor_parse_full(
-                        OsStr::new(full_trade_list_path.as_ref()),
-                        appdata_paths.scryfall_path(),
-                        appdata_paths.card_definitions_path(),
-                        appdata_paths.price_history_path(),
-                        Some(appdata_paths.appdata_dir_path()),
-                    ) {
-                        Ok(cards) => {
-                            log::info!("MTGO Preprocessor output: {} cards", cards.len());
-                            // Fill the progress bar as appropriate
+                    match processed {
+                        Ok(ProcessedTradeList { cards, scryfall }) => {
                             // Give all the data to the collection table
-                            sender.send(Message::MenuBar(MenubarMessage::ProgressBar(
-                                ProgressUpdate {
-                                    show: true,
-                                    progress: 95.,
-                                    label: "Processing complete...".into(),
-                                    ..Default::default()
-                                },
-                            )));
-
                             fadeout_progress_bar(sender.clone());
-                            sender.send(Message::SetCollectionStats(CollectionStats::from_cards(
-                                &cards,
-                            )));
                             sender.send(Message::SetCards(cards));
+                            if let Some(scryfall) = scryfall {
+                                sender.send(Message::SetScryfallCards(Arc::new(scryfall)));
+                            }
                         }
-                        Err(e) => {
-                            log::info!("MTGO Preprocessor error: {e}");
-                        }
+                        Err(ProcessError::UpdateCardData(details)) => notify_failure(
+                            &sender,
+                            Notification::error("Failed to update the card data")
+                                .with_details(details)
+                                .with_action(NotificationAction::retry(Message::ProcessTradeList(
+                                    full_trade_list_path,
+                                ))),
+                        ),
+                        Err(err @ ProcessError::Preprocessor(_)) => notify_failure(
+                            &sender,
+                            Notification::error("Failed to process the trade list")
+                                .with_details(err)
+                                .with_action(NotificationAction::retry(Message::ProcessTradeList(
+                                    full_trade_list_path,
+                                )))
+                                .with_action(NotificationAction::choose_another_file()),
+                        ),
                     }
                 }
             })
@@ -140,6 +109,17 @@ impl TradelistProcessor {
     }
 }
 
+/// Hide the progress bar and notify the user that processing the trade list failed
+fn notify_failure(ev_sender: &Sender<Message>, notification: Notification) {
+    ev_sender.send(Message::MenuBar(MenubarMessage::ProgressBar(
+        ProgressUpdate {
+            show: false,
+            ..Default::default()
+        },
+    )));
+    ev_sender.send(notification.into());
+}
+
 /// Spawn a thread to set the progress bar to 100% and then fade it out.
 ///
 /// # Arguments
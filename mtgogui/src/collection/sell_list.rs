@@ -0,0 +1,411 @@
+//! Generates a list of cards to sell from a set of rules, such as selling surplus playsets and bulk.
+
+use std::collections::HashMap;
+
+use mtgoupdater::mtgo_card::{ItemCategory, MtgoCard};
+
+/// Names of the basic lands, they are never considered surplus by [SellRule::KeepPlayset]
+const BASIC_LANDS: [&str; 11] = [
+    "Plains",
+    "Island",
+    "Swamp",
+    "Mountain",
+    "Forest",
+    "Wastes",
+    "Snow-Covered Plains",
+    "Snow-Covered Island",
+    "Snow-Covered Swamp",
+    "Snow-Covered Mountain",
+    "Snow-Covered Forest",
+];
+
+/// A rule that decides how many copies of a card to sell
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SellRule {
+    /// Keep the given number of copies of each non-basic card name and sell the rest
+    ///
+    /// The copies are counted across all printings of the card, and the surplus is sold from the cheapest printings first.
+    /// The copies sold by the other rules count towards the surplus, so the given number of copies is always kept.
+    /// Sealed product and event tickets are not affected.
+    KeepPlayset(u32),
+    /// Sell all copies of cards priced under the given amount of tix at Goatbots
+    ///
+    /// Cards without a Goatbots price are skipped.
+    SellUnderPrice(f32),
+    /// Sell all copies of a foil card if a non-foil version of the same card in the collection is cheaper
+    SellFoilsWhenNonFoilCheaper,
+}
+
+impl SellRule {
+    /// A short description of the rule, used as the reason an entry is on the sell list
+    pub fn description(&self) -> String {
+        match self {
+            SellRule::KeepPlayset(keep) => format!("Keep {keep}"),
+            SellRule::SellUnderPrice(price) => format!("Under {price} tix"),
+            SellRule::SellFoilsWhenNonFoilCheaper => "Non-foil cheaper".to_string(),
+        }
+    }
+
+    /// Returns the number of copies of the card to sell according to this rule
+    ///
+    /// # Arguments
+    ///
+    /// * `card` - The card to evaluate
+    /// * `context` - The collection wide data the rules depend on
+    fn sell_quantity(&self, card: &MtgoCard, context: &RuleContext) -> u32 {
+        match self {
+            // Depends on all printings of the card and the other rules, see [keep_playset]
+            SellRule::KeepPlayset(_) => 0,
+            SellRule::SellUnderPrice(price) => {
+                if card.goatbots_price > 0. && card.goatbots_price < *price {
+                    card.quantity
+                } else {
+                    0
+                }
+            }
+            SellRule::SellFoilsWhenNonFoilCheaper => {
+                if !card.foil {
+                    return 0;
+                }
+                match context.cheapest_non_foil.get(card.name.as_ref()) {
+                    Some(non_foil_price) if *non_foil_price < card.goatbots_price => card.quantity,
+                    _ => 0,
+                }
+            }
+        }
+    }
+}
+
+/// Data about the whole collection that the [SellRule]s depend on, computed once per sell list
+struct RuleContext<'a> {
+    /// The lowest Goatbots price of the non-foil versions of each card name in the collection
+    cheapest_non_foil: HashMap<&'a str, f32>,
+}
+
+impl<'a> RuleContext<'a> {
+    fn new(cards: &'a [MtgoCard]) -> Self {
+        let mut cheapest_non_foil: HashMap<&str, f32> = HashMap::new();
+        for card in cards.iter().filter(|c| !c.foil) {
+            cheapest_non_foil
+                .entry(card.name.as_ref())
+                .and_modify(|price| *price = price.min(card.goatbots_price))
+                .or_insert(card.goatbots_price);
+        }
+
+        Self { cheapest_non_foil }
+    }
+}
+
+/// Applies [SellRule::KeepPlayset] on top of the copies the other rules sell
+///
+/// The copies sold of each non-basic card name are capped so `keep` copies are left,
+/// and the rest of the surplus is sold from the cheapest printings first.
+///
+/// # Arguments
+///
+/// * `cards` - The cards in the collection
+/// * `keep` - The number of copies of each card name to keep
+/// * `quantities` - The copies of each card the other rules sell, indexed like the cards
+/// * `reasons` - The rules that sell each card, indexed like the cards
+fn keep_playset(
+    cards: &[MtgoCard],
+    keep: u32,
+    quantities: &mut [u32],
+    reasons: &mut [Vec<SellRule>],
+) {
+    let mut printings_by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, card) in cards.iter().enumerate() {
+        if card.category() == ItemCategory::Card && !BASIC_LANDS.contains(&card.name.as_ref()) {
+            printings_by_name
+                .entry(card.name.as_ref())
+                .or_default()
+                .push(idx);
+        }
+    }
+
+    for mut printings in printings_by_name.into_values() {
+        let total: u32 = printings.iter().map(|idx| cards[*idx].quantity).sum();
+        let mut to_sell = total.saturating_sub(keep);
+        printings.sort_by(|a, b| {
+            cards[*a]
+                .goatbots_price
+                .total_cmp(&cards[*b].goatbots_price)
+        });
+        // The copies the other rules sell come out of the surplus first
+        for idx in &printings {
+            quantities[*idx] = quantities[*idx].min(to_sell);
+            to_sell -= quantities[*idx];
+        }
+        for idx in printings {
+            if to_sell == 0 {
+                break;
+            }
+            let extra = (cards[idx].quantity - quantities[idx]).min(to_sell);
+            if extra > 0 {
+                quantities[idx] += extra;
+                to_sell -= extra;
+                reasons[idx].push(SellRule::KeepPlayset(keep));
+            }
+        }
+    }
+}
+
+/// A card on the sell list with the number of copies to sell and the rules that put it there
+#[derive(Debug, Clone)]
+pub struct SellEntry {
+    card: MtgoCard,
+    quantity: u32,
+    reasons: Vec<SellRule>,
+}
+
+impl SellEntry {
+    /// The card to sell
+    pub fn card(&self) -> &MtgoCard {
+        &self.card
+    }
+
+    /// The number of copies to sell
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    /// The rules that put the card on the sell list
+    pub fn reasons(&self) -> &[SellRule] {
+        &self.reasons
+    }
+
+    /// Expected proceeds from selling the copies at Goatbots
+    pub fn goatbots_proceeds(&self) -> f64 {
+        self.card.goatbots_price as f64 * self.quantity as f64
+    }
+
+    /// Expected proceeds from selling the copies at Cardhoarder
+    pub fn cardhoarder_proceeds(&self) -> f64 {
+        self.card
+            .scryfall_price
+            .map_or(0., |price| price as f64 * self.quantity as f64)
+    }
+}
+
+/// The list of cards to sell, generated by applying a set of [SellRule]s to the collection
+#[derive(Debug, Clone, Default)]
+pub struct SellList {
+    entries: Vec<SellEntry>,
+}
+
+impl SellList {
+    /// Generate a sell list by applying the given rules to the given cards
+    ///
+    /// If several rules apply to the same card, the rule that sells the most copies decides the quantity,
+    /// capped by [SellRule::KeepPlayset] so the copies to keep are never sold.
+    /// If there are several [SellRule::KeepPlayset] rules, the one keeping the most copies applies.
+    /// Event tickets are never put on the sell list.
+    ///
+    /// # Arguments
+    ///
+    /// * `cards` - A borrowed slice of the cards in the collection
+    /// * `rules` - The rules to apply
+    ///
+    /// # Returns
+    ///
+    /// A [SellList] sorted by expected Goatbots proceeds in descending order
+    pub fn generate(cards: &[MtgoCard], rules: &[SellRule]) -> Self {
+        let context = RuleContext::new(cards);
+
+        let mut quantities = vec![0; cards.len()];
+        let mut reasons = vec![Vec::new(); cards.len()];
+        for (idx, card) in cards.iter().enumerate() {
+            if card.category() == ItemCategory::EventTicket {
+                continue;
+            }
+            for rule in rules {
+                let rule_quantity = rule.sell_quantity(card, &context);
+                if rule_quantity > 0 {
+                    quantities[idx] = quantities[idx].max(rule_quantity);
+                    reasons[idx].push(*rule);
+                }
+            }
+        }
+        let keep = rules
+            .iter()
+            .filter_map(|rule| match rule {
+                SellRule::KeepPlayset(keep) => Some(*keep),
+                _ => None,
+            })
+            .max();
+        if let Some(keep) = keep {
+            keep_playset(cards, keep, &mut quantities, &mut reasons);
+        }
+
+        let mut entries: Vec<SellEntry> = cards
+            .iter()
+            .zip(quantities)
+            .zip(reasons)
+            .filter(|((_, quantity), _)| *quantity > 0)
+            .map(|((card, quantity), reasons)| SellEntry {
+                card: card.clone(),
+                quantity,
+                reasons,
+            })
+            .collect();
+
+        entries.sort_by(|a, b| {
+            b.goatbots_proceeds()
+                .partial_cmp(&a.goatbots_proceeds())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Self { entries }
+    }
+
+    /// The entries on the sell list
+    pub fn entries(&self) -> &[SellEntry] {
+        &self.entries
+    }
+
+    /// The total number of copies on the sell list
+    pub fn total_quantity(&self) -> u32 {
+        self.entries.iter().map(|e| e.quantity).sum()
+    }
+
+    /// Expected proceeds from selling everything on the list at Goatbots
+    pub fn goatbots_proceeds(&self) -> f64 {
+        self.entries.iter().map(|e| e.goatbots_proceeds()).sum()
+    }
+
+    /// Expected proceeds from selling everything on the list at Cardhoarder
+    pub fn cardhoarder_proceeds(&self) -> f64 {
+        self.entries.iter().map(|e| e.cardhoarder_proceeds()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mtgoupdater::mtgo_card::Rarity;
+    use pretty_assertions::assert_eq;
+
+    fn card(id: u32, name: &str, quantity: u32, foil: bool, goatbots_price: f32) -> MtgoCard {
+        MtgoCard {
+            id,
+            quantity,
+            name: name.into(),
+            foil,
+            goatbots_price,
+            scryfall_price: Some(goatbots_price * 2.),
+            rarity: Rarity::Rare,
+            ..Default::default()
+        }
+    }
+
+    fn test_cards() -> Vec<MtgoCard> {
+        vec![
+            MtgoCard {
+                rarity: Rarity::None,
+                ..card(1, "Event Ticket", 100, false, 1.0)
+            },
+            card(10, "Ragavan, Nimble Pilferer", 6, false, 20.0),
+            card(11, "Ragavan, Nimble Pilferer", 1, true, 30.0),
+            card(20, "Swamp", 30, false, 0.001),
+            card(30, "Tranquil Cove", 2, false, 0.01),
+            MtgoCard {
+                rarity: Rarity::Booster,
+                ..card(40, "Modern Horizons 3 Booster", 10, false, 4.0)
+            },
+        ]
+    }
+
+    #[test]
+    fn test_keep_playset() {
+        let cards = test_cards();
+        let list = SellList::generate(&cards, &[SellRule::KeepPlayset(4)]);
+
+        // 7 Ragavans counting the foil, the surplus is sold from the cheaper non-foil, sealed product is kept
+        assert_eq!(list.entries().len(), 1);
+        assert_eq!(list.entries()[0].card().id, 10);
+        assert_eq!(list.entries()[0].quantity(), 3);
+    }
+
+    #[test]
+    fn test_keep_playset_across_printings() {
+        let cards = vec![
+            card(40, "Lightning Bolt", 2, false, 0.5),
+            card(41, "Lightning Bolt", 3, false, 0.1),
+            card(42, "Lightning Bolt", 2, false, 0.2),
+            card(50, "Counterspell", 2, false, 0.3),
+            card(51, "Counterspell", 2, false, 0.4),
+        ];
+        let list = SellList::generate(&cards, &[SellRule::KeepPlayset(4)]);
+
+        let sold: Vec<(u32, u32)> = list
+            .entries()
+            .iter()
+            .map(|e| (e.card().id, e.quantity()))
+            .collect();
+        assert_eq!(sold, vec![(41, 3)]);
+
+        let list = SellList::generate(&cards, &[SellRule::KeepPlayset(1)]);
+        let mut sold: Vec<(u32, u32)> = list
+            .entries()
+            .iter()
+            .map(|e| (e.card().id, e.quantity()))
+            .collect();
+        sold.sort();
+        assert_eq!(sold, vec![(40, 1), (41, 3), (42, 2), (50, 2), (51, 1)]);
+    }
+
+    #[test]
+    fn test_sell_under_price() {
+        let cards = test_cards();
+        let list = SellList::generate(&cards, &[SellRule::SellUnderPrice(0.05)]);
+
+        let ids: Vec<u32> = list.entries().iter().map(|e| e.card().id).collect();
+        // Sorted by proceeds, 30 swamps are worth more than 2 coves
+        assert_eq!(ids, vec![20, 30]);
+        assert_eq!(list.total_quantity(), 32);
+
+        // Cards without a Goatbots price are not sold for nothing
+        let unpriced = [card(60, "Unpriced Promo", 1, false, 0.)];
+        assert!(
+            SellList::generate(&unpriced, &[SellRule::SellUnderPrice(0.05)])
+                .entries()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_sell_foil_when_non_foil_cheaper() {
+        let cards = test_cards();
+        let list = SellList::generate(&cards, &[SellRule::SellFoilsWhenNonFoilCheaper]);
+
+        assert_eq!(list.entries().len(), 1);
+        assert_eq!(list.entries()[0].card().id, 11);
+        assert_eq!(list.entries()[0].quantity(), 1);
+    }
+
+    #[test]
+    fn test_combined_rules_and_proceeds() {
+        let cards = test_cards();
+        let list = SellList::generate(
+            &cards,
+            &[
+                SellRule::KeepPlayset(4),
+                SellRule::SellUnderPrice(0.05),
+                SellRule::SellFoilsWhenNonFoilCheaper,
+            ],
+        );
+
+        let ids: Vec<u32> = list.entries().iter().map(|e| e.card().id).collect();
+        assert_eq!(ids, vec![10, 11, 20]);
+        // 3 of the 7 Ragavans are sold: the foil and 2 of the non-foils, keeping a playset
+        assert_eq!(list.entries()[0].quantity(), 2);
+        assert_eq!(list.entries()[0].reasons(), &[SellRule::KeepPlayset(4)]);
+        assert_eq!(list.entries()[1].quantity(), 1);
+        assert_eq!(
+            list.entries()[1].reasons(),
+            &[SellRule::SellFoilsWhenNonFoilCheaper]
+        );
+        // The 2 coves are under the price but there are fewer than 4 of them
+        assert!((list.goatbots_proceeds() - (40. + 30. + 0.03)).abs() < 1e-3);
+        assert!((list.cardhoarder_proceeds() - 2. * (40. + 30. + 0.03)).abs() < 1e-3);
+    }
+}
@@ -5,6 +5,7 @@ use fltk::{
 use mtgoupdater::mtgo_card::MtgoCard;
 
 pub mod container;
+pub mod group;
 pub mod items;
 mod util;
 pub mod view;
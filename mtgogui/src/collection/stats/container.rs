@@ -1,6 +1,11 @@
-use mtgoupdater::mtgo_card::{MtgoCard, Rarity};
+use mtgoupdater::{
+    mtgo_card::{ItemCategory, MtgoCard, Rarity},
+    price_source::{self, PriceSide},
+    scryfall::FORMATS,
+};
 
-use super::util::{MultiValueStat, UniqueTotal};
+use super::util::{CategoryStat, MultiValueStat, UniqueTotal};
+use crate::appdata::currency::Money;
 
 /// Container for collection stats
 #[derive(Debug, Clone)]
@@ -11,9 +16,25 @@ pub struct CollectionStats {
     cards_under_a_tenth_tix: UniqueTotal,
     cards_over_5_tix: UniqueTotal,
     rarity_distribution: Option<MultiValueStat>,
+    value_distribution: Option<CategoryStat>,
+    top_holdings: Option<MultiValueStat>,
+    value_concentration: Option<CategoryStat>,
+    format_legality: Option<CategoryStat>,
+    event_tickets: u32,
+    sealed_items: UniqueTotal,
+    sealed_value: Option<MultiValueStat>,
+    net_worth: Option<MultiValueStat>,
+    money: Money,
 }
 
 impl CollectionStats {
+    /// Upper bounds in tix of the price buckets in the value distribution, the last bucket has no upper bound
+    pub const VALUE_BUCKETS: [f32; 5] = [0.1, 1., 5., 20., 50.];
+    /// Number of holdings listed in the most valuable holdings
+    pub const TOP_HOLDINGS: usize = 10;
+    /// Number of the most valuable holdings the value concentration is calculated for
+    pub const CONCENTRATION_TOP_N: [usize; 2] = [10, 50];
+
     /// Create a new empty [CollectionStats] container
     pub fn new() -> Self {
         Self {
@@ -23,11 +44,23 @@ impl CollectionStats {
             cards_under_a_tenth_tix: UniqueTotal::default(),
             cards_over_5_tix: UniqueTotal::default(),
             rarity_distribution: None,
+            value_distribution: None,
+            top_holdings: None,
+            value_concentration: None,
+            format_legality: None,
+            event_tickets: 0,
+            sealed_items: UniqueTotal::default(),
+            sealed_value: None,
+            net_worth: None,
+            money: Money::TIX,
         }
     }
 
     /// Create a new [CollectionStats] from a list of cards
     ///
+    /// Event Tickets are counted as liquid tix and boosters as sealed product,
+    /// the card stats only include the items in the [ItemCategory::Card] category.
+    ///
     /// # Arguments
     ///
     /// * `cards` - A borrowed slice of cards to create stats from
@@ -36,14 +69,60 @@ impl CollectionStats {
     ///
     /// A new [CollectionStats] container
     pub fn from_cards(cards: &[MtgoCard]) -> Self {
+        Self::from_cards_in(cards, Money::TIX)
+    }
+
+    /// Create a new [CollectionStats] from a list of cards with the values displayed as [Money]
+    ///
+    /// # Arguments
+    ///
+    /// * `cards` - A borrowed slice of cards to create stats from
+    /// * `money` - The currency and exchange rate to display the values in
+    ///
+    /// # Returns
+    ///
+    /// A new [CollectionStats] container
+    pub fn from_cards_in(cards: &[MtgoCard], money: Money) -> Self {
         let mut stats = Self::new();
+        stats.money = money;
+
+        let (mut only_cards, mut sealed) = (Vec::new(), Vec::new());
+        for item in cards {
+            match item.category() {
+                ItemCategory::Card => only_cards.push(item.clone()),
+                ItemCategory::Sealed => sealed.push(item.clone()),
+                ItemCategory::EventTicket => stats.event_tickets += item.quantity,
+            }
+        }
 
-        stats.calc_total_cards(cards);
-        stats.calc_most_expensive_item(cards);
-        stats.calc_total_value(cards);
-        stats.cards_under_a_tenth_tix = Self::calc_cards_under_tix(0.1, cards);
-        stats.cards_over_5_tix = Self::calc_cards_over_tix(5.0, cards);
-        stats.rarity_distribution = Some(Self::calc_rarity_distribution(cards));
+        let sources = price_source::source_names(cards);
+        stats.calc_total_cards(&only_cards);
+        stats.calc_most_expensive_item(
+            &cards
+                .iter()
+                .filter(|c| c.category() != ItemCategory::EventTicket)
+                .cloned()
+                .collect::<Vec<MtgoCard>>(),
+            &sources,
+        );
+        stats.calc_total_value(&only_cards, &sources);
+        stats.cards_under_a_tenth_tix = Self::calc_cards_under_tix(0.1, &only_cards);
+        stats.cards_over_5_tix = Self::calc_cards_over_tix(5.0, &only_cards);
+        stats.rarity_distribution = Some(Self::calc_rarity_distribution(&only_cards));
+        // The per card stats are valued at the source the cards can be sold for the most at,
+        // the sell prices of a custom price list are what the user asks, not what the cards are worth
+        let buy_sources: Vec<Box<str>> = sources
+            .iter()
+            .filter(|source| PriceSide::of(source) == PriceSide::Buy)
+            .cloned()
+            .collect();
+        let valuation_source =
+            &buy_sources[Self::max_index(Self::sum_values(&only_cards, &buy_sources).into_iter())];
+        stats.calc_value_distribution(&only_cards, valuation_source);
+        stats.calc_top_holdings(&only_cards, valuation_source);
+        stats.calc_format_legality(&only_cards, valuation_source);
+        stats.calc_sealed(&sealed, &sources);
+        stats.calc_net_worth(&only_cards, &sealed, &sources);
         stats
     }
 
@@ -52,47 +131,31 @@ impl CollectionStats {
         self.set_total_cards(cards.len(), quantity_count as usize);
     }
 
-    fn calc_most_expensive_item(&mut self, cards: &[MtgoCard]) {
-        let gb_most_expensive = cards
-            .iter()
-            .max_by(|a, b| a.goatbots_price.partial_cmp(&b.goatbots_price).unwrap())
-            .unwrap_or_else(|| panic!("No cards in collection!"));
-        let scryfall_most_expensive = cards
+    fn calc_most_expensive_item(&mut self, cards: &[MtgoCard], sources: &[Box<str>]) {
+        if cards.is_empty() {
+            return;
+        }
+        // The most expensive item at each source, with its price
+        let most_expensive: Vec<(&MtgoCard, f32)> = sources
             .iter()
-            .max_by(|a, b| {
-                a.scryfall_price
-                    .partial_cmp(&b.scryfall_price)
-                    .unwrap_or(std::cmp::Ordering::Equal)
+            .map(|source| {
+                cards
+                    .iter()
+                    .map(|c| (c, c.price(source).unwrap_or_default()))
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                    .unwrap_or_else(|| panic!("No cards in collection!"))
             })
-            .unwrap_or_else(|| panic!("No cards in collection!"));
+            .collect();
+        let top_idx = Self::max_index(most_expensive.iter().map(|(_, price)| *price as f64));
 
-        let descriptions: Vec<String> = if gb_most_expensive.goatbots_price
-            > scryfall_most_expensive.scryfall_price.unwrap_or(0.)
-        {
-            vec![
-                gb_most_expensive.name.to_string(),
-                format!(
-                    "@C2@.{price} tix @Goatbots",
-                    price = gb_most_expensive.goatbots_price
-                ),
-                format!(
-                    "@C3@.{price} tix @Cardhoarder",
-                    price = scryfall_most_expensive.scryfall_price.unwrap_or_default()
-                ),
-            ]
-        } else {
-            vec![
-                scryfall_most_expensive.name.to_string(),
-                format!(
-                    "@C3@.{price} tix @Goatbots",
-                    price = gb_most_expensive.goatbots_price
-                ),
-                format!(
-                    "@C2@.{price} tix @Cardhoarder",
-                    price = scryfall_most_expensive.scryfall_price.unwrap_or_default()
-                ),
-            ]
-        };
+        let mut descriptions = vec![most_expensive[top_idx].0.name.to_string()];
+        for (idx, (source, (_, price))) in sources.iter().zip(&most_expensive).enumerate() {
+            descriptions.push(format!(
+                "{color}@.{price} @{source}",
+                color = if idx == top_idx { "@C2" } else { "@C3" },
+                price = self.money.format(*price as f64, 3)
+            ));
+        }
 
         self.most_expensive_item = Some(MultiValueStat::new(
             "Most expensive item".to_string(),
@@ -100,31 +163,75 @@ impl CollectionStats {
         ));
     }
 
-    fn calc_total_value(&mut self, cards: &[MtgoCard]) {
-        let total_gb_value = cards
+    fn calc_total_value(&mut self, cards: &[MtgoCard], sources: &[Box<str>]) {
+        let values = Self::sum_values(cards, sources);
+        self.total_value = Some(self.value_stat("Card value", sources, &values));
+    }
+
+    fn calc_sealed(&mut self, sealed: &[MtgoCard], sources: &[Box<str>]) {
+        let quantity_count = sealed.iter().fold(0, |acc, item| acc + item.quantity);
+        self.sealed_items = UniqueTotal::new(sealed.len(), quantity_count as usize);
+        let values = Self::sum_values(sealed, sources);
+        self.sealed_value = Some(self.value_stat("Sealed value", sources, &values));
+    }
+
+    /// Net worth is the value of the cards and sealed product, plus the Event Tickets at 1 tix each
+    fn calc_net_worth(&mut self, cards: &[MtgoCard], sealed: &[MtgoCard], sources: &[Box<str>]) {
+        let tickets = self.event_tickets as f64;
+        let values: Vec<f64> = Self::sum_values(cards, sources)
+            .into_iter()
+            .zip(Self::sum_values(sealed, sources))
+            .map(|(cards_value, sealed_value)| cards_value + sealed_value + tickets)
+            .collect();
+        self.net_worth = Some(self.value_stat("Net worth", sources, &values));
+    }
+
+    /// Sum the value of the given items at each of the price sources
+    fn sum_values(items: &[MtgoCard], sources: &[Box<str>]) -> Vec<f64> {
+        sources
             .iter()
-            .map(|card| card.goatbots_price as f64 * card.quantity as f64)
-            .sum::<f64>();
-        let total_scryfall_value = cards.iter().fold(0., |acc, card| {
-            acc + card
-                .scryfall_price
-                .map_or(0., |price| price as f64 * card.quantity as f64)
-        });
+            .map(|source| {
+                items.iter().fold(0., |acc, item| {
+                    acc + item
+                        .price(source)
+                        .map_or(0., |price| price as f64 * item.quantity as f64)
+                })
+            })
+            .collect()
+    }
 
-        self.total_value = Some(MultiValueStat::new(
-            "Total value".to_string(),
-            if total_gb_value > total_scryfall_value {
-                vec![
-                    format!("@C2@.{:.2} tix @Goatbots", total_gb_value),
-                    format!("@C3@.{:.2} tix @Cardhoarder", total_scryfall_value),
-                ]
-            } else {
-                vec![
-                    format!("@C3@.{:.2} tix @Goatbots", total_gb_value),
-                    format!("@C2@.{:.2} tix @Cardhoarder", total_scryfall_value),
-                ]
-            },
-        ));
+    /// Index of the highest value, the first one if several are equal
+    fn max_index(values: impl Iterator<Item = f64>) -> usize {
+        values
+            .enumerate()
+            .fold((0, f64::MIN), |(max_idx, max), (idx, value)| {
+                if value > max {
+                    (idx, value)
+                } else {
+                    (max_idx, max)
+                }
+            })
+            .0
+    }
+
+    /// Create a [MultiValueStat] with the value at each price source, highlighting the highest value
+    fn value_stat(&self, title: &str, sources: &[Box<str>], values: &[f64]) -> MultiValueStat {
+        let top_idx = Self::max_index(values.iter().copied());
+        MultiValueStat::new(
+            title.to_string(),
+            sources
+                .iter()
+                .zip(values)
+                .enumerate()
+                .map(|(idx, (source, value))| {
+                    format!(
+                        "{color}@.{value} @{source}",
+                        color = if idx == top_idx { "@C2" } else { "@C3" },
+                        value = self.money.format(*value, 2)
+                    )
+                })
+                .collect(),
+        )
     }
 
     fn calc_cards_under_tix(price: f32, cards: &[MtgoCard]) -> UniqueTotal {
@@ -196,6 +303,152 @@ impl CollectionStats {
         )
     }
 
+    /// Group the cards by their price at the given source into the [VALUE_BUCKETS](Self::VALUE_BUCKETS),
+    /// with the number of cards and their total value in each bucket
+    fn calc_value_distribution(&mut self, cards: &[MtgoCard], source: &str) {
+        let bucket_count = Self::VALUE_BUCKETS.len() + 1;
+        let mut counts = vec![UniqueTotal::default(); bucket_count];
+        let mut values = vec![0.; bucket_count];
+        for card in cards {
+            let bucket = Self::VALUE_BUCKETS
+                .iter()
+                .position(|upper| card.price(source).unwrap_or_default() < *upper)
+                .unwrap_or(Self::VALUE_BUCKETS.len());
+            counts[bucket] = UniqueTotal::new(
+                counts[bucket].unique() + 1,
+                counts[bucket].total() + card.quantity as usize,
+            );
+            values[bucket] += Self::holding_value(card, source);
+        }
+
+        let mut distribution =
+            CategoryStat::new(format!("Value distribution ({source})"), Vec::new());
+        for (idx, (count, value)) in counts.into_iter().zip(values).enumerate() {
+            let range = match idx {
+                0 => format!("< {}", Self::VALUE_BUCKETS[0]),
+                idx if idx == Self::VALUE_BUCKETS.len() => {
+                    format!(">= {}", Self::VALUE_BUCKETS[idx - 1])
+                }
+                idx => format!(
+                    "{} - {}",
+                    Self::VALUE_BUCKETS[idx - 1],
+                    Self::VALUE_BUCKETS[idx]
+                ),
+            };
+            distribution.add_value_pair(
+                format!("{range} tix"),
+                format!(
+                    "{unique} ({total})  {value}",
+                    unique = count.unique(),
+                    total = count.total(),
+                    value = self.money.format(value, 2)
+                ),
+            );
+        }
+        self.value_distribution = Some(distribution);
+    }
+
+    /// Find the most valuable holdings, i.e. the cards with the highest price at the given source times quantity,
+    /// and the share of the card value held by the [CONCENTRATION_TOP_N](Self::CONCENTRATION_TOP_N) most valuable holdings
+    fn calc_top_holdings(&mut self, cards: &[MtgoCard], source: &str) {
+        if cards.is_empty() {
+            return;
+        }
+        let mut holdings: Vec<(&MtgoCard, f64)> = cards
+            .iter()
+            .map(|card| (card, Self::holding_value(card, source)))
+            .collect();
+        holdings.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        self.top_holdings = Some(MultiValueStat::new(
+            format!("Top {} holdings ({source})", Self::TOP_HOLDINGS),
+            holdings
+                .iter()
+                .take(Self::TOP_HOLDINGS)
+                .map(|(card, value)| {
+                    format!(
+                        "@.{value} {quantity}x {name}",
+                        value = self.money.format(*value, 2),
+                        quantity = card.quantity,
+                        name = card.name
+                    )
+                })
+                .collect(),
+        ));
+
+        let total_value: f64 = holdings.iter().map(|(_, value)| value).sum();
+        if total_value <= 0. {
+            return;
+        }
+        self.value_concentration = Some(CategoryStat::new(
+            format!("Value concentration ({source})"),
+            Self::CONCENTRATION_TOP_N
+                .iter()
+                .map(|top_n| {
+                    let top_value: f64 = holdings.iter().take(*top_n).map(|(_, value)| value).sum();
+                    (
+                        format!("Top {top_n}"),
+                        format!("{percent:.1}%", percent = top_value / total_value * 100.),
+                    )
+                })
+                .collect(),
+        ));
+    }
+
+    /// Count the cards playable in each of the [FORMATS], and their share of the card value at the given source
+    ///
+    /// Not set if none of the cards have legalities, e.g. if the Scryfall data could not be loaded.
+    fn calc_format_legality(&mut self, cards: &[MtgoCard], source: &str) {
+        if cards.iter().all(|card| card.legalities.is_empty()) {
+            return;
+        }
+        let total_value: f64 = cards
+            .iter()
+            .map(|card| Self::holding_value(card, source))
+            .sum();
+        let value_pairs = FORMATS
+            .iter()
+            .map(|(format, name)| {
+                let (count, value) = cards
+                    .iter()
+                    .filter(|card| card.legality(format).is_some_and(|l| l.is_playable()))
+                    .fold((UniqueTotal::default(), 0.), |(count, value), card| {
+                        (
+                            UniqueTotal::new(
+                                count.unique() + 1,
+                                count.total() + card.quantity as usize,
+                            ),
+                            value + Self::holding_value(card, source),
+                        )
+                    });
+                let share = if total_value > 0. {
+                    value / total_value * 100.
+                } else {
+                    0.
+                };
+                (
+                    name.to_string(),
+                    format!(
+                        "{unique} ({total})  {value} ({share:.1}%)",
+                        unique = count.unique(),
+                        total = count.total(),
+                        value = self.money.format(value, 2)
+                    ),
+                )
+            })
+            .collect();
+        self.format_legality = Some(CategoryStat::new(
+            format!("Format legality ({source})"),
+            value_pairs,
+        ));
+    }
+
+    /// The value of all copies of the card at the given price source, 0 if the source has no price for it
+    fn holding_value(card: &MtgoCard, source: &str) -> f64 {
+        card.price(source)
+            .map_or(0., |price| price as f64 * card.quantity as f64)
+    }
+
     pub fn set_total_cards(&mut self, total_unique_cards: usize, total_card_quantity: usize) {
         self.total_cards = UniqueTotal::new(total_unique_cards, total_card_quantity);
     }
@@ -241,6 +494,39 @@ impl CollectionStats {
     pub fn take_most_expensive_item(&mut self) -> Option<MultiValueStat> {
         self.most_expensive_item.take()
     }
+
+    pub fn take_value_distribution(&mut self) -> Option<CategoryStat> {
+        self.value_distribution.take()
+    }
+
+    pub fn take_top_holdings(&mut self) -> Option<MultiValueStat> {
+        self.top_holdings.take()
+    }
+
+    pub fn take_value_concentration(&mut self) -> Option<CategoryStat> {
+        self.value_concentration.take()
+    }
+
+    pub fn take_format_legality(&mut self) -> Option<CategoryStat> {
+        self.format_legality.take()
+    }
+
+    /// The number of Event Tickets in the collection, i.e. the liquid tix
+    pub fn event_tickets(&self) -> u32 {
+        self.event_tickets
+    }
+
+    pub fn sealed_items(&self) -> UniqueTotal {
+        self.sealed_items
+    }
+
+    pub fn take_sealed_value(&mut self) -> Option<MultiValueStat> {
+        self.sealed_value.take()
+    }
+
+    pub fn take_net_worth(&mut self) -> Option<MultiValueStat> {
+        self.net_worth.take()
+    }
 }
 
 impl Default for CollectionStats {
@@ -248,3 +534,282 @@ impl Default for CollectionStats {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_tickets_and_sealed_are_separate_from_cards() {
+        let cards = vec![
+            MtgoCard {
+                id: MtgoCard::EVENT_TICKET_ID,
+                quantity: 391,
+                name: "Event Ticket".into(),
+                rarity: Rarity::None,
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 74160,
+                quantity: 3,
+                name: "Modern Horizons 3 Booster".into(),
+                rarity: Rarity::Booster,
+                goatbots_price: 4.5,
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 235,
+                quantity: 10,
+                name: "Swamp".into(),
+                rarity: Rarity::Common,
+                goatbots_price: 0.01,
+                ..Default::default()
+            },
+        ];
+
+        let mut stats = CollectionStats::from_cards(&cards);
+
+        assert_eq!(stats.event_tickets(), 391);
+        assert_eq!(stats.sealed_items().unique(), 1);
+        assert_eq!(stats.sealed_items().total(), 3);
+        assert_eq!(stats.total_cards().unique(), 1);
+        assert_eq!(stats.total_cards().total(), 10);
+        assert_eq!(
+            stats.take_net_worth().unwrap().take_values(),
+            vec![
+                "@C2@.404.60 tix @Goatbots".to_string(),
+                "@C3@.391.00 tix @Cardhoarder".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value_stats_include_additional_price_sources() {
+        let mut swamp = MtgoCard {
+            id: 235,
+            quantity: 10,
+            name: "Swamp".into(),
+            goatbots_price: 0.01,
+            scryfall_price: Some(0.02),
+            ..Default::default()
+        };
+        swamp.prices.insert("Manatraders".into(), 0.05);
+
+        let mut stats = CollectionStats::from_cards(&[swamp]);
+
+        assert_eq!(
+            stats.take_total_value().unwrap().take_values(),
+            vec![
+                "@C3@.0.10 tix @Goatbots".to_string(),
+                "@C3@.0.20 tix @Cardhoarder".to_string(),
+                "@C2@.0.50 tix @Manatraders".to_string()
+            ]
+        );
+        assert_eq!(
+            stats.take_most_expensive_item().unwrap().take_values(),
+            vec![
+                "Swamp".to_string(),
+                "@C3@.0.010 tix @Goatbots".to_string(),
+                "@C3@.0.020 tix @Cardhoarder".to_string(),
+                "@C2@.0.050 tix @Manatraders".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value_stats_in_display_currency() {
+        use crate::appdata::currency::{Currency, ExchangeRates};
+
+        let mut rates = ExchangeRates::default();
+        rates.set_rate(Currency::Usd, 0.5, chrono::Utc::now());
+        let money = rates.money_at(Currency::Usd, chrono::Utc::now());
+        let swamp = MtgoCard {
+            id: 235,
+            quantity: 10,
+            name: "Swamp".into(),
+            goatbots_price: 1.,
+            scryfall_price: Some(2.),
+            ..Default::default()
+        };
+
+        let mut stats = CollectionStats::from_cards_in(&[swamp], money);
+
+        assert_eq!(
+            stats.take_total_value().unwrap().take_values(),
+            vec![
+                "@C3@.5.00 USD @Goatbots".to_string(),
+                "@C2@.10.00 USD @Cardhoarder".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value_distribution_and_concentration() {
+        let mut cards = vec![
+            MtgoCard {
+                id: 10,
+                quantity: 1,
+                name: "Ragavan, Nimble Pilferer".into(),
+                rarity: Rarity::Mythic,
+                goatbots_price: 60.,
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 11,
+                quantity: 4,
+                name: "Force of Will".into(),
+                rarity: Rarity::Mythic,
+                goatbots_price: 15.,
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 12,
+                quantity: 4,
+                name: "Counterspell".into(),
+                rarity: Rarity::Common,
+                goatbots_price: 0.05,
+                ..Default::default()
+            },
+        ];
+        cards.extend((0..20).map(|i| MtgoCard {
+            id: 100 + i,
+            quantity: 1,
+            name: "Island".into(),
+            rarity: Rarity::Common,
+            goatbots_price: 0.5,
+            ..Default::default()
+        }));
+
+        let mut stats = CollectionStats::from_cards(&cards);
+
+        assert_eq!(
+            stats.take_value_distribution().unwrap().take_value_pairs(),
+            vec![
+                ("< 0.1 tix".to_string(), "1 (4)  0.20 tix".to_string()),
+                ("0.1 - 1 tix".to_string(), "20 (20)  10.00 tix".to_string()),
+                ("1 - 5 tix".to_string(), "0 (0)  0.00 tix".to_string()),
+                ("5 - 20 tix".to_string(), "1 (4)  60.00 tix".to_string()),
+                ("20 - 50 tix".to_string(), "0 (0)  0.00 tix".to_string()),
+                (">= 50 tix".to_string(), "1 (1)  60.00 tix".to_string()),
+            ]
+        );
+        let top_holdings = stats.take_top_holdings().unwrap().take_values();
+        assert_eq!(top_holdings.len(), CollectionStats::TOP_HOLDINGS);
+        assert_eq!(top_holdings[0], "@.60.00 tix 1x Ragavan, Nimble Pilferer");
+        assert_eq!(top_holdings[1], "@.60.00 tix 4x Force of Will");
+        assert_eq!(top_holdings[2], "@.0.50 tix 1x Island");
+        // 120 of the 130.2 tix are in the top 2, the top 10 adds 8 Islands
+        assert_eq!(
+            stats.take_value_concentration().unwrap().take_value_pairs(),
+            vec![
+                ("Top 10".to_string(), "95.2%".to_string()),
+                ("Top 50".to_string(), "100.0%".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_card_stats_valued_at_highest_source() {
+        let mut cards = vec![
+            MtgoCard {
+                id: 10,
+                quantity: 1,
+                name: "Ragavan, Nimble Pilferer".into(),
+                rarity: Rarity::Mythic,
+                goatbots_price: 40.,
+                scryfall_price: Some(60.),
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 11,
+                quantity: 1,
+                name: "Force of Will".into(),
+                rarity: Rarity::Mythic,
+                goatbots_price: 30.,
+                scryfall_price: Some(20.),
+                ..Default::default()
+            },
+        ];
+        // The user's own sell prices are never used to value the collection
+        for card in &mut cards {
+            card.prices.insert("Our Bot sell".into(), 100.);
+        }
+
+        let mut stats = CollectionStats::from_cards(&cards);
+
+        let mut top_holdings = stats.take_top_holdings().unwrap();
+        assert_eq!(top_holdings.title(), "Top 10 holdings (Cardhoarder)");
+        assert_eq!(
+            top_holdings.take_values(),
+            vec![
+                "@.60.00 tix 1x Ragavan, Nimble Pilferer".to_string(),
+                "@.20.00 tix 1x Force of Will".to_string(),
+            ]
+        );
+        let mut distribution = stats.take_value_distribution().unwrap();
+        assert_eq!(distribution.title(), "Value distribution (Cardhoarder)");
+        assert_eq!(
+            distribution.take_value_pairs()[3..],
+            [
+                ("5 - 20 tix".to_string(), "0 (0)  0.00 tix".to_string()),
+                ("20 - 50 tix".to_string(), "1 (1)  20.00 tix".to_string()),
+                (">= 50 tix".to_string(), "1 (1)  60.00 tix".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_legality() {
+        use mtgoupdater::scryfall::Legality;
+
+        let mut force_of_will = MtgoCard {
+            id: 11,
+            quantity: 4,
+            name: "Force of Will".into(),
+            goatbots_price: 15.,
+            ..Default::default()
+        };
+        force_of_will.legalities.extend([
+            ("legacy".into(), Legality::Legal),
+            ("vintage".into(), Legality::Legal),
+            ("modern".into(), Legality::NotLegal),
+        ]);
+        let mut counterspell = MtgoCard {
+            id: 12,
+            quantity: 4,
+            name: "Counterspell".into(),
+            goatbots_price: 5.,
+            ..Default::default()
+        };
+        counterspell.legalities.extend([
+            ("legacy".into(), Legality::Legal),
+            ("vintage".into(), Legality::Restricted),
+            ("pauper".into(), Legality::Legal),
+        ]);
+
+        let mut stats = CollectionStats::from_cards(&[force_of_will.clone(), counterspell]);
+
+        assert_eq!(
+            stats.take_format_legality().unwrap().take_value_pairs(),
+            vec![
+                ("Standard".to_string(), "0 (0)  0.00 tix (0.0%)".to_string()),
+                ("Pioneer".to_string(), "0 (0)  0.00 tix (0.0%)".to_string()),
+                ("Modern".to_string(), "0 (0)  0.00 tix (0.0%)".to_string()),
+                (
+                    "Legacy".to_string(),
+                    "2 (8)  80.00 tix (100.0%)".to_string()
+                ),
+                (
+                    "Vintage".to_string(),
+                    "2 (8)  80.00 tix (100.0%)".to_string()
+                ),
+                ("Pauper".to_string(), "1 (4)  20.00 tix (25.0%)".to_string()),
+            ]
+        );
+
+        force_of_will.legalities.clear();
+        let mut stats = CollectionStats::from_cards(&[force_of_will]);
+        assert!(stats.take_format_legality().is_none());
+    }
+}
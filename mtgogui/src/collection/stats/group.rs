@@ -0,0 +1,229 @@
+//! Grouping of the collection by set, rarity or foil/non-foil with subtotals for each group.
+
+use std::collections::BTreeMap;
+
+use mtgoupdater::mtgo_card::MtgoCard;
+
+use super::util::UniqueTotal;
+
+/// The attribute to group the collection by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Set,
+    Rarity,
+    Foil,
+}
+
+impl GroupBy {
+    /// Returns the label used to describe the grouping, e.g. in window titles
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupBy::Set => "Set",
+            GroupBy::Rarity => "Rarity",
+            GroupBy::Foil => "Foil",
+        }
+    }
+
+    /// Returns the key of the group the given card belongs to
+    fn key(&self, card: &MtgoCard) -> String {
+        match self {
+            GroupBy::Set => {
+                if card.set.is_empty() {
+                    "No set".to_string()
+                } else {
+                    card.set.to_string()
+                }
+            }
+            GroupBy::Rarity => card.rarity.to_string(),
+            GroupBy::Foil => {
+                if card.foil {
+                    "Foil".to_string()
+                } else {
+                    "Non-foil".to_string()
+                }
+            }
+        }
+    }
+}
+
+/// A group of cards with subtotals for the unique count, the total quantity and the value at each vendor
+#[derive(Debug, Clone)]
+pub struct CardGroup {
+    key: String,
+    items: UniqueTotal,
+    goatbots_value: f64,
+    cardhoarder_value: f64,
+    cards: Vec<MtgoCard>,
+}
+
+impl CardGroup {
+    fn new(key: String) -> Self {
+        Self {
+            key,
+            items: UniqueTotal::default(),
+            goatbots_value: 0.,
+            cardhoarder_value: 0.,
+            cards: Vec::new(),
+        }
+    }
+
+    fn add_card(&mut self, card: &MtgoCard) {
+        self.items = UniqueTotal::new(
+            self.items.unique() + 1,
+            self.items.total() + card.quantity as usize,
+        );
+        self.goatbots_value += card.goatbots_price as f64 * card.quantity as f64;
+        self.cardhoarder_value += card
+            .scryfall_price
+            .map_or(0., |price| price as f64 * card.quantity as f64);
+        self.cards.push(card.clone());
+    }
+
+    /// The key (e.g. set code or rarity) that all cards in the group share
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The number of unique cards and the total quantity of cards in the group
+    pub fn items(&self) -> UniqueTotal {
+        self.items
+    }
+
+    /// The value of the group at Goatbots prices
+    pub fn goatbots_value(&self) -> f64 {
+        self.goatbots_value
+    }
+
+    /// The value of the group at Cardhoarder prices
+    pub fn cardhoarder_value(&self) -> f64 {
+        self.cardhoarder_value
+    }
+
+    /// The cards in the group sorted by Goatbots value (price * quantity) in descending order
+    pub fn cards(&self) -> &[MtgoCard] {
+        &self.cards
+    }
+}
+
+/// Group the given cards by the given attribute
+///
+/// # Arguments
+///
+/// * `cards` - A borrowed slice of cards to group
+/// * `group_by` - The attribute to group the cards by
+///
+/// # Returns
+///
+/// The groups sorted by Goatbots value in descending order, so the groups that hold the most value come first
+pub fn group_cards(cards: &[MtgoCard], group_by: GroupBy) -> Vec<CardGroup> {
+    let mut groups: BTreeMap<String, CardGroup> = BTreeMap::new();
+
+    for card in cards {
+        let key = group_by.key(card);
+        groups
+            .entry(key.clone())
+            .or_insert_with(|| CardGroup::new(key))
+            .add_card(card);
+    }
+
+    let mut groups: Vec<CardGroup> = groups.into_values().collect();
+    for group in &mut groups {
+        group.cards.sort_by(|a, b| {
+            let a_value = a.goatbots_price * a.quantity as f32;
+            let b_value = b.goatbots_price * b.quantity as f32;
+            b_value
+                .partial_cmp(&a_value)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+    groups.sort_by(|a, b| {
+        b.goatbots_value
+            .partial_cmp(&a.goatbots_value)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mtgoupdater::mtgo_card::Rarity;
+    use pretty_assertions::assert_eq;
+
+    fn card(
+        id: u32,
+        quantity: u32,
+        set: &str,
+        rarity: Rarity,
+        foil: bool,
+        goatbots_price: f32,
+        scryfall_price: Option<f32>,
+    ) -> MtgoCard {
+        MtgoCard {
+            id,
+            quantity,
+            name: format!("Card {id}").into(),
+            set: set.into(),
+            rarity,
+            foil,
+            goatbots_price,
+            scryfall_price,
+            ..Default::default()
+        }
+    }
+
+    fn test_cards() -> Vec<MtgoCard> {
+        vec![
+            card(1, 4, "MH2", Rarity::Rare, false, 1.0, Some(1.5)),
+            card(2, 1, "MH2", Rarity::Mythic, true, 10.0, None),
+            card(3, 10, "LCI", Rarity::Common, false, 0.01, Some(0.02)),
+            card(4, 2, "LCI", Rarity::Rare, false, 0.5, Some(0.25)),
+        ]
+    }
+
+    #[test]
+    fn test_group_by_set() {
+        let groups = group_cards(&test_cards(), GroupBy::Set);
+
+        assert_eq!(groups.len(), 2);
+        // MH2 holds the most value so it comes first
+        assert_eq!(groups[0].key(), "MH2");
+        assert_eq!(groups[0].items().unique(), 2);
+        assert_eq!(groups[0].items().total(), 5);
+        assert!((groups[0].goatbots_value() - 14.0).abs() < 1e-6);
+        assert!((groups[0].cardhoarder_value() - 6.0).abs() < 1e-6);
+        // Most valuable card in the group first
+        assert_eq!(groups[0].cards()[0].id, 2);
+
+        assert_eq!(groups[1].key(), "LCI");
+        assert_eq!(groups[1].items().unique(), 2);
+        assert_eq!(groups[1].items().total(), 12);
+        assert!((groups[1].goatbots_value() - 1.1).abs() < 1e-6);
+        assert!((groups[1].cardhoarder_value() - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_group_by_rarity() {
+        let groups = group_cards(&test_cards(), GroupBy::Rarity);
+
+        let keys: Vec<&str> = groups.iter().map(|g| g.key()).collect();
+        assert_eq!(keys, vec!["Mythic", "Rare", "Common"]);
+        assert_eq!(groups[1].items().unique(), 2);
+        assert_eq!(groups[1].items().total(), 6);
+    }
+
+    #[test]
+    fn test_group_by_foil() {
+        let groups = group_cards(&test_cards(), GroupBy::Foil);
+
+        let keys: Vec<&str> = groups.iter().map(|g| g.key()).collect();
+        assert_eq!(keys, vec!["Foil", "Non-foil"]);
+        assert_eq!(groups[0].items().total(), 1);
+        assert_eq!(groups[1].items().total(), 16);
+    }
+
+    #[test]
+    fn test_group_empty() {
+        assert!(group_cards(&[], GroupBy::Set).is_empty());
+    }
+}
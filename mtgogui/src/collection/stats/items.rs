@@ -1,6 +1,8 @@
 use std::vec::Drain;
 
-use crate::appdata::metadata::MetaData;
+use chrono::{DateTime, Utc};
+
+use crate::appdata::metadata::{self, MetaData};
 
 use super::{
     container::CollectionStats,
@@ -165,7 +167,13 @@ impl TryFrom<CollectionStats> for BrowserItems {
 
     fn try_from(mut stats: CollectionStats) -> Result<Self, Self::Error> {
         let mut browser_items = BrowserItems::new();
-        browser_items.add_item_unique_total("Total items", stats.total_cards());
+        if let Some(net_worth_stat_val) = stats.take_net_worth() {
+            browser_items.add_multi_value_item(net_worth_stat_val);
+        } else {
+            return Err("No net worth stat set".into());
+        }
+        browser_items.add_item("Event Tickets", &format!("{} tix", stats.event_tickets()));
+        browser_items.add_item_unique_total("Total cards", stats.total_cards());
 
         if let Some(tot_stat_val) = stats.take_total_value() {
             browser_items.add_multi_value_item(tot_stat_val);
@@ -173,10 +181,16 @@ impl TryFrom<CollectionStats> for BrowserItems {
             return Err("No total value stat set".into());
         }
 
+        browser_items.add_item_unique_total("Sealed product", stats.sealed_items());
+        if let Some(sealed_value_stat_val) = stats.take_sealed_value() {
+            browser_items.add_multi_value_item(sealed_value_stat_val);
+        } else {
+            return Err("No sealed value stat set".into());
+        }
+
+        // Not set if the collection only contains Event Tickets
         if let Some(most_expensive_item_stat_val) = stats.take_most_expensive_item() {
             browser_items.add_multi_value_item(most_expensive_item_stat_val);
-        } else {
-            return Err("No most expensive item stat set".into());
         }
         browser_items.add_item_unique_total("Cards > 5 tix", stats.cards_over_5_tix());
         browser_items.add_item_unique_total("Cards < 0.1 tix", stats.cards_under_a_tenth_tix());
@@ -185,10 +199,42 @@ impl TryFrom<CollectionStats> for BrowserItems {
         } else {
             return Err("No rarity distribution stat set".into());
         }
+        if let Some(value_distribution_stat_val) = stats.take_value_distribution() {
+            browser_items.add_category_item(value_distribution_stat_val);
+        }
+        // Not set if the collection has no cards
+        if let Some(top_holdings_stat_val) = stats.take_top_holdings() {
+            browser_items.add_multi_value_item(top_holdings_stat_val);
+        }
+        // Not set if the cards have no value
+        if let Some(concentration_stat_val) = stats.take_value_concentration() {
+            browser_items.add_category_item(concentration_stat_val);
+        }
+        // Not set without Scryfall legalities
+        if let Some(format_legality_stat_val) = stats.take_format_legality() {
+            browser_items.add_category_item(format_legality_stat_val);
+        }
         Ok(browser_items)
     }
 }
 
+impl BrowserItems {
+    /// Format the age of a data source, colored green if it was updated within a day, yellow within a week, and red otherwise
+    fn format_staleness(updated_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        let age_days = now.signed_duration_since(updated_at).num_days();
+        let color = match age_days {
+            0 => "@C2",
+            1..=6 => "@C3",
+            _ => "@C1",
+        };
+        format!(
+            "{color}@.{age} ({date})",
+            age = metadata::format_age(updated_at, now),
+            date = updated_at.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
+
 impl TryFrom<MetaData> for BrowserItems {
     type Error = String;
 
@@ -196,16 +242,21 @@ impl TryFrom<MetaData> for BrowserItems {
         log::info!("Converting metadata to browser items");
         let mut items = BrowserItems::new();
 
+        let now = Utc::now();
         let last_updated = CategoryStat::new(
             "Prices updated".into(),
             vec![
                 (
                     "Goatbots".into(),
-                    value.goatbots_prices_updated_at().to_string(),
+                    Self::format_staleness(value.goatbots_prices_updated_at(), now),
                 ),
                 (
                     "Cardhoarder".into(),
-                    value.scryfall_bulk_data_updated_at().to_string(),
+                    Self::format_staleness(value.scryfall_bulk_data_updated_at(), now),
+                ),
+                (
+                    "Card definitions".into(),
+                    Self::format_staleness(value.goatbots_card_definitions_updated_at(), now),
                 ),
             ],
         );
@@ -2,21 +2,30 @@ use std::sync::{Arc, Mutex};
 
 use crate::{
     collection::{
+        dek,
         view::table::{column::SortStates, CollectionTable, SortToggle},
         TableMessage,
     },
+    notification::{Notification, NotificationAction},
     Message, DEFAULT_APP_HEIGHT, MENU_BAR_HEIGHT,
 };
 use fltk::{
     app, button,
+    dialog::{FileDialog, FileDialogOptions, FileDialogType},
     enums::{self, Event},
     prelude::{GroupExt, WidgetBase, WidgetExt},
     widget_extends,
 };
 use fltk_flex::Flex;
+use mtgoupdater::mtgo_card::MtgoCard;
 use table::column::Column::*;
 
+pub mod arbitrage;
+pub mod card_detail;
+pub mod group;
+pub mod sell_list;
 pub mod table;
+pub mod value_history;
 
 const TABLE_WIDTH: i32 = 790;
 
@@ -95,8 +104,49 @@ pub fn set_collection_main_box(ev_send: app::Sender<Message>) -> table::Collecti
     flx_header.end();
 
     flx_table.fixed(&flx_header, 50);
-    let collection_table = table::CollectionTable::new(TABLE_WIDTH, 720, ev_send, sort_states);
+    let collection_table =
+        table::CollectionTable::new(TABLE_WIDTH, 720, ev_send, sort_states, flx_header);
     flx_table.end();
 
     collection_table
 }
+
+/// Let the user choose a destination and save the cards as a `.dek`-file
+///
+/// If saving fails, the user is notified with a [Message::Notify] that lets them retry the export.
+///
+/// # Arguments
+///
+/// * `cards` - The cards and the number of copies of each to save
+/// * `preset_file` - The file name suggested in the file dialog
+/// * `ev_send` - Sender to send messages to the main thread
+pub fn export_dek(
+    cards: Arc<[(MtgoCard, u32)]>,
+    preset_file: &str,
+    ev_send: &app::Sender<Message>,
+) {
+    let mut dlg = FileDialog::new(FileDialogType::BrowseSaveFile);
+    dlg.set_option(FileDialogOptions::SaveAsConfirm);
+    dlg.set_filter("MTGO deck\t*.dek");
+    dlg.set_preset_file(preset_file);
+    dlg.show();
+    let filename = dlg.filename();
+    if filename.as_os_str().is_empty() {
+        return;
+    }
+
+    if let Err(e) = dek::save_dek(
+        &filename,
+        cards.iter().map(|(card, quantity)| (card, *quantity)),
+    ) {
+        ev_send.send(
+            Notification::error("Failed to export .dek-file")
+                .with_details(format!("{filename:?}: {e}"))
+                .with_action(NotificationAction::retry(Message::ExportDek(
+                    cards,
+                    preset_file.into(),
+                )))
+                .into(),
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use fltk::{
+    app,
+    browser::Browser,
+    button::Button,
+    dialog,
+    frame::Frame,
+    input::FloatInput,
+    menu::Choice,
+    prelude::{BrowserExt, GroupExt, InputExt, MenuExt, WidgetBase, WidgetExt, WindowExt},
+    window::Window,
+};
+use fltk_flex::Flex;
+use mtgoupdater::{mtgo_card::MtgoCard, price_source::PriceSide};
+
+use crate::{
+    assets,
+    collection::arbitrage::{find_arbitrage, ArbitrageItem, SpreadThreshold},
+    util::center,
+    Message,
+};
+
+const WINDOW_WIDTH: i32 = 900;
+const WINDOW_HEIGHT: i32 = 600;
+
+/// Show the cards where the spread between the vendor prices is above a configurable threshold
+///
+/// The cards are sorted by the tix gained by selling at the better vendor,
+/// and the cards to sell at each vendor can be exported as a `.dek`-file.
+///
+/// # Arguments
+///
+/// * `cards` - A borrowed slice of the cards in the collection
+/// * `ev_send` - Sender to send messages to the main thread, e.g. to report a failed export
+pub fn show_arbitrage_view(cards: &[MtgoCard], ev_send: app::Sender<Message>) {
+    let sources: Rc<[Box<str>]> = mtgoupdater::price_source::source_names(cards).into();
+    let cards: Rc<[MtgoCard]> = cards.into();
+    let items: Rc<RefCell<Vec<ArbitrageItem>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let mut win = Window::default()
+        .with_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .with_pos(
+            center().0 - WINDOW_WIDTH / 2,
+            center().1 - WINDOW_HEIGHT / 2,
+        )
+        .with_label("Vendor arbitrage");
+    win.set_icon(Some(assets::get_logo()));
+    win.make_resizable(true);
+
+    let mut flx_main = Flex::default().size_of_parent().column();
+    flx_main.set_margin(5);
+
+    let mut flx_threshold = Flex::default().row();
+    let lbl_threshold = Frame::default().with_label("Minimum spread");
+    let mut choice_kind = Choice::default();
+    choice_kind.add_choice("Percent|Tix");
+    choice_kind.set_value(0);
+    let mut inp_threshold = FloatInput::default();
+    inp_threshold.set_value("20");
+    let mut btn_apply = Button::default().with_label("Apply");
+    flx_threshold.fixed(&lbl_threshold, 120);
+    flx_threshold.fixed(&choice_kind, 100);
+    flx_threshold.fixed(&inp_threshold, 80);
+    flx_threshold.fixed(&btn_apply, 80);
+    flx_threshold.end();
+    flx_main.fixed(&flx_threshold, 30);
+
+    let mut browser = Browser::default();
+    let column_widths: Vec<i32> = [300, 50]
+        .into_iter()
+        .chain(sources.iter().map(|_| 100))
+        .chain([110, 110, 100])
+        .collect();
+    browser.set_column_widths(&column_widths);
+    browser.set_column_char('\t');
+
+    let flx_export = Flex::default().row();
+    // Only the vendors that pay for cards can be the better vendor
+    let export_buttons: Vec<(Box<str>, Button)> = sources
+        .iter()
+        .filter(|source| PriceSide::of(source) == PriceSide::Buy)
+        .map(|source| {
+            let btn = Button::default().with_label(&format!("Export {source} .dek"));
+            (source.clone(), btn)
+        })
+        .collect();
+    flx_export.end();
+    flx_main.fixed(&flx_export, 30);
+
+    flx_main.end();
+    win.end();
+    win.show();
+
+    let mut refresh = {
+        let cards = cards.clone();
+        let items = items.clone();
+        let mut browser = browser.clone();
+        let sources = sources.clone();
+        let choice_kind = choice_kind.clone();
+        let inp_threshold = inp_threshold.clone();
+        move || {
+            let value = match inp_threshold.value().parse::<f32>() {
+                Ok(value) if value >= 0. => value,
+                _ => {
+                    dialog::alert(
+                        center().0 - 200,
+                        center().1 - 100,
+                        "The minimum spread must be a positive number",
+                    );
+                    return;
+                }
+            };
+            let threshold = if choice_kind.value() == 1 {
+                SpreadThreshold::Tix(value)
+            } else {
+                SpreadThreshold::Percent(value)
+            };
+
+            let found = find_arbitrage(&cards, threshold);
+            fill_browser(&mut browser, &found, &sources);
+            *items.borrow_mut() = found;
+        }
+    };
+    refresh();
+    btn_apply.set_callback(move |_| refresh());
+
+    for (source, mut btn) in export_buttons {
+        let items = items.clone();
+        let ev_send = ev_send.clone();
+        btn.set_callback(move |_| export_vendor_dek(&items.borrow(), &source, &ev_send));
+    }
+}
+
+/// Fill the browser with the arbitrage items, one line per item with a price column for each of the `sources`
+fn fill_browser(browser: &mut Browser, items: &[ArbitrageItem], sources: &[Box<str>]) {
+    browser.clear();
+    let source_titles: String = sources.iter().map(|s| format!("@b{s}\t")).collect();
+    browser.add(&format!(
+        "@bName\t@b#\t{source_titles}@bSpread\t@bSell at\t@bGained"
+    ));
+    for item in items {
+        let card = item.card();
+        let source_prices: String = sources
+            .iter()
+            .map(|s| match item.price_at(s) {
+                Some(price) => format!("{price:.3}\t"),
+                None => "N/A\t".to_string(),
+            })
+            .collect();
+        browser.add(&format!(
+            "{name}{foil}\t{quantity}\t{source_prices}{spread:.3} ({percent:.0}%)\t{vendor}\t{gained:.3}",
+            name = card.name,
+            foil = if card.foil { " (foil)" } else { "" },
+            quantity = card.quantity,
+            spread = item.spread_tix(),
+            percent = item.spread_percent(),
+            vendor = item.better_vendor(),
+            gained = item.tix_gained(),
+        ));
+    }
+}
+
+/// Let the user choose a destination and save the cards that sell best at the given vendor as a `.dek`-file
+fn export_vendor_dek(items: &[ArbitrageItem], vendor: &str, ev_send: &app::Sender<Message>) {
+    let vendor_cards: Arc<[(MtgoCard, u32)]> = items
+        .iter()
+        .filter(|item| item.better_vendor() == vendor)
+        .map(|item| (item.card().clone(), item.card().quantity))
+        .collect();
+    super::export_dek(
+        vendor_cards,
+        &format!("sell-to-{}.dek", vendor.to_lowercase().replace(' ', "-")),
+        ev_send,
+    );
+}
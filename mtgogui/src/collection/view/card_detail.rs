@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use fltk::{
+    browser::Browser,
+    enums::{Align, Font},
+    frame::Frame,
+    image::JpegImage,
+    prelude::{BrowserExt, DisplayExt, GroupExt, ImageExt, WidgetBase, WidgetExt, WindowExt},
+    text::{TextBuffer, TextDisplay, WrapMode},
+    window::Window,
+};
+use fltk_flex::Flex;
+use mtgoupdater::{mtgo_card::MtgoCard, scryfall::ScryfallCards};
+
+use crate::{appdata::currency::Money, assets, collection::card_detail, util::center};
+
+const WINDOW_WIDTH: i32 = 750;
+const WINDOW_HEIGHT: i32 = 600;
+const IMAGE_WIDTH: i32 = 250;
+const IMAGE_HEIGHT: i32 = 350;
+
+/// A window with the details of the card selected in the collection table
+///
+/// The window is reused for every selected card, and shown again if it was closed.
+pub struct CardDetailView {
+    win: Window,
+    title: Frame,
+    text: TextBuffer,
+    printings: Browser,
+    image: Frame,
+    /// Scryfall ID of the shown card, to ignore images of previously selected cards that arrive late
+    scryfall_id: Option<Box<str>>,
+}
+
+impl CardDetailView {
+    /// Create the (hidden) detail window
+    pub fn new() -> Self {
+        let mut win = Window::default()
+            .with_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+            .with_pos(
+                center().0 - WINDOW_WIDTH / 2,
+                center().1 - WINDOW_HEIGHT / 2,
+            )
+            .with_label("Card details");
+        win.set_icon(Some(assets::get_logo()));
+        win.make_resizable(true);
+
+        let mut flx_row = Flex::default().size_of_parent().row();
+        flx_row.set_margin(5);
+
+        let mut flx_image = Flex::default().column();
+        let image = Frame::default().with_align(Align::Center | Align::Inside);
+        flx_image.fixed(&image, IMAGE_HEIGHT);
+        flx_image.end();
+        flx_row.fixed(&flx_image, IMAGE_WIDTH);
+
+        let mut flx_main = Flex::default().column();
+
+        let mut title = Frame::default().with_align(Align::Left | Align::Inside);
+        title.set_label_font(Font::HelveticaBold);
+        title.set_label_size(18);
+        flx_main.fixed(&title, 30);
+
+        let text = TextBuffer::default();
+        let mut txt_disp = TextDisplay::default();
+        txt_disp.set_buffer(text.clone());
+        txt_disp.wrap_mode(WrapMode::AtBounds, 0);
+
+        let lbl_printings = Frame::default()
+            .with_label("Owned printings")
+            .with_align(Align::Left | Align::Inside);
+        flx_main.fixed(&lbl_printings, 25);
+
+        let mut printings = Browser::default();
+        printings.set_column_widths(&[70, 50, 50, 120, 120]);
+        printings.set_column_char('\t');
+        flx_main.fixed(&printings, 160);
+
+        flx_main.end();
+        flx_row.end();
+        win.end();
+
+        Self {
+            win,
+            title,
+            text,
+            printings,
+            image,
+            scryfall_id: None,
+        }
+    }
+
+    /// Show the details of the card and all the printings of it in the collection
+    ///
+    /// # Arguments
+    ///
+    /// * `card` - The selected card
+    /// * `cards` - All the cards in the collection
+    /// * `scryfall` - The Scryfall data to get the oracle data from, if loaded
+    /// * `money` - The currency and exchange rate to display the prices in
+    pub fn show(
+        &mut self,
+        card: &MtgoCard,
+        cards: &[MtgoCard],
+        scryfall: Option<&ScryfallCards>,
+        money: Money,
+    ) {
+        let details = scryfall.and_then(|s| s.get(card.id));
+        self.title.set_label(&card.name);
+        self.text.set_text(&card_detail::describe(card, details));
+        self.scryfall_id = details.filter(|d| !d.id.is_empty()).map(|d| d.id.clone());
+        self.image.set_image(None::<JpegImage>);
+        self.image.set_label(if self.scryfall_id.is_some() {
+            "Loading image..."
+        } else {
+            "No image"
+        });
+
+        self.printings.clear();
+        self.printings
+            .add("@bSet\t@bFoil\t@b#\t@bGoatbots\t@bCardhoarder");
+        for printing in card_detail::owned_printings(cards, &card.name) {
+            self.printings.add(&format!(
+                "{set}\t{foil}\t{quantity}\t{goatbots}\t{cardhoarder}",
+                set = printing.set,
+                foil = if printing.foil { "Yes" } else { "No" },
+                quantity = printing.quantity,
+                goatbots = money.format(printing.goatbots_price as f64, 3),
+                cardhoarder = match printing.scryfall_price {
+                    Some(price) => money.format(price as f64, 3),
+                    None => "N/A".to_string(),
+                },
+            ));
+        }
+
+        self.win.show();
+        self.win.redraw();
+    }
+
+    /// The Scryfall ID of the shown card, if known
+    pub fn scryfall_id(&self) -> Option<&str> {
+        self.scryfall_id.as_deref()
+    }
+
+    /// Show the image of the card with the given Scryfall ID, if it's still the shown card
+    ///
+    /// # Arguments
+    ///
+    /// * `scryfall_id` - The Scryfall ID of the card the image is of
+    /// * `path` - Path to the cached image, or [None] if it's not available
+    pub fn set_image(&mut self, scryfall_id: &str, path: Option<&Path>) {
+        if self.scryfall_id.as_deref() != Some(scryfall_id) {
+            return;
+        }
+        let image = path.map(JpegImage::load);
+        match image {
+            Some(Ok(mut image)) => {
+                image.scale(IMAGE_WIDTH, IMAGE_HEIGHT, true, true);
+                self.image.set_label("");
+                self.image.set_image(Some(image));
+            }
+            Some(Err(e)) => {
+                log::warn!("Failed to load card image of {scryfall_id}: {e}");
+                self.image.set_label("No image");
+            }
+            None => self.image.set_label("No image"),
+        }
+        self.image.redraw();
+    }
+}
+
+impl Default for CardDetailView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
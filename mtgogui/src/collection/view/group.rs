@@ -0,0 +1,105 @@
+use fltk::{
+    enums::{Color, Font, FrameType},
+    prelude::{GroupExt, WidgetBase, WidgetExt, WindowExt},
+    tree::{Tree, TreeConnectorStyle, TreeItem, TreeSelect},
+    window::Window,
+};
+use mtgoupdater::mtgo_card::MtgoCard;
+
+use crate::{
+    assets,
+    collection::stats::group::{group_cards, CardGroup, GroupBy},
+    util::center,
+};
+
+const WINDOW_WIDTH: i32 = 700;
+const WINDOW_HEIGHT: i32 = 600;
+
+/// Show the collection grouped by the given attribute as a collapsible tree in a pop-up window
+///
+/// Each group shows the unique count, total quantity, and value at Goatbots and Cardhoarder.
+/// Expanding a group shows the cards in the group sorted by their value.
+///
+/// # Arguments
+///
+/// * `cards` - A borrowed slice of the cards in the collection
+/// * `group_by` - The attribute to group the cards by
+pub fn show_group_view(cards: &[MtgoCard], group_by: GroupBy) {
+    let groups = group_cards(cards, group_by);
+
+    let mut win = Window::default()
+        .with_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .with_pos(
+            center().0 - WINDOW_WIDTH / 2,
+            center().1 - WINDOW_HEIGHT / 2,
+        )
+        .with_label(&format!("Collection grouped by {}", group_by.label()));
+    win.set_icon(Some(assets::get_logo()));
+    win.make_resizable(true);
+
+    let mut tree = Tree::default().size_of_parent();
+    tree.set_show_root(false);
+    tree.set_select_mode(TreeSelect::None);
+    tree.set_connector_style(TreeConnectorStyle::Solid);
+    tree.set_frame(FrameType::FlatBox);
+    tree.set_item_label_font(Font::Courier);
+    tree.set_item_label_fgcolor(Color::White);
+
+    if let Some(root) = tree.root() {
+        for (idx, group) in groups.iter().enumerate() {
+            add_group(&mut tree, &root, idx as i32, group);
+        }
+    }
+
+    win.end();
+    win.show();
+}
+
+/// Add a group and its cards as children of the given parent item, the group is added collapsed
+fn add_group(tree: &mut Tree, parent: &TreeItem, pos: i32, group: &CardGroup) {
+    // Insert instead of add to avoid the label being interpreted as a path (card names can contain '/')
+    let mut group_item = match tree.insert(parent, &format_group_label(group), pos) {
+        Some(item) => item,
+        None => {
+            log::error!("Failed to add group {} to tree", group.key());
+            return;
+        }
+    };
+    group_item.set_label_font(Font::CourierBold);
+
+    for (idx, card) in group.cards().iter().enumerate() {
+        if tree
+            .insert(&group_item, &format_card_label(card), idx as i32)
+            .is_none()
+        {
+            log::error!("Failed to add {} to group {}", card.name, group.key());
+        }
+    }
+    group_item.close();
+}
+
+/// Format the label of a group with its subtotals
+fn format_group_label(group: &CardGroup) -> String {
+    format!(
+        "{key:<12} {unique:>5} ({total:>6})  {gb:>10.2} tix @Goatbots  {ch:>10.2} tix @Cardhoarder",
+        key = group.key(),
+        unique = group.items().unique(),
+        total = group.items().total(),
+        gb = group.goatbots_value(),
+        ch = group.cardhoarder_value(),
+    )
+}
+
+/// Format the label of a card in a group
+fn format_card_label(card: &MtgoCard) -> String {
+    format!(
+        "{quantity:>4}x {name}{foil}  {gb:.3} tix @Goatbots  {ch} @Cardhoarder",
+        quantity = card.quantity,
+        name = card.name,
+        foil = if card.foil { " (foil)" } else { "" },
+        gb = card.goatbots_price,
+        ch = card
+            .scryfall_price
+            .map_or_else(|| "N/A".to_string(), |p| format!("{p:.3} tix")),
+    )
+}
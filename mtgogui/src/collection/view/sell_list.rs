@@ -0,0 +1,231 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use fltk::{
+    app,
+    browser::CheckBrowser,
+    button::{Button, CheckButton},
+    dialog,
+    enums::{Align, Font},
+    frame::Frame,
+    input::{FloatInput, IntInput},
+    prelude::{ButtonExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt},
+    window::Window,
+};
+use fltk_flex::Flex;
+use mtgoupdater::mtgo_card::MtgoCard;
+
+use crate::{
+    assets,
+    collection::sell_list::{SellEntry, SellList, SellRule},
+    util::center,
+    Message,
+};
+
+const WINDOW_WIDTH: i32 = 900;
+const WINDOW_HEIGHT: i32 = 650;
+
+/// The widgets used to configure the sell rules
+#[derive(Clone)]
+struct RuleInputs {
+    keep_playset: CheckButton,
+    keep_count: IntInput,
+    under_price: CheckButton,
+    under_price_tix: FloatInput,
+    foil_non_foil: CheckButton,
+}
+
+impl RuleInputs {
+    /// Get the enabled [SellRule]s from the current state of the inputs
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if an enabled rule has an invalid value
+    fn rules(&self) -> Result<Vec<SellRule>, String> {
+        let mut rules = Vec::new();
+        if self.keep_playset.is_checked() {
+            match self.keep_count.value().parse::<u32>() {
+                Ok(keep) => rules.push(SellRule::KeepPlayset(keep)),
+                Err(_) => return Err("The number of copies to keep must be a whole number".into()),
+            }
+        }
+        if self.under_price.is_checked() {
+            match self.under_price_tix.value().parse::<f32>() {
+                Ok(price) if price >= 0. => rules.push(SellRule::SellUnderPrice(price)),
+                _ => return Err("The price to sell under must be a positive number".into()),
+            }
+        }
+        if self.foil_non_foil.is_checked() {
+            rules.push(SellRule::SellFoilsWhenNonFoilCheaper);
+        }
+        Ok(rules)
+    }
+}
+
+/// Show the sell list generator
+///
+/// The user picks the rules to apply, reviews the generated sell list by (un)checking entries,
+/// and exports the checked entries as an MTGO `.dek`-file that can be handed to a bot in one trade.
+///
+/// # Arguments
+///
+/// * `cards` - A borrowed slice of the cards in the collection
+/// * `ev_send` - Sender to send messages to the main thread, e.g. to report a failed export
+pub fn show_sell_list_view(cards: &[MtgoCard], ev_send: app::Sender<Message>) {
+    let cards: Rc<[MtgoCard]> = cards.into();
+    let sell_list: Rc<RefCell<SellList>> = Rc::new(RefCell::new(SellList::default()));
+
+    let mut win = Window::default()
+        .with_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .with_pos(
+            center().0 - WINDOW_WIDTH / 2,
+            center().1 - WINDOW_HEIGHT / 2,
+        )
+        .with_label("Sell list");
+    win.set_icon(Some(assets::get_logo()));
+    win.make_resizable(true);
+
+    let mut flx_main = Flex::default().size_of_parent().column();
+    flx_main.set_margin(5);
+
+    let rule_inputs = setup_rule_inputs(&mut flx_main);
+
+    let mut btn_generate = Button::default().with_label("Generate sell list");
+    flx_main.fixed(&btn_generate, 30);
+
+    let mut browser = CheckBrowser::default();
+    browser.set_text_font(Font::Courier);
+
+    let mut lbl_proceeds = Frame::default().with_align(Align::Left | Align::Inside);
+    flx_main.fixed(&lbl_proceeds, 30);
+
+    let mut btn_export = Button::default().with_label("Export checked as .dek");
+    flx_main.fixed(&btn_export, 30);
+
+    flx_main.end();
+    win.end();
+    win.show();
+
+    btn_generate.set_callback({
+        let sell_list = sell_list.clone();
+        let mut browser = browser.clone();
+        let mut lbl_proceeds = lbl_proceeds.clone();
+        move |_| {
+            let rules = match rule_inputs.rules() {
+                Ok(rules) => rules,
+                Err(e) => {
+                    dialog::alert(center().0 - 200, center().1 - 100, &e);
+                    return;
+                }
+            };
+            let generated = SellList::generate(&cards, &rules);
+            fill_browser(&mut browser, &generated);
+            update_proceeds_label(&mut lbl_proceeds, &browser, &generated);
+            *sell_list.borrow_mut() = generated;
+        }
+    });
+
+    browser.set_callback({
+        let sell_list = sell_list.clone();
+        move |b| update_proceeds_label(&mut lbl_proceeds, b, &sell_list.borrow())
+    });
+
+    btn_export.set_callback(move |_| {
+        let sell_list = sell_list.borrow();
+        export_checked_dek(&checked_entries(&browser, &sell_list), &ev_send);
+    });
+}
+
+/// Create the rows with a checkbox and value input for each sell rule
+fn setup_rule_inputs(flx_main: &mut Flex) -> RuleInputs {
+    let mut flx_keep = Flex::default().row();
+    let keep_playset = CheckButton::default().with_label("Keep this many of each non-basic:");
+    keep_playset.set_checked(true);
+    let mut keep_count = IntInput::default();
+    keep_count.set_value("4");
+    flx_keep.fixed(&keep_count, 80);
+    flx_keep.end();
+    flx_main.fixed(&flx_keep, 30);
+
+    let mut flx_under = Flex::default().row();
+    let under_price = CheckButton::default().with_label("Sell everything under (tix):");
+    under_price.set_checked(true);
+    let mut under_price_tix = FloatInput::default();
+    under_price_tix.set_value("0.05");
+    flx_under.fixed(&under_price_tix, 80);
+    flx_under.end();
+    flx_main.fixed(&flx_under, 30);
+
+    let foil_non_foil =
+        CheckButton::default().with_label("Sell foils when the non-foil is cheaper");
+    flx_main.fixed(&foil_non_foil, 30);
+
+    RuleInputs {
+        keep_playset,
+        keep_count,
+        under_price,
+        under_price_tix,
+        foil_non_foil,
+    }
+}
+
+/// Fill the browser with the entries of the sell list, all entries start out checked
+fn fill_browser(browser: &mut CheckBrowser, sell_list: &SellList) {
+    browser.clear();
+    for entry in sell_list.entries() {
+        let card = entry.card();
+        let reasons: Vec<String> = entry.reasons().iter().map(|r| r.description()).collect();
+        browser.add(
+            &format!(
+                "{quantity:>4}x {name:<40.40} {set:<5} {gb:>9.3} {ch:>9.3}  {reasons}",
+                quantity = entry.quantity(),
+                name = format!("{}{}", card.name, if card.foil { " (foil)" } else { "" }),
+                set = card.set,
+                gb = entry.goatbots_proceeds(),
+                ch = entry.cardhoarder_proceeds(),
+                reasons = reasons.join(", "),
+            ),
+            true,
+        );
+    }
+}
+
+/// Get the entries of the sell list that are checked in the browser
+fn checked_entries<'s>(browser: &CheckBrowser, sell_list: &'s SellList) -> Vec<&'s SellEntry> {
+    sell_list
+        .entries()
+        .iter()
+        .enumerate()
+        // Browser items are 1-indexed
+        .filter(|(idx, _)| browser.checked(*idx as i32 + 1))
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// Update the label with the expected proceeds at each vendor from selling the checked entries
+fn update_proceeds_label(lbl: &mut Frame, browser: &CheckBrowser, sell_list: &SellList) {
+    let checked = checked_entries(browser, sell_list);
+    let quantity: u32 = checked.iter().map(|e| e.quantity()).sum();
+    let goatbots: f64 = checked.iter().map(|e| e.goatbots_proceeds()).sum();
+    let cardhoarder: f64 = checked.iter().map(|e| e.cardhoarder_proceeds()).sum();
+    lbl.set_label(&format!(
+        "{quantity} cards - expected proceeds: {goatbots:.2} tix @Goatbots, {cardhoarder:.2} tix @Cardhoarder"
+    ));
+}
+
+/// Let the user choose a destination and save the given entries as a `.dek`-file
+fn export_checked_dek(entries: &[&SellEntry], ev_send: &app::Sender<Message>) {
+    if entries.is_empty() {
+        dialog::alert(
+            center().0 - 200,
+            center().1 - 100,
+            "No cards are checked on the sell list!",
+        );
+        return;
+    }
+
+    let cards: Arc<[(MtgoCard, u32)]> = entries
+        .iter()
+        .map(|entry| (entry.card().clone(), entry.quantity()))
+        .collect();
+    super::export_dek(cards, "sell-list.dek", ev_send);
+}
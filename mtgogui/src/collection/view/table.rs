@@ -1,3 +1,5 @@
+use crate::app_core::{self, CollectionModel, Command};
+use crate::appdata::currency::Money;
 use crate::assets::{get_asc_svg, get_desc_svg};
 use crate::Message;
 use fltk::{app, button, group::Column};
@@ -7,17 +9,24 @@ use fltk::{
     image,
     prelude::*,
     prelude::{GroupExt, TableExt, WidgetExt},
+    table::TableContext,
 };
+use fltk_flex::Flex;
 use fltk_table::{SmartTable, TableOpts};
-use mtgoupdater::mtgo_card::MtgoCard;
+use mtgoupdater::{
+    image_cache::ImageCache,
+    mtgo_card::{ItemCategory, MtgoCard},
+    scryfall::ScryfallCards,
+};
 use std::{
     fmt::Alignment,
     sync::{Arc, Mutex},
 };
 
-use self::column::{SortStates, SortedBy};
+use self::column::{Column as SortColumn, SortStates, SortedBy};
 
-use super::TableMessage;
+use super::{card_detail::CardDetailView, TableMessage};
+use crate::collection::clipboard::CopyFormat;
 
 pub mod column;
 mod util;
@@ -63,8 +72,19 @@ fltk::widget_extends!(SortToggle, button::Button, b);
 /// The collection table that displays all cards in the collection
 pub struct CollectionTable {
     table: SmartTable,
-    cards: Vec<MtgoCard>,
     sort_states: SortStates,
+    /// Names of the additional price sources shown after the fixed columns
+    price_sources: Vec<Box<str>>,
+    /// The row of sort buttons above the table, the additional price source buttons are added to it
+    header: Flex,
+    source_buttons: Vec<SortToggle>,
+    ev_sender: app::Sender<Message>,
+    /// The currency and exchange rate the prices are displayed in
+    money: Money,
+    /// The Scryfall data shown in the card details, loaded after the collection is processed
+    scryfall: Option<Arc<ScryfallCards>>,
+    detail_view: Option<CardDetailView>,
+    image_cache: Option<Arc<ImageCache>>,
 }
 
 impl CollectionTable {
@@ -76,9 +96,30 @@ impl CollectionTable {
     pub const COL_CARDHOARDER: CollectionColumn = CollectionColumn::new(4, "CARDHOARDER", 120);
     pub const COL_SET: CollectionColumn = CollectionColumn::new(5, "SET", 60);
     pub const COL_RARITY: CollectionColumn = CollectionColumn::new(6, "RARITY", 100);
+    /// Number of columns that are always shown, the additional price source columns follow them
+    pub const FIXED_COL_COUNT: i32 = 7;
+    /// Width of each additional price source column
+    pub const SOURCE_COL_WIDTH: i32 = 120;
+
+    /// Column of the additional price source at the given index
+    pub const fn source_column(idx: usize) -> CollectionColumn {
+        CollectionColumn::new(
+            Self::FIXED_COL_COUNT + idx as i32,
+            "",
+            Self::SOURCE_COL_WIDTH,
+        )
+    }
 
     /// Create a new [CollectionTable] with the given width, height, and event sender
-    pub fn new(w: i32, h: i32, ev_sender: app::Sender<Message>, sort_states: SortStates) -> Self {
+    ///
+    /// `header` is the row of [SortToggle] buttons above the table
+    pub fn new(
+        w: i32,
+        h: i32,
+        ev_sender: app::Sender<Message>,
+        sort_states: SortStates,
+        header: Flex,
+    ) -> Self {
         // Create the row of buttons to sort by columns
 
         // Create the table that displays all cards with their info
@@ -87,7 +128,7 @@ impl CollectionTable {
             .center_of_parent()
             .with_opts(TableOpts {
                 rows: 0,
-                cols: 7,
+                cols: Self::FIXED_COL_COUNT,
                 editable: false,
                 cell_font_color: Color::White,
                 header_frame: FrameType::NoBox,
@@ -107,91 +148,214 @@ impl CollectionTable {
         table.set_col_width(Self::COL_RARITY.idx, Self::COL_RARITY.width);
 
         // Support drag-and-drop a full trade list file
-        util::set_drag_and_drop_callback(&mut table, ev_sender);
+        util::set_drag_and_drop_callback(&mut table, ev_sender.clone());
+        // Select whole rows and show the details of a card when its row is clicked,
+        // shift-click or drag to select several rows
+        table.set_callback({
+            let ev_sender = ev_sender.clone();
+            move |t| {
+                if t.callback_context() == TableContext::Cell && app::event() == Event::Released {
+                    let (top, _, bottom, _) = t.get_selection();
+                    let cols = t.column_count();
+                    t.set_selection(top, 0, bottom, cols - 1);
+                    ev_sender.send(TableMessage::SelectRow(t.callback_row()).into());
+                }
+            }
+        });
 
         Self {
             table,
-            cards: vec![],
             sort_states,
+            price_sources: Vec::new(),
+            header,
+            source_buttons: Vec::new(),
+            ev_sender,
+            money: Money::TIX,
+            scryfall: None,
+            detail_view: None,
+            image_cache: None,
+        }
+    }
+
+    /// Reflect the [Event](app_core::Event) from the application core in the table
+    pub fn on_event(&mut self, ev: &app_core::Event, model: &CollectionModel) {
+        match ev {
+            app_core::Event::PriceSourcesChanged => {
+                self.set_price_sources(model.price_sources().to_vec())
+            }
+            app_core::Event::Sorted { column, ordering } => {
+                self.sort_states.set_sorted(*column, *ordering)
+            }
+            app_core::Event::RowsChanged => self.draw_cards(model),
+            app_core::Event::MoneyChanged(money) => {
+                self.money = *money;
+                self.draw_cards(model);
+            }
+            app_core::Event::StatsChanged(_)
+            | app_core::Event::Progress { .. }
+            | app_core::Event::OfflineModeDetected => (),
         }
     }
 
     /// Handle the given [TableMessage] event
-    pub fn handle_ev(&mut self, ev: TableMessage) {
+    ///
+    /// Sorting and filtering are [Command]s to the application core, see [TableMessage::into_command]
+    pub fn handle_ev(&mut self, ev: TableMessage, model: &CollectionModel) {
         match ev {
-            TableMessage::SortBy(cat) => {
-                util::sort_cards(&mut self.cards, &mut self.sort_states, cat);
-                self.draw_cards();
+            TableMessage::SortBy(_)
+            | TableMessage::Search(_)
+            | TableMessage::FilterCategory(_)
+            | TableMessage::FilterFormat(_) => {
+                log::warn!("{ev:?} should be dispatched to the application core");
             }
-            TableMessage::Search(str) => {
-                if self.cards.is_empty() {
-                    return;
-                }
-                let pattern = str.to_lowercase();
-                let filtered_cards = self
-                    .cards
-                    .iter()
-                    .filter(|c| c.name.to_lowercase().contains(&pattern));
-
-                let mut filter_count = 0;
-                filtered_cards.into_iter().enumerate().for_each(|(idx, c)| {
-                    let row_idx = idx as i32;
-                    if row_idx > self.table.row_count() - 1 {
-                        self.table.append_empty_row("");
-                    }
-                    util::fill_card_row(&mut self.table, row_idx, c);
-                    filter_count = row_idx + 1
-                });
-                if filter_count < self.table.row_count() {
-                    let mut table_row_count = self.table.row_count();
-                    let diff = table_row_count - filter_count;
-                    for _ in 0..diff {
-                        table_row_count -= 1;
-                        self.table.remove_row(table_row_count);
-                    }
+            TableMessage::GroupBy(group_by) => {
+                super::group::show_group_view(model.cards(), group_by);
+            }
+            TableMessage::ShowArbitrage => {
+                super::arbitrage::show_arbitrage_view(model.cards(), self.ev_sender.clone());
+            }
+            TableMessage::ShowSellList => {
+                super::sell_list::show_sell_list_view(model.cards(), self.ev_sender.clone());
+            }
+            TableMessage::SelectRow(row) => self.show_card_details(row, model),
+            TableMessage::Copy(format) => self.copy_selection(format, model),
+            TableMessage::CardImage { scryfall_id, path } => {
+                if let Some(detail_view) = &mut self.detail_view {
+                    detail_view.set_image(&scryfall_id, path.as_deref());
                 }
             }
         }
     }
 
-    /// Set the cards to display in the table from the given [MtgoCard]s vector
-    pub fn set_cards(&mut self, cards: Vec<MtgoCard>) {
-        self.cards = cards;
-        self.draw_cards();
+    /// Set the Scryfall data used in the card details
+    pub fn set_scryfall_cards(&mut self, scryfall: Arc<ScryfallCards>) {
+        self.scryfall = Some(scryfall);
     }
 
-    /// Draw/refresh the cards in the table
-    fn draw_cards(&mut self) {
-        util::draw_cards(&mut self.table, &self.cards);
+    /// Show the details of the card in the given row
+    fn show_card_details(&mut self, row: i32, model: &CollectionModel) {
+        let card = match usize::try_from(row)
+            .ok()
+            .and_then(|row| model.card_in_row(row))
+        {
+            Some(card) => card,
+            None => return,
+        };
+        let detail_view = self.detail_view.get_or_insert_with(CardDetailView::new);
+        detail_view.show(card, model.cards(), self.scryfall.as_deref(), self.money);
+
+        let scryfall_id: Box<str> = match detail_view.scryfall_id() {
+            Some(id) => id.into(),
+            None => return,
+        };
+        let image_cache = match &self.image_cache {
+            Some(image_cache) => image_cache.clone(),
+            None => return,
+        };
+        // Fetching the image can take a while, so it's done in the background
+        let ev_sender = self.ev_sender.clone();
+        let spawned = std::thread::Builder::new()
+            .name("Card Image Fetcher".to_string())
+            .spawn(move || {
+                let path = match image_cache.get(&scryfall_id) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        log::warn!("Failed to get card image of {scryfall_id}: {e}");
+                        None
+                    }
+                };
+                ev_sender.send(
+                    TableMessage::CardImage {
+                        scryfall_id,
+                        path: path.map(Into::into),
+                    }
+                    .into(),
+                );
+            });
+        if let Err(e) = spawned {
+            log::error!("Failed spawning Card Image Fetcher thread: {e}");
+        }
     }
 
-    /// Filter the cards in the table by the given string
-    fn filter_cards_by_str(&mut self, str: &str) {
-        // Early return if no cards to filter
-        if self.cards.is_empty() {
+    /// The cards in the selected rows
+    fn selected_cards<'m>(&self, model: &'m CollectionModel) -> Vec<&'m MtgoCard> {
+        let (top, _, bottom, _) = self.table.get_selection();
+        if top < 0 || bottom < 0 {
+            return Vec::new();
+        }
+        (top..=bottom)
+            .filter_map(|row| model.card_in_row(row as usize))
+            .collect()
+    }
+
+    /// Copy the cards in the selected rows to the clipboard in the given format
+    fn copy_selection(&self, format: CopyFormat, model: &CollectionModel) {
+        let cards = self.selected_cards(model);
+        if cards.is_empty() {
             return;
         }
-        // Filter the cards by the given string in a case-insensitive manner
-        let pattern = str.to_lowercase();
-        let filtered_cards = self
-            .cards
-            .iter()
-            .filter(|c| c.name.to_lowercase().contains(&pattern));
-
-        // Iterate over the filtered cards and fill the table with them
-        let mut filter_count = 0;
-        filtered_cards.into_iter().enumerate().for_each(|(idx, c)| {
+        log::info!("Copying {} cards as {format:?}", cards.len());
+        app::copy(&format.format(&cards, self.money));
+    }
+
+    /// Set the cache the card images in the card details are served from
+    pub fn set_image_cache(&mut self, image_cache: Arc<ImageCache>) {
+        self.image_cache = Some(image_cache);
+    }
+
+    /// Replace the additional price source columns and their sort buttons
+    fn set_price_sources(&mut self, price_sources: Vec<Box<str>>) {
+        while self.table.column_count() > Self::FIXED_COL_COUNT {
+            self.table.remove_col(self.table.column_count() - 1);
+        }
+        for btn in self.source_buttons.drain(..) {
+            self.header.remove(&*btn);
+            app::delete_widget(btn.b);
+        }
+
+        self.sort_states.reset_sources(price_sources.len());
+        for (idx, source) in price_sources.iter().enumerate() {
+            let col = Self::source_column(idx);
+            self.table.append_empty_col("");
+            self.table.set_col_width(col.idx, col.width);
+
+            let mut btn = SortToggle::new(source, self.sort_states.sources[idx].clone());
+            btn.emit(
+                self.ev_sender.clone(),
+                TableMessage::SortBy(SortColumn::Source(idx)).into(),
+            );
+            self.header.add(&*btn);
+            self.header.fixed(&*btn, col.width - 1);
+            self.source_buttons.push(btn);
+        }
+        let header_w = self.table.w() + price_sources.len() as i32 * Self::SOURCE_COL_WIDTH;
+        let header_h = self.header.h();
+        WidgetExt::set_size(&mut self.header, header_w, header_h);
+        self.header.recalc();
+        self.price_sources = price_sources;
+    }
+
+    /// Draw/refresh the cards in the table that match the search and category filter of the model
+    fn draw_cards(&mut self, model: &CollectionModel) {
+        let mut row_count = 0;
+        for (idx, card) in model.visible_cards().enumerate() {
             let row_idx = idx as i32;
             // Extend the table with rows matching the amount of cards
             if row_idx > self.table.row_count() - 1 {
                 self.table.append_empty_row("");
             }
-            util::fill_card_row(&mut self.table, row_idx, c);
-            filter_count = row_idx + 1
-        });
+            util::fill_card_row(
+                &mut self.table,
+                row_idx,
+                card,
+                &self.price_sources,
+                self.money,
+            );
+            row_count = row_idx + 1;
+        }
 
-        if filter_count < self.table.row_count() {
-            self.remove_excess_rows(filter_count);
+        if row_count < self.table.row_count() {
+            self.remove_excess_rows(row_count);
         }
     }
 
@@ -1,16 +1,6 @@
 use std::sync::{Arc, Mutex};
 
-/// Represents a column with a value description in the collection table
-#[derive(Debug, Clone, Copy)]
-pub enum Column {
-    Name,
-    Quantity,
-    Foil,
-    Goatbots,
-    Scryfall,
-    Set,
-    Rarity,
-}
+pub use crate::app_core::model::{Column, Ordering};
 
 /// Represents the most recent sort state of a column
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -23,13 +13,7 @@ pub enum SortedBy {
     Scryfall(Ordering),
     Set(Ordering),
     Rarity(Ordering),
-}
-
-/// Represents the sort order of a column
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Ordering {
-    Ascending,
-    Descending,
+    Source(Ordering),
 }
 
 impl SortedBy {
@@ -41,7 +25,8 @@ impl SortedBy {
             | SortedBy::Goatbots(d)
             | SortedBy::Scryfall(d)
             | SortedBy::Set(d)
-            | SortedBy::Rarity(d) if *d == Ordering::Descending)
+            | SortedBy::Rarity(d)
+            | SortedBy::Source(d) if *d == Ordering::Descending)
     }
 
     /// Returns true if the column has been sorted by any order at any point
@@ -71,6 +56,8 @@ pub struct SortStates {
     pub set: Arc<Mutex<SortedBy>>,
     /// The sort state of the rarity column
     pub rarity: Arc<Mutex<SortedBy>>,
+    /// The sort states of the additional price source columns
+    pub sources: Vec<Arc<Mutex<SortedBy>>>,
 }
 
 impl SortStates {
@@ -84,6 +71,7 @@ impl SortStates {
             cardhoarder: Arc::new(Mutex::new(SortedBy::None)),
             set: Arc::new(Mutex::new(SortedBy::None)),
             rarity: Arc::new(Mutex::new(SortedBy::None)),
+            sources: Vec::new(),
         }
     }
 
@@ -149,6 +137,40 @@ impl SortStates {
     pub fn set_rarity_ord(&mut self, new_ord: SortedBy) {
         *self.rarity.lock().unwrap() = new_ord;
     }
+
+    /// Returns the [SortedBy] state of the additional price source column at the given index
+    pub fn source_ord(&self, idx: usize) -> SortedBy {
+        self.sources
+            .get(idx)
+            .map_or(SortedBy::None, |ord| *ord.lock().unwrap())
+    }
+    /// Sets the [SortedBy] state of the additional price source column at the given index
+    pub fn set_source_ord(&mut self, idx: usize, new_ord: SortedBy) {
+        if let Some(ord) = self.sources.get(idx) {
+            *ord.lock().unwrap() = new_ord;
+        }
+    }
+
+    /// Show the column as sorted in the given order, as reported by [Event::Sorted](crate::app_core::Event::Sorted)
+    pub fn set_sorted(&mut self, column: Column, ordering: Ordering) {
+        match column {
+            Column::Name => self.set_name_ord(SortedBy::Name(ordering)),
+            Column::Quantity => self.set_quantity_ord(SortedBy::Quantity(ordering)),
+            Column::Foil => self.set_foil_ord(SortedBy::Foil(ordering)),
+            Column::Goatbots => self.set_goatbots_ord(SortedBy::Goatbots(ordering)),
+            Column::Scryfall => self.set_cardhoarder_ord(SortedBy::Scryfall(ordering)),
+            Column::Set => self.set_set_ord(SortedBy::Set(ordering)),
+            Column::Rarity => self.set_rarity_ord(SortedBy::Rarity(ordering)),
+            Column::Source(idx) => self.set_source_ord(idx, SortedBy::Source(ordering)),
+        }
+    }
+
+    /// Reset the sort states of the additional price source columns to one unsorted state per source
+    pub fn reset_sources(&mut self, count: usize) {
+        self.sources = (0..count)
+            .map(|_| Arc::new(Mutex::new(SortedBy::None)))
+            .collect();
+    }
 }
 
 impl Default for SortStates {
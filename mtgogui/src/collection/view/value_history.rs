@@ -0,0 +1,69 @@
+use fltk::{
+    browser::Browser,
+    prelude::{BrowserExt, GroupExt, WidgetBase, WidgetExt, WindowExt},
+    window::Window,
+};
+use fltk_flex::Flex;
+use mtgoupdater::{
+    price_history::DailyValue,
+    price_source::{CARDHOARDER, GOATBOTS},
+};
+
+use crate::{
+    appdata::currency::{Currency, ExchangeRates},
+    assets,
+    util::center,
+};
+
+const WINDOW_WIDTH: i32 = 500;
+const WINDOW_HEIGHT: i32 = 600;
+
+/// Show the value of the collection on each day in the price history
+///
+/// Only the built-in price sources are shown, as the price history store only records their prices.
+/// The store only has the date of each day, so the values are converted to the display currency
+/// at the exchange rate in effect at noon UTC that day, as an approximation of the rate when the values were recorded.
+///
+/// # Arguments
+///
+/// * `values` - The value of the collection on each day, oldest first
+/// * `rates` - The exchange rate history
+/// * `currency` - The currency to display the values in
+pub fn show_value_history_view(values: &[DailyValue], rates: &ExchangeRates, currency: Currency) {
+    let mut win = Window::default()
+        .with_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .with_pos(
+            center().0 - WINDOW_WIDTH / 2,
+            center().1 - WINDOW_HEIGHT / 2,
+        )
+        .with_label("Collection value history");
+    win.set_icon(Some(assets::get_logo()));
+    win.make_resizable(true);
+
+    let mut flx_main = Flex::default().size_of_parent().column();
+    flx_main.set_margin(5);
+
+    let mut browser = Browser::default();
+    browser.set_column_widths(&[120, 170, 170]);
+    browser.set_column_char('\t');
+    browser.add(&format!("@bDate\t@b{GOATBOTS}\t@b{CARDHOARDER}"));
+    // Newest first
+    for value in values.iter().rev() {
+        let at = value
+            .date
+            .and_hms_opt(12, 0, 0)
+            .expect("noon is a valid time")
+            .and_utc();
+        let money = rates.money_at(currency, at);
+        browser.add(&format!(
+            "{date}\t{goatbots}\t{cardhoarder}",
+            date = value.date,
+            goatbots = money.format(value.goatbots_value, 2),
+            cardhoarder = money.format(value.scryfall_value, 2),
+        ));
+    }
+
+    flx_main.end();
+    win.end();
+    win.show();
+}
@@ -0,0 +1,232 @@
+//! Watching a folder for new Full Trade List exports, e.g. MTGO's export location or a synced folder.
+//!
+//! When a `Full Trade List*.dek`-file in the folder is created or modified, it's validated and imported with
+//! [Message::GotFullTradeList], so re-exporting the trade list from MTGO is enough to update the collection.
+//! Other `.dek`-files, e.g. exported decks, are ignored as importing them would replace the collection with a single deck.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use fltk::app::Sender;
+use notify_debouncer_mini::{
+    new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode},
+    DebounceEventResult, Debouncer,
+};
+
+use crate::{
+    collection::dek,
+    notification::{Notification, NotificationAction},
+    Message,
+};
+
+/// How long a file has to be unchanged before it's imported, MTGO writes the export in several steps
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Start of the file names of the trade lists exported by MTGO, e.g. `Full Trade List.dek` or `Full Trade List (2).dek`
+const TRADE_LIST_PREFIX: &str = "Full Trade List";
+
+/// Decides which changed files in the watched folder are imported
+///
+/// A file is imported if it's a valid `Full Trade List*.dek`-file with different contents than the last imported trade list.
+#[derive(Debug, Default)]
+pub struct TradeListDedupe {
+    last_hash: Option<u64>,
+}
+
+impl TradeListDedupe {
+    /// Create a new [TradeListDedupe] that skips files with the same contents as the given trade list
+    ///
+    /// # Arguments
+    ///
+    /// * `current_trade_list` - The trade list that was last imported, if any
+    pub fn new(current_trade_list: Option<&Path>) -> Self {
+        let last_hash = current_trade_list.and_then(|path| match fs::read(path) {
+            Ok(contents) => Some(content_hash(&contents)),
+            Err(e) => {
+                log::warn!("Failed to read the current trade list {path:?}: {e}");
+                None
+            }
+        });
+        Self { last_hash }
+    }
+
+    /// Check if the changed file should be imported, and remember its contents if so
+    ///
+    /// # Returns
+    ///
+    /// True if the file is a new trade list, false if it's not a `Full Trade List*.dek`-file, was removed or is a duplicate
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if the file is a trade list that cannot be read or is invalid
+    pub fn check(&mut self, path: &Path) -> Result<bool, String> {
+        if !is_trade_list_name(path) || !path.is_file() {
+            return Ok(false);
+        }
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+        let hash = content_hash(contents.as_bytes());
+        if self.last_hash == Some(hash) {
+            log::info!("Skipping {path:?}, it's identical to the last imported trade list");
+            return Ok(false);
+        }
+        dek::validate_dek(&contents)?;
+        self.last_hash = Some(hash);
+        Ok(true)
+    }
+}
+
+/// Watches a folder and imports new Full Trade List exports, stops watching when dropped
+pub struct TradeListWatcher {
+    dir: PathBuf,
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl TradeListWatcher {
+    /// Start watching the folder
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The folder to watch, subfolders are not watched
+    /// * `current_trade_list` - The trade list that was last imported, an export with the same contents is not imported
+    /// * `ev_sender` - Sender for the [Message::GotFullTradeList] of new trade lists
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the folder cannot be watched, e.g. if it doesn't exist
+    pub fn new(
+        dir: &Path,
+        current_trade_list: Option<&Path>,
+        ev_sender: Sender<Message>,
+    ) -> io::Result<Self> {
+        let mut dedupe = TradeListDedupe::new(current_trade_list);
+        let mut debouncer = new_debouncer(
+            DEBOUNCE_TIMEOUT,
+            None,
+            move |res: DebounceEventResult| match res {
+                Ok(events) => {
+                    for event in events {
+                        handle_changed_file(&event.path, &mut dedupe, &ev_sender);
+                    }
+                }
+                Err(errors) => {
+                    for e in errors {
+                        log::warn!("Watch folder error: {e}");
+                    }
+                }
+            },
+        )
+        .map_err(io::Error::other)?;
+        debouncer
+            .watcher()
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| io::Error::other(format!("{dir:?}: {e}")))?;
+        log::info!("Watching {dir:?} for new trade lists");
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            _debouncer: debouncer,
+        })
+    }
+
+    /// The watched folder
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Import the changed file if it's a new trade list, or tell the user why it was skipped
+fn handle_changed_file(path: &Path, dedupe: &mut TradeListDedupe, ev_sender: &Sender<Message>) {
+    match dedupe.check(path) {
+        Ok(true) => {
+            log::info!("New trade list in watch folder: {path:?}");
+            ev_sender.send(Message::GotFullTradeList(path.into()));
+        }
+        Ok(false) => (),
+        Err(e) => ev_sender.send(
+            Notification::warning("A Full Trade List in the watch folder is not valid")
+                .with_details(e)
+                .with_action(NotificationAction::choose_another_file())
+                .into(),
+        ),
+    }
+}
+
+/// Returns true if the file is named like a trade list exported by MTGO, see [TRADE_LIST_PREFIX]
+fn is_trade_list_name(path: &Path) -> bool {
+    let is_dek = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dek"));
+    let has_prefix = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with(TRADE_LIST_PREFIX));
+    is_dek && has_prefix
+}
+
+/// Hash of the file contents to detect duplicate exports
+fn content_hash(contents: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    const TRADE_LIST: &str = "../test/test-data/mtgo/Full Trade List-small-5cards.dek";
+
+    #[test]
+    fn test_dedupe_by_content() {
+        let tmp_dir = TempDir::new().unwrap();
+        let export = tmp_dir.child("Full Trade List.dek");
+        fs::copy(TRADE_LIST, &export).unwrap();
+
+        let mut dedupe = TradeListDedupe::new(None);
+        assert_eq!(dedupe.check(&export), Ok(true));
+        // Touched or re-exported without changes
+        assert_eq!(dedupe.check(&export), Ok(false));
+
+        let mut contents = fs::read_to_string(&export).unwrap();
+        contents = contents.replace("Quantity=\"1\"", "Quantity=\"2\"");
+        fs::write(&export, contents).unwrap();
+        assert_eq!(dedupe.check(&export), Ok(true));
+    }
+
+    #[test]
+    fn test_dedupe_skips_current_trade_list() {
+        let mut dedupe = TradeListDedupe::new(Some(Path::new(TRADE_LIST)));
+
+        assert_eq!(dedupe.check(Path::new(TRADE_LIST)), Ok(false));
+    }
+
+    #[test]
+    fn test_dedupe_ignores_other_files_and_rejects_invalid() {
+        let tmp_dir = TempDir::new().unwrap();
+        let notes = tmp_dir.child("notes.txt");
+        fs::write(&notes, "not a trade list").unwrap();
+        let invalid = tmp_dir.child("Full Trade List (2).dek");
+        fs::write(&invalid, "not a trade list").unwrap();
+        // A valid .dek-file, but an exported deck and not the collection
+        let deck = tmp_dir.child("Burn.dek");
+        fs::copy(TRADE_LIST, &deck).unwrap();
+
+        let mut dedupe = TradeListDedupe::default();
+
+        assert_eq!(dedupe.check(&notes), Ok(false));
+        assert_eq!(dedupe.check(&deck), Ok(false));
+        assert_eq!(
+            dedupe.check(&tmp_dir.child("Full Trade List.dek")),
+            Ok(false)
+        );
+        assert!(dedupe.check(&invalid).is_err());
+    }
+}
@@ -1,21 +1,30 @@
 use std::ffi::OsStr;
-use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
+use crate::app_core::{AppCore, Command};
+use crate::appdata::currency::{Currency, ExchangeRates, Money};
+use crate::appdata::diagnostics::{export_diagnostics, SystemInfo};
 use crate::appdata::metadata::{self, MetaData};
+use crate::appdata::retention::RetentionPolicy;
 use crate::appdata::state::GuiState;
+use crate::appdata::update::OfflineMode;
 use crate::assets::{self, get_asc_svg, get_icon_search, get_logo};
 use crate::collection::processor::TradelistProcessor;
+use crate::collection::stats::container::CollectionStats;
 use crate::collection::stats::items::BrowserItems;
 use crate::collection::stats::view::StatsView;
 use crate::collection::view::table::CollectionTable;
+use crate::collection::watch_folder::TradeListWatcher;
 use crate::collection::TableMessage;
-use crate::menubar::McmMenuBar;
-use crate::util::first_file_match_from_dir;
+use crate::menubar::{McmMenuBar, MenubarMessage};
+use crate::notification::{self, Notification, NotificationAction};
+use crate::util::{center, first_file_match_from_dir};
 use crate::{
-    appdata, collection, Message, DEFAULT_APP_HEIGHT, DEFAULT_APP_WIDTH, MENU_BAR_HEIGHT,
-    MIN_APP_HEIGHT, MIN_APP_WIDTH,
+    app_core, appdata, collection, menubar, Message, DEFAULT_APP_HEIGHT, DEFAULT_APP_WIDTH,
+    MENU_BAR_HEIGHT, MIN_APP_HEIGHT, MIN_APP_WIDTH,
 };
+use flexi_logger::LoggerHandle;
 use fltk::enums::{Align, CallbackTrigger, Event, Font, FrameType, Shortcut};
 use fltk::frame::Frame;
 use fltk::image::{Image, PngImage, TiledImage};
@@ -29,22 +38,39 @@ use fltk_flex::{Flex, FlexType};
 use fltk_grid::Grid;
 use fltk_table::{SmartTable, TableOpts};
 use fltk_theme::{widget_themes, ThemeType, WidgetTheme};
+use mtgoupdater::image_cache::ImageCache;
 
+use self::onboarding::OnboardingWizard;
 use self::setup::setup_main_window;
 
+mod onboarding;
 mod setup;
 
+/// Seconds between checks if the daily collection snapshot is due
+const DAILY_SNAPSHOT_CHECK_INTERVAL: f64 = 15. * 60.;
+
 /// [MtgoGui] is the main GUI struct that holds all the widgets and state for the application
 pub struct MtgoGui {
     app: app::App,
     state: GuiState,
     rcv: app::Receiver<Message>,
+    ev_sender: app::Sender<Message>,
     main_win: window::Window,
     menu: McmMenuBar,
+    /// The collection model, the table and stats are views of it
+    core: AppCore,
     collection: CollectionTable,
     collection_stats: StatsView,
     metadata: StatsView,
     tradelist_processor: TradelistProcessor,
+    offline_mode: OfflineMode,
+    rates: ExchangeRates,
+    onboarding: Option<OnboardingWizard>,
+    watcher: Option<TradeListWatcher>,
+    logger: Option<LoggerHandle>,
+    log_level: log::LevelFilter,
+    /// The day a trade list was last processed, to take at most one daily snapshot a day
+    last_processed: Option<chrono::NaiveDate>,
 }
 
 impl Default for MtgoGui {
@@ -83,24 +109,72 @@ impl MtgoGui {
 
         let tradelist_processor = TradelistProcessor::new(ev_send.clone());
 
-        main_win.set_callback(move |_| {
-            if app::event() == Event::Close {
-                ev_send.send(Message::Quit);
+        let mut core = AppCore::new();
+        // The views are updated from the main event loop
+        core.subscribe({
+            let ev_send = ev_send.clone();
+            move |ev| ev_send.send(Message::Core(ev.clone()))
+        });
+
+        main_win.set_callback({
+            let ev_send = ev_send.clone();
+            move |_| {
+                if app::event() == Event::Close {
+                    ev_send.send(Message::Quit);
+                }
             }
         });
         Self {
             app,
             state: GuiState::default(), // Placeholder, is overwritten at startup
             rcv: ev_rcv,
+            ev_sender: ev_send,
             main_win,
             menu,
+            core,
             collection,
             collection_stats,
             metadata,
             tradelist_processor,
+            offline_mode: OfflineMode::default(),
+            rates: ExchangeRates::default(),
+            onboarding: None,
+            watcher: None,
+            logger: None,
+            log_level: log::LevelFilter::Info,
+            last_processed: None,
         }
     }
 
+    /// Set the handle to the logger, used by the log viewer to find the log file and change the log level
+    pub fn set_logger(&mut self, logger: LoggerHandle) {
+        self.logger = Some(logger);
+    }
+
+    /// Set the [OfflineMode] used when updating the card data
+    pub fn set_offline_mode(&mut self, offline_mode: OfflineMode) {
+        self.offline_mode = offline_mode;
+        self.tradelist_processor.set_offline_mode(offline_mode);
+        self.menu
+            .set_offline_mode_checked(offline_mode.is_offline());
+        self.refresh_image_cache();
+    }
+
+    /// Set up the card image cache with the configured size limit and image source, see [GuiState::card_image_cache]
+    fn refresh_image_cache(&mut self) {
+        let appdata_dir = match appdata::util::appdata_path() {
+            Ok(appdata_dir) => appdata_dir,
+            Err(e) => {
+                log::error!("Failed to get appdata path: {e}");
+                return;
+            }
+        };
+        let image_cache = self
+            .state
+            .card_image_cache(&appdata_dir, self.offline_mode.is_offline());
+        self.collection.set_image_cache(Arc::new(image_cache));
+    }
+
     /// Perform any startup tasks.
     ///
     /// Runs after all the GUI elements are created. And just before the main event loop starts.
@@ -117,18 +191,62 @@ impl MtgoGui {
                 GuiState::default()
             }
         };
+        // The cache set up before the state was loaded, e.g. by the offline flag, ignores the configured settings
+        self.refresh_image_cache();
+
+        self.rates = match ExchangeRates::load(&appdata_dir) {
+            Ok(rates) => rates,
+            Err(e) => {
+                log::warn!("Failed to load exchange rates: {e}");
+                ExchangeRates::default()
+            }
+        };
+        self.menu
+            .set_currency_checked(self.state.display_currency());
+        self.core.dispatch(Command::SetMoney(self.money()));
+
+        self.tradelist_processor
+            .set_price_sources(self.state.price_sources().to_vec());
+
+        // The command-line flag takes precedence over the setting
+        if self.offline_mode == OfflineMode::Disabled && self.state.offline_mode() {
+            self.set_offline_mode(OfflineMode::Setting);
+        }
+        self.check_helper_binaries();
+
+        self.menu
+            .set_daily_snapshots_checked(self.state.daily_snapshots());
+        app::add_timeout3(DAILY_SNAPSHOT_CHECK_INTERVAL, {
+            let ev_sender = self.ev_sender.clone();
+            move |handle| {
+                ev_sender.send(Message::DailySnapshot);
+                app::repeat_timeout3(DAILY_SNAPSHOT_CHECK_INTERVAL, handle);
+            }
+        });
 
         self.set_metadata_browser_view(appdata_dir.clone())?;
 
+        log::info!("Applying appdata retention policy");
+        match RetentionPolicy::default().apply(&appdata_dir, chrono::Utc::now()) {
+            Ok(report) => log::info!("{}", report.summary()),
+            Err(e) => log::warn!("Failed to apply appdata retention policy: {e}"),
+        }
+
+        self.refresh_watch_folder();
+
+        if self.state.needs_onboarding() {
+            log::info!("First launch, showing the onboarding wizard");
+            self.show_onboarding();
+        }
+
         log::info!("Processing current tradelist");
         match appdata::util::current_tradelist_path() {
-            Ok(Some(current_trade_list)) => {
-                self.tradelist_processor.process(current_trade_list.into())
-            }
-            Err(e) => {
-                // TODO - Error pop-up dialog if fails.
-                return Err(format!("Failed to get current tradelist path: {e}"));
-            }
+            Ok(Some(current_trade_list)) => self.process_tradelist(current_trade_list.into()),
+            Err(e) => self.notify(
+                Notification::error("Failed to find the current trade list")
+                    .with_details(e)
+                    .with_action(NotificationAction::choose_another_file()),
+            ),
             Ok(None) => {
                 log::info!("No current trade list found");
             }
@@ -165,41 +283,142 @@ impl MtgoGui {
                         }
                         self.app.quit();
                     }
-                    Message::MenuBar(mb_msg) => self.menu.handle_ev(mb_msg),
+                    Message::MenuBar(mb_msg) => {
+                        if let (MenubarMessage::ProgressBar(update), Some(onboarding)) =
+                            (&mb_msg, &mut self.onboarding)
+                        {
+                            onboarding.set_progress(update);
+                        }
+                        self.menu.handle_ev(mb_msg)
+                    }
 
                     Message::Table(t_m) => {
-                        self.collection.handle_ev(t_m);
+                        match t_m.into_command() {
+                            Ok(command) => _ = self.core.dispatch(command),
+                            Err(t_m) => self.collection.handle_ev(t_m, self.core.model()),
+                        }
                         self.app.redraw();
                     }
                     Message::GotFullTradeList(full_trade_list_path) => {
-                        // TODO: Error pop-up dialog if fails.
-                        // Should implement a generic error dialog that can be used for all unexpected errors that cannot be handled programmatically.
-                        appdata::util::copy_tradelist_to_appdata(full_trade_list_path.as_os_str())
-                            .unwrap();
+                        if let Err(e) = appdata::util::copy_tradelist_to_appdata(
+                            full_trade_list_path.as_os_str(),
+                        ) {
+                            self.notify(
+                                Notification::error("Failed to copy the trade list to appdata")
+                                    .with_details(format!("{full_trade_list_path:?}: {e}"))
+                                    .with_action(NotificationAction::retry(
+                                        Message::GotFullTradeList(full_trade_list_path),
+                                    ))
+                                    .with_action(NotificationAction::choose_another_file()),
+                            );
+                            continue;
+                        }
                         self.state.new_tradelist();
 
-                        self.tradelist_processor.process(full_trade_list_path);
+                        self.process_tradelist(full_trade_list_path);
                     }
-                    Message::SetCards(cards) => self.collection.set_cards(cards),
-                    Message::SetCollectionStats(stats) => {
-                        match stats.try_into() {
-                            Ok(browser_items) => {
-                                self.collection_stats.set_items(browser_items);
-                            }
-                            Err(e) => {
-                                log::error!("Failed to convert stats to browser items: {e}");
+                    Message::ProcessTradeList(full_trade_list_path) => {
+                        self.process_tradelist(full_trade_list_path)
+                    }
+                    Message::SetCards(cards) => {
+                        self.core.dispatch(Command::SetCards(cards));
+                        self.refresh_metadata_browser_view();
+                    }
+                    Message::Core(ev) => {
+                        self.collection.on_event(&ev, self.core.model());
+                        if let app_core::Event::StatsChanged(stats) = ev {
+                            match (*stats).try_into() {
+                                Ok(browser_items) => {
+                                    self.collection_stats.set_items(browser_items);
+                                }
+                                Err(e) => {
+                                    log::error!("Failed to convert stats to browser items: {e}");
+                                }
                             }
                         }
-                        match appdata::util::appdata_path() {
-                            Ok(appdata_dir) => {
-                                let _ = self.set_metadata_browser_view(appdata_dir).map_err(|e| {
-                                    log::error!("Failed to set metadata browser view: {e}");
-                                });
-                            }
-                            Err(e) => {
-                                log::error!("Failed to get appdata path: {e}");
-                            }
+                        self.app.redraw();
+                    }
+                    Message::ToggleOfflineMode => {
+                        let enable = !matches!(
+                            self.offline_mode,
+                            OfflineMode::CliFlag | OfflineMode::Setting
+                        );
+                        log::info!("Offline mode enabled: {enable}");
+                        self.state.set_offline_mode(enable);
+                        self.set_offline_mode(if enable {
+                            OfflineMode::Setting
+                        } else {
+                            OfflineMode::Disabled
+                        });
+                        self.refresh_metadata_browser_view();
+                    }
+                    Message::CleanupAppdata => self.cleanup_appdata(),
+                    Message::GotPriceList(path) => self.import_price_list(&path),
+                    Message::SetDisplayCurrency(currency) => {
+                        log::info!("Display currency: {}", currency.name());
+                        self.state.set_display_currency(currency);
+                        self.refresh_money();
+                    }
+                    Message::ExchangeRatesChanged => {
+                        match appdata::util::appdata_path()
+                            .and_then(|dir| ExchangeRates::load(&dir))
+                        {
+                            Ok(rates) => self.rates = rates,
+                            Err(e) => log::error!("Failed to load exchange rates: {e}"),
                         }
+                        self.refresh_money();
+                    }
+                    Message::ShowValueHistory => self.show_value_history(),
+                    Message::ShowOnboarding => self.show_onboarding(),
+                    Message::OnboardingCompleted(daily_snapshots) => {
+                        log::info!("Onboarding completed, daily snapshots: {daily_snapshots:?}");
+                        self.onboarding = None;
+                        self.state.complete_onboarding();
+                        if let Some(daily_snapshots) = daily_snapshots {
+                            self.state.set_daily_snapshots(daily_snapshots);
+                            self.menu.set_daily_snapshots_checked(daily_snapshots);
+                        }
+                    }
+                    Message::ToggleDailySnapshots => {
+                        let enable = !self.state.daily_snapshots();
+                        log::info!("Daily snapshots enabled: {enable}");
+                        self.state.set_daily_snapshots(enable);
+                    }
+                    Message::DailySnapshot => self.take_daily_snapshot(),
+                    Message::SetWatchFolder(dir) => {
+                        self.state
+                            .set_watch_folder(dir.map(|dir| dir.into_path_buf()));
+                        self.refresh_watch_folder();
+                        if let Some(watcher) = &self.watcher {
+                            self.notify(Notification::info(&format!(
+                                "Watching {dir:?} for new trade lists",
+                                dir = watcher.dir()
+                            )));
+                        }
+                    }
+                    Message::ShowLogs => match &self.logger {
+                        Some(logger) => menubar::log_viewer::show_log_viewer(
+                            logger.clone(),
+                            self.log_level,
+                            self.ev_sender.clone(),
+                        ),
+                        None => log::warn!("No logger to show the logs of"),
+                    },
+                    Message::SetLogLevel(level) => self.set_log_level(level),
+                    Message::ExportDiagnostics => self.export_diagnostics(),
+                    Message::Notify(notification) => self.notify(*notification),
+                    Message::ExportDek(cards, preset_file) => {
+                        crate::collection::view::export_dek(cards, &preset_file, &self.ev_sender)
+                    }
+                    Message::SetScryfallCards(scryfall) => {
+                        self.collection.set_scryfall_cards(scryfall)
+                    }
+                    Message::OfflineModeDetected => {
+                        // Only shown in the metadata view, the download is attempted again on the next update
+                        if !self.offline_mode.is_offline() {
+                            self.offline_mode = OfflineMode::Detected;
+                        }
+                        self.refresh_metadata_browser_view();
                     }
                 }
             }
@@ -210,6 +429,271 @@ impl MtgoGui {
 // Utility functions
 
 impl MtgoGui {
+    /// Apply the default [RetentionPolicy] to the appdata directory and show how much space was freed
+    fn cleanup_appdata(&mut self) {
+        let result = appdata::util::appdata_path().and_then(|appdata_dir| {
+            RetentionPolicy::default().apply(&appdata_dir, chrono::Utc::now())
+        });
+        match result {
+            Ok(report) => self.notify(Notification::info(&report.summary())),
+            Err(e) => self.notify(
+                Notification::error("Failed to clean up appdata")
+                    .with_details(e)
+                    .with_action(NotificationAction::retry(Message::CleanupAppdata)),
+            ),
+        }
+    }
+
+    /// Import a custom price list into appdata and add its prices to the current collection
+    fn import_price_list(&mut self, path: &Path) {
+        let result = appdata::util::appdata_path()
+            .map_err(|e| e.into())
+            .and_then(|appdata_dir| {
+                appdata::price_lists::import_price_list(path, &appdata_dir)
+                    .map(|list| (list, appdata_dir))
+            });
+        let (list, appdata_dir) = match result {
+            Ok(imported) => imported,
+            Err(e) => {
+                self.notify(
+                    Notification::error("Failed to import price list")
+                        .with_details(format!("{path:?}: {e}"))
+                        .with_action(NotificationAction::new(
+                            "Choose another file...",
+                            MenubarMessage::ImportPriceList.into(),
+                        )),
+                );
+                return;
+            }
+        };
+
+        let mut cards = self.core.model().cards().to_vec();
+        if !cards.is_empty() {
+            appdata::price_lists::apply_price_lists(&appdata_dir, &mut cards);
+            self.core.dispatch(Command::SetCards(cards));
+        }
+        self.notify(Notification::info(&format!(
+            "Imported {count} prices from the {name} price list",
+            count = list.entries().len(),
+            name = list.name()
+        )));
+    }
+
+    /// Process the trade list and remember the day it was processed
+    fn process_tradelist(&mut self, full_trade_list_path: Box<Path>) {
+        self.last_processed = Some(chrono::Utc::now().date_naive());
+        self.tradelist_processor.process(full_trade_list_path);
+    }
+
+    /// Process the current trade list if daily snapshots are enabled and it wasn't processed today
+    ///
+    /// Processing updates the card data and prices, and records a collection snapshot for the day.
+    fn take_daily_snapshot(&mut self) {
+        if !self.state.daily_snapshots()
+            || self.last_processed == Some(chrono::Utc::now().date_naive())
+        {
+            return;
+        }
+        match appdata::util::current_tradelist_path() {
+            Ok(Some(current_trade_list)) => {
+                log::info!("Taking daily collection snapshot");
+                self.process_tradelist(current_trade_list.into());
+            }
+            Ok(None) => log::info!("No current trade list to take a daily snapshot of"),
+            Err(e) => log::warn!("Failed to get current tradelist path: {e}"),
+        }
+    }
+
+    /// Check that the MTGO Getter and MTGO Preprocessor binaries can run and have compatible versions
+    ///
+    /// In debug mode the problems are only logged, as the binaries are not extracted (see `write_binaries_out`)
+    /// and are missing unless they've been built in each subproject.
+    fn check_helper_binaries(&mut self) {
+        let problems = menubar::util::check_helper_versions();
+        if problems.is_empty() {
+            log::info!("MTGO Getter and MTGO Preprocessor versions are compatible");
+            return;
+        }
+        let details = problems.join("\n");
+        if cfg!(debug_assertions) {
+            log::warn!("Helper binary self-check failed:\n{details}");
+            return;
+        }
+        log::error!("Helper binary self-check failed:\n{details}");
+        self.notify(
+            Notification::error(
+                "MTGO Getter or MTGO Preprocessor is missing or incompatible, try reinstalling",
+            )
+            .with_details(details)
+            .modal(),
+        );
+    }
+
+    /// Let the user choose where to save a diagnostics bundle and create it
+    fn export_diagnostics(&mut self) {
+        let mut dlg = dialog::FileDialog::new(dialog::FileDialogType::BrowseSaveFile);
+        dlg.set_option(dialog::FileDialogOptions::SaveAsConfirm);
+        dlg.set_filter("ZIP\t*.zip");
+        dlg.set_preset_file(&format!(
+            "mcm-diagnostics-{}.zip",
+            chrono::Utc::now().format("%Y-%m-%d")
+        ));
+        dlg.show();
+        let dest = dlg.filename();
+        if dest.as_os_str().is_empty() {
+            return;
+        }
+
+        let info = SystemInfo {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            mtgogetter_version: menubar::util::mtgogetter_version_str()
+                .unwrap_or_else(|e| format!("unavailable: {e}")),
+            mtgo_preprocessor_version: menubar::util::mtgo_preprocessor_version()
+                .unwrap_or_else(|e| format!("unavailable: {e}")),
+            mtgoupdater_version: mtgoupdater::mtgo_updater_version().to_string(),
+        };
+        if let Some(logger) = &self.logger {
+            logger.flush();
+        }
+        let result = appdata::util::appdata_path()
+            .and_then(|appdata_dir| export_diagnostics(&appdata_dir, &info, &dest));
+        match result {
+            Ok(files) => {
+                log::info!("Exported diagnostics to {dest:?}: {files:?}");
+                self.notify(Notification::info(&format!(
+                    "Exported diagnostics to {}",
+                    dest.display()
+                )));
+            }
+            Err(e) => self.notify(
+                Notification::error("Failed to export diagnostics")
+                    .with_details(format!("{dest:?}: {e}"))
+                    .with_action(NotificationAction::retry(Message::ExportDiagnostics)),
+            ),
+        }
+    }
+
+    /// Change the level of the messages written to the log
+    fn set_log_level(&mut self, level: log::LevelFilter) {
+        let logger = match &self.logger {
+            Some(logger) => logger,
+            None => return,
+        };
+        match logger.parse_new_spec(&level.as_str().to_lowercase()) {
+            Ok(()) => {
+                self.log_level = level;
+                log::info!("Log level set to {level}");
+            }
+            Err(e) => {
+                self.notify(Notification::error("Failed to change the log level").with_details(e))
+            }
+        }
+    }
+
+    /// Watch the configured folder for new Full Trade List exports, or stop watching if none is configured
+    fn refresh_watch_folder(&mut self) {
+        // Stop watching the previous folder first
+        self.watcher = None;
+        let dir = match self.state.watch_folder() {
+            Some(dir) => dir,
+            None => return,
+        };
+        let current_trade_list = appdata::util::current_tradelist_path().unwrap_or_else(|e| {
+            log::warn!("Failed to get current tradelist path: {e}");
+            None
+        });
+        match TradeListWatcher::new(dir, current_trade_list.as_deref(), self.ev_sender.clone()) {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(e) => self.notify(
+                Notification::error("Failed to watch the folder for new trade lists")
+                    .with_details(format!("{dir:?}: {e}"))
+                    .with_action(NotificationAction::new(
+                        "Choose another folder...",
+                        MenubarMessage::WatchFolder.into(),
+                    )),
+            ),
+        }
+    }
+
+    /// Show the onboarding wizard, unless it's already shown
+    fn show_onboarding(&mut self) {
+        if self.onboarding.as_ref().is_some_and(|w| w.shown()) {
+            return;
+        }
+        self.onboarding = Some(OnboardingWizard::new(&self.ev_sender));
+    }
+
+    /// Show the notification to the user, see [notification::show]
+    fn notify(&self, notification: Notification) {
+        notification::show(&notification, &self.ev_sender);
+    }
+
+    /// The currency and exchange rate currently used to display values
+    fn money(&self) -> Money {
+        self.rates
+            .money_at(self.state.display_currency(), chrono::Utc::now())
+    }
+
+    /// Redraw the table and stats in the current display currency
+    fn refresh_money(&mut self) {
+        let money = self.money();
+        if money.currency() != self.state.display_currency() {
+            log::warn!(
+                "No exchange rate set for {}, showing values in tix",
+                self.state.display_currency().name()
+            );
+        }
+        self.core.dispatch(Command::SetMoney(money));
+    }
+
+    /// Show the value of the collection on each day in the price history, in the display currency
+    fn show_value_history(&mut self) {
+        let values = appdata::util::appdata_path().and_then(|appdata_dir| {
+            let store_path = appdata_dir
+                .join(appdata::COLLECTION_HISTORY_DIR)
+                .join(appdata::PRICE_HISTORY_STORE);
+            if !store_path.try_exists()? {
+                return Ok(Vec::new());
+            }
+            let store = mtgoupdater::price_history::PriceHistoryStore::open(&store_path)?;
+            if store.removed_bytes() > 0 {
+                log::warn!(
+                    "Removed {} bytes of an incomplete record from the end of the price history {store_path:?}",
+                    store.removed_bytes()
+                );
+            }
+            store.daily_values()
+        });
+        match values {
+            Ok(values) if values.is_empty() => dialog::message(
+                center().0 - 200,
+                center().1 - 100,
+                "No value history yet, the value is recorded every time the collection is updated",
+            ),
+            Ok(values) => collection::view::value_history::show_value_history_view(
+                &values,
+                &self.rates,
+                self.state.display_currency(),
+            ),
+            Err(e) => {
+                self.notify(Notification::error("Failed to read the price history").with_details(e))
+            }
+        }
+    }
+
+    fn refresh_metadata_browser_view(&mut self) {
+        match appdata::util::appdata_path() {
+            Ok(appdata_dir) => {
+                let _ = self.set_metadata_browser_view(appdata_dir).map_err(|e| {
+                    log::error!("Failed to set metadata browser view: {e}");
+                });
+            }
+            Err(e) => {
+                log::error!("Failed to get appdata path: {e}");
+            }
+        }
+    }
+
     fn set_metadata_browser_view(&mut self, appdata_dir: PathBuf) -> Result<(), String> {
         let tradelist_added_date_str: Option<String> =
             if let Some(tradelist_added_date) = self.state.get_tradelist_added_date() {
@@ -220,6 +704,7 @@ impl MtgoGui {
             };
 
         let mut metadata_browser_items = BrowserItems::new();
+        metadata_browser_items.add_item("Mode", self.offline_mode.description());
         metadata_browser_items.add_item(
             "dek-File added",
             tradelist_added_date_str
@@ -0,0 +1,367 @@
+//! The onboarding wizard shown on the first launch, guiding the user through importing their collection.
+
+use std::path::{Path, PathBuf};
+
+use fltk::{
+    app::{self, Sender},
+    browser::Browser,
+    button::{Button, CheckButton},
+    dialog::{FileDialog, FileDialogOptions, FileDialogType},
+    enums::{Align, CallbackTrigger, Color, Event, Font},
+    frame::Frame,
+    group::Wizard,
+    input::Input,
+    misc::Progress,
+    prelude::{
+        BrowserExt, ButtonExt, DisplayExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt,
+    },
+    text::{TextBuffer, TextDisplay, WrapMode},
+    window::Window,
+};
+use fltk_flex::Flex;
+
+use crate::{assets, collection::dek, menubar::util::ProgressUpdate, util::center, Message};
+
+const WINDOW_WIDTH: i32 = 600;
+const WINDOW_HEIGHT: i32 = 420;
+
+/// Progress reported by the trade list processor when the collection is ready to be shown
+const PROGRESS_COMPLETE: f64 = 95.;
+
+/// The steps of importing the collection, with the progress reported when each step starts
+const IMPORT_STEPS: [(f64, &str); 4] = [
+    (5., "Copy the trade list to appdata"),
+    (10., "Download card data and prices"),
+    (75., "Process the collection"),
+    (PROGRESS_COMPLETE, "Show the collection"),
+];
+
+const TEXT_WELCOME: &str = "\
+Welcome to MTGO Collection Manager!
+
+This guide helps you import your collection. You need a 'Full Trade List' exported from Magic: The Gathering Online:
+
+  1. In MTGO, go to Collection.
+  2. Select the 'Full Trade List' binder, or create a binder with all your cards.
+  3. Right-click the binder and choose 'Export', and save it as a .dek-file.
+
+Click Next when you have exported the file.";
+
+const TEXT_SNAPSHOTS: &str = "\
+Your collection has been imported.
+
+MTGO Collection Manager can record a snapshot of your collection every day while it's running. \
+The snapshots are used for the value history of your collection, and require downloading the latest prices once a day.
+
+You can change this later in the File menu.";
+
+/// The onboarding wizard window
+///
+/// The wizard imports the collection by sending [Message::GotFullTradeList], and follows the import
+/// with the progress updates sent to the menubar, see [OnboardingWizard::set_progress].
+/// When the wizard is completed or skipped it sends [Message::OnboardingCompleted].
+pub struct OnboardingWizard {
+    win: Window,
+    import_page: Flex,
+    steps: Browser,
+    progress: Progress,
+    status: Frame,
+    btn_import_next: Button,
+    import_running: bool,
+    max_progress: f64,
+}
+
+impl OnboardingWizard {
+    /// Create and show the onboarding wizard
+    ///
+    /// # Arguments
+    ///
+    /// * `ev_sender` - Sender for importing the trade list and completing the wizard
+    pub fn new(ev_sender: &Sender<Message>) -> Self {
+        let mut win = Window::default()
+            .with_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+            .with_pos(
+                center().0 - WINDOW_WIDTH / 2,
+                center().1 - WINDOW_HEIGHT / 2,
+            )
+            .with_label("Getting started");
+        win.set_icon(Some(assets::get_logo()));
+        win.make_resizable(true);
+
+        let wizard = Wizard::default().size_of_parent();
+
+        // Welcome and how to export the trade list from MTGO
+        let mut flx_welcome = Flex::default().size_of_parent().column();
+        flx_welcome.set_margin(10);
+        text_display(TEXT_WELCOME);
+        let mut flx_welcome_buttons = Flex::default().row();
+        let mut btn_skip = Button::default().with_label("Skip");
+        Frame::default();
+        let mut btn_welcome_next = Button::default().with_label("Next");
+        flx_welcome_buttons.fixed(&btn_skip, 100);
+        flx_welcome_buttons.fixed(&btn_welcome_next, 100);
+        flx_welcome_buttons.end();
+        flx_welcome.fixed(&flx_welcome_buttons, 30);
+        flx_welcome.end();
+
+        // Choose the trade list
+        let mut flx_file = Flex::default().size_of_parent().column();
+        flx_file.set_margin(10);
+        let lbl_file = Frame::default()
+            .with_label("Choose the exported Full Trade List (.dek)")
+            .with_align(Align::Left | Align::Inside);
+        flx_file.fixed(&lbl_file, 30);
+        let mut flx_path = Flex::default().row();
+        let inp_path = Input::default();
+        let mut btn_browse = Button::default().with_label("Browse...");
+        flx_path.fixed(&btn_browse, 100);
+        flx_path.end();
+        flx_file.fixed(&flx_path, 30);
+        let mut file_status = Frame::default().with_align(Align::Left | Align::Inside | Align::Top);
+        file_status.set_label_font(Font::HelveticaItalic);
+        let mut flx_file_buttons = Flex::default().row();
+        let mut btn_file_back = Button::default().with_label("Back");
+        Frame::default();
+        let mut btn_import = Button::default().with_label("Import");
+        btn_import.deactivate();
+        flx_file_buttons.fixed(&btn_file_back, 100);
+        flx_file_buttons.fixed(&btn_import, 100);
+        flx_file_buttons.end();
+        flx_file.fixed(&flx_file_buttons, 30);
+        flx_file.end();
+
+        // Import progress
+        let mut flx_import = Flex::default().size_of_parent().column();
+        flx_import.set_margin(10);
+        let lbl_import = Frame::default()
+            .with_label("Importing your collection")
+            .with_align(Align::Left | Align::Inside);
+        flx_import.fixed(&lbl_import, 30);
+        let mut steps = Browser::default();
+        flx_import.fixed(&steps, 120);
+        let mut progress = Progress::default();
+        progress.set_selection_color(Color::Green);
+        progress.set_maximum(100.);
+        flx_import.fixed(&progress, 25);
+        let status = Frame::default().with_align(Align::Left | Align::Inside | Align::Top);
+        let mut flx_import_buttons = Flex::default().row();
+        let mut btn_import_back = Button::default().with_label("Back");
+        Frame::default();
+        let mut btn_import_next = Button::default().with_label("Next");
+        btn_import_next.deactivate();
+        flx_import_buttons.fixed(&btn_import_back, 100);
+        flx_import_buttons.fixed(&btn_import_next, 100);
+        flx_import_buttons.end();
+        flx_import.fixed(&flx_import_buttons, 30);
+        flx_import.end();
+
+        // Daily snapshots
+        let mut flx_snapshots = Flex::default().size_of_parent().column();
+        flx_snapshots.set_margin(10);
+        text_display(TEXT_SNAPSHOTS);
+        let chk_snapshots = CheckButton::default().with_label("Record a daily snapshot");
+        flx_snapshots.fixed(&chk_snapshots, 30);
+        let mut flx_snapshots_buttons = Flex::default().row();
+        Frame::default();
+        let mut btn_finish = Button::default().with_label("Finish");
+        flx_snapshots_buttons.fixed(&btn_finish, 100);
+        flx_snapshots_buttons.end();
+        flx_snapshots.fixed(&flx_snapshots_buttons, 30);
+        flx_snapshots.end();
+
+        wizard.end();
+        win.end();
+        win.show();
+
+        let import_page = flx_import.clone();
+        set_step_lines(&mut steps, 0.);
+
+        btn_skip.set_callback({
+            let ev_sender = ev_sender.clone();
+            let mut win = win.clone();
+            move |_| {
+                win.hide();
+                ev_sender.send(Message::OnboardingCompleted(None));
+            }
+        });
+        win.set_callback({
+            let ev_sender = ev_sender.clone();
+            move |w| {
+                if app::event() == Event::Close {
+                    w.hide();
+                    ev_sender.send(Message::OnboardingCompleted(None));
+                }
+            }
+        });
+        btn_welcome_next.set_callback({
+            let mut wizard = wizard.clone();
+            move |_| wizard.next()
+        });
+        btn_file_back.set_callback({
+            let mut wizard = wizard.clone();
+            move |_| wizard.prev()
+        });
+        btn_browse.set_callback({
+            let mut inp_path = inp_path.clone();
+            let mut file_status = file_status.clone();
+            let mut btn_import = btn_import.clone();
+            move |_| {
+                let mut dlg = FileDialog::new(FileDialogType::BrowseFile);
+                dlg.set_option(FileDialogOptions::NoOptions);
+                dlg.set_filter("MTGO Full Trade List\t*.dek");
+                dlg.show();
+                let filename = dlg.filename();
+                if filename.as_os_str().is_empty() {
+                    return;
+                }
+                inp_path.set_value(&filename.to_string_lossy());
+                validate(&filename, &mut file_status, &mut btn_import);
+            }
+        });
+        let mut inp_path_cb = inp_path.clone();
+        inp_path_cb.set_trigger(CallbackTrigger::Changed);
+        inp_path_cb.set_callback({
+            let mut file_status = file_status.clone();
+            let mut btn_import = btn_import.clone();
+            move |inp| {
+                validate(Path::new(&inp.value()), &mut file_status, &mut btn_import);
+            }
+        });
+        btn_import.set_callback({
+            let ev_sender = ev_sender.clone();
+            let mut wizard = wizard.clone();
+            let mut file_status = file_status.clone();
+            move |btn| {
+                let path = PathBuf::from(inp_path.value());
+                if !validate(&path, &mut file_status, btn) {
+                    return;
+                }
+                log::info!("Onboarding: importing full trade list {path:?}");
+                wizard.next();
+                ev_sender.send(Message::GotFullTradeList(path.into()));
+            }
+        });
+        btn_import_back.set_callback({
+            let mut wizard = wizard.clone();
+            move |_| wizard.prev()
+        });
+        btn_import_next.set_callback({
+            let mut wizard = wizard.clone();
+            move |_| wizard.next()
+        });
+        btn_finish.set_callback({
+            let ev_sender = ev_sender.clone();
+            let mut win = win.clone();
+            move |_| {
+                win.hide();
+                ev_sender.send(Message::OnboardingCompleted(Some(
+                    chk_snapshots.is_checked(),
+                )));
+            }
+        });
+
+        Self {
+            win,
+            import_page,
+            steps,
+            progress,
+            status,
+            btn_import_next,
+            import_running: false,
+            max_progress: 0.,
+        }
+    }
+
+    /// Returns true if the wizard window is shown
+    pub fn shown(&self) -> bool {
+        self.win.shown()
+    }
+
+    /// Follow the progress of importing the collection, updating the steps on the import page
+    ///
+    /// The update is ignored if the import page is not shown, e.g. when the collection is processed before
+    /// the user reaches it. An update that hides the progress bar ends the import, which failed if it
+    /// didn't reach [PROGRESS_COMPLETE].
+    pub fn set_progress(&mut self, update: &ProgressUpdate) {
+        if !self.win.shown() || !self.import_page.visible() {
+            return;
+        }
+        if update.show {
+            self.import_running = true;
+            self.max_progress = self.max_progress.max(update.progress);
+            self.progress.set_value(self.max_progress);
+            self.status.set_label("");
+            set_step_lines(&mut self.steps, self.max_progress);
+            return;
+        }
+        if !self.import_running {
+            return;
+        }
+        self.import_running = false;
+        if self.max_progress >= PROGRESS_COMPLETE {
+            self.status
+                .set_label("Your collection was imported, click Next to continue");
+            self.status.set_label_color(Color::Foreground);
+            self.btn_import_next.activate();
+        } else {
+            self.status
+                .set_label("The import failed, go back to choose another file or try again");
+            self.status.set_label_color(Color::Red);
+            self.max_progress = 0.;
+        }
+        self.win.redraw();
+    }
+}
+
+/// A read-only text display filling the rest of the page
+fn text_display(text: &str) -> TextDisplay {
+    let mut buf = TextBuffer::default();
+    buf.set_text(text);
+    let mut txt_disp = TextDisplay::default();
+    txt_disp.set_buffer(buf);
+    txt_disp.wrap_mode(WrapMode::AtBounds, 0);
+    txt_disp
+}
+
+/// Validate the trade list, show the result and only allow importing a valid trade list
+///
+/// # Returns
+///
+/// True if the trade list is valid
+fn validate(path: &Path, status: &mut Frame, btn_import: &mut Button) -> bool {
+    match dek::validate_dek_file(path) {
+        Ok(summary) => {
+            status.set_label(&format!(
+                "Found {quantity} cards in {entries} entries",
+                quantity = summary.quantity,
+                entries = summary.entries
+            ));
+            status.set_label_color(Color::Foreground);
+            btn_import.activate();
+            true
+        }
+        Err(e) => {
+            log::warn!("Onboarding: invalid trade list: {e}");
+            status.set_label(&e);
+            status.set_label_color(Color::Red);
+            btn_import.deactivate();
+            false
+        }
+    }
+}
+
+/// Show each import step as done, in progress or pending
+fn set_step_lines(steps: &mut Browser, progress: f64) {
+    steps.clear();
+    for (idx, (start, label)) in IMPORT_STEPS.iter().enumerate() {
+        let next_start = IMPORT_STEPS.get(idx + 1).map(|(s, _)| *s);
+        let line = if progress < *start {
+            format!("      {label}")
+        } else if next_start.is_none_or(|next| progress < next) && progress < 100. {
+            format!("@b>   {label}...")
+        } else {
+            format!("@.\u{2713}   {label}")
+        };
+        steps.add(&line);
+    }
+}
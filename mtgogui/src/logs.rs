@@ -0,0 +1,174 @@
+//! Reading the log file written by the logger set up in [setup_logger](crate::util::setup_logger).
+//!
+//! Log lines are formatted as `<LEVEL> [<module>] <message>`, a message with line breaks
+//! continues on the following lines without a level.
+
+use std::{
+    fs,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use log::{Level, LevelFilter};
+
+/// The levels the log can be filtered by and the logger can be set to, from least to most verbose
+pub const LEVELS: [LevelFilter; 5] = [
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+/// A line in the log file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    /// The level of the message the line is part of, [None] if the line is not part of a message,
+    /// e.g. the start of a file that was cut off
+    pub level: Option<Level>,
+    pub text: String,
+}
+
+impl LogLine {
+    /// Returns true if the line is at or above the level and contains the search text, ignoring case
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The most verbose level to show
+    /// * `search` - Lowercase text to search for, empty to match all lines
+    pub fn matches(&self, level: LevelFilter, search: &str) -> bool {
+        self.level.is_none_or(|l| l <= level)
+            && (search.is_empty() || self.text.to_lowercase().contains(search))
+    }
+}
+
+/// Follows a log file, reading the lines that were appended since the last read
+#[derive(Debug)]
+pub struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    /// Level of the last message, continuation lines are part of it
+    level: Option<Level>,
+}
+
+impl LogTail {
+    /// Create a new [LogTail] that reads the log file from the start
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            offset: 0,
+            level: None,
+        }
+    }
+
+    /// The path of the followed log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the complete lines that were appended since the last read
+    ///
+    /// If the file was truncated, e.g. by log rotation, it's read from the start again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be read
+    pub fn read_new_lines(&mut self) -> io::Result<Vec<LogLine>> {
+        let mut file = fs::File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.level = None;
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::with_capacity((len - self.offset) as usize);
+        file.read_to_end(&mut buf)?;
+        // A line that is still being written is read next time
+        let complete = match buf.iter().rposition(|b| *b == b'\n') {
+            Some(last_newline) => &buf[..=last_newline],
+            None => return Ok(Vec::new()),
+        };
+        self.offset += complete.len() as u64;
+
+        let lines = String::from_utf8_lossy(complete)
+            .lines()
+            .map(|text| {
+                if let Some(level) = parse_level(text) {
+                    self.level = Some(level);
+                }
+                LogLine {
+                    level: self.level,
+                    text: text.to_string(),
+                }
+            })
+            .collect();
+        Ok(lines)
+    }
+}
+
+/// The level at the start of a line that starts a new message
+fn parse_level(line: &str) -> Option<Level> {
+    let (first, rest) = line.split_once(' ')?;
+    if !rest.starts_with('[') {
+        return None;
+    }
+    Level::from_str(first).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use temp_dir::TempDir;
+
+    #[test]
+    fn test_tail_reads_appended_lines() {
+        let tmp_dir = TempDir::new().unwrap();
+        let log_path = tmp_dir.child("mcm_log_rCURRENT.log");
+        fs::write(
+            &log_path,
+            "INFO [mtgogui::gui] Running startup\nWARN [mtgogui::gui] Failed to load\nGUI state\n",
+        )
+        .unwrap();
+
+        let mut tail = LogTail::new(&log_path);
+        let lines = tail.read_new_lines().unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].level, Some(Level::Info));
+        // The continuation line is part of the warning
+        assert_eq!(lines[2].level, Some(Level::Warn));
+        assert_eq!(lines[2].text, "GUI state");
+
+        let mut file = fs::File::options().append(true).open(&log_path).unwrap();
+        write!(file, "ERROR [mtgogui::gui] Quit\nDEBUG [mtgogui").unwrap();
+        let lines = tail.read_new_lines().unwrap();
+        assert_eq!(
+            lines,
+            vec![LogLine {
+                level: Some(Level::Error),
+                text: "ERROR [mtgogui::gui] Quit".to_string()
+            }]
+        );
+
+        // Rotated
+        fs::write(&log_path, "INFO [mtgogui] New file\n").unwrap();
+        let lines = tail.read_new_lines().unwrap();
+        assert_eq!(lines[0].text, "INFO [mtgogui] New file");
+    }
+
+    #[test]
+    fn test_log_line_matches() {
+        let line = LogLine {
+            level: Some(Level::Debug),
+            text: "DEBUG [mtgogui::collection] Sorted 500 cards".to_string(),
+        };
+
+        assert!(line.matches(LevelFilter::Trace, ""));
+        assert!(line.matches(LevelFilter::Debug, "sorted"));
+        assert!(!line.matches(LevelFilter::Info, ""));
+        assert!(!line.matches(LevelFilter::Debug, "error"));
+        assert_eq!(parse_level("Info without module"), None);
+    }
+}
@@ -23,11 +23,14 @@ use fltk_grid::Grid;
 use fltk_table::{SmartTable, TableOpts};
 use fltk_theme::{widget_themes, ThemeType, WidgetTheme};
 
+mod app_core;
 mod appdata;
 mod assets;
 mod collection;
 mod gui;
+mod logs;
 mod menubar;
+mod notification;
 mod util;
 
 use collection::view::table;
@@ -39,6 +42,7 @@ use mtgoupdater::mtgo_card::MtgoCard;
 use mtgoupdater::mtgo_preprocessor_api::run_mtgo_preprocessor_version;
 use mtgoupdater::mtgogetter_api::mtgogetter_version;
 
+use crate::appdata::update::OfflineMode;
 use crate::util::center;
 
 // Include the `include_binaries.rs` file that's generated by `build.rs`
@@ -60,8 +64,47 @@ pub enum Message {
     MenuBar(menubar::MenubarMessage),
     Table(collection::TableMessage),
     GotFullTradeList(Box<Path>),
+    /// Process the trade list at the given path without copying it to appdata, e.g. to retry a failed update
+    ProcessTradeList(Box<Path>),
     SetCards(Vec<MtgoCard>),
-    SetCollectionStats(CollectionStats),
+    /// An event from the application core, see [AppCore::subscribe](app_core::AppCore::subscribe)
+    Core(app_core::Event),
+    /// Toggle the offline mode setting
+    ToggleOfflineMode,
+    /// Apply the retention policy to the appdata directory
+    CleanupAppdata,
+    /// Downloading the card data failed and the cached data was used instead
+    OfflineModeDetected,
+    /// Import the custom price list at the given path
+    GotPriceList(Box<Path>),
+    /// Display values in the given currency
+    SetDisplayCurrency(appdata::currency::Currency),
+    /// New exchange rates were saved to appdata
+    ExchangeRatesChanged,
+    /// Show the value of the collection over time
+    ShowValueHistory,
+    /// The Scryfall data of the processed collection, used for the card details
+    SetScryfallCards(std::sync::Arc<mtgoupdater::scryfall::ScryfallCards>),
+    /// Show the onboarding wizard
+    ShowOnboarding,
+    /// The onboarding wizard was completed with the daily snapshots choice, or skipped if [None]
+    OnboardingCompleted(Option<bool>),
+    /// Toggle the daily snapshots setting
+    ToggleDailySnapshots,
+    /// Check if the daily collection snapshot is due
+    DailySnapshot,
+    /// Watch the folder for new Full Trade List exports, or stop watching if [None]
+    SetWatchFolder(Option<Box<Path>>),
+    /// Show the log viewer
+    ShowLogs,
+    /// Change the log level at runtime
+    SetLogLevel(log::LevelFilter),
+    /// Export a diagnostics bundle for bug reports
+    ExportDiagnostics,
+    /// Show an error or other notification to the user
+    Notify(Box<notification::Notification>),
+    /// Let the user choose where to save the cards and quantities as a `.dek`-file, with the given preset file name
+    ExportDek(std::sync::Arc<[(MtgoCard, u32)]>, Box<str>),
 }
 
 /// Conversion from [menubar::MenubarMessage] to [Message]
@@ -71,6 +114,13 @@ impl From<menubar::MenubarMessage> for Message {
     }
 }
 
+/// Conversion from [notification::Notification] to [Message]
+impl From<notification::Notification> for Message {
+    fn from(notification: notification::Notification) -> Self {
+        Message::Notify(Box::new(notification))
+    }
+}
+
 /// Conversion from [collection::TableMessage] to [Message]
 impl From<collection::TableMessage> for Message {
     fn from(ct_msg: collection::TableMessage) -> Self {
@@ -88,9 +138,9 @@ fn main() {
     }
 
     // Setup logger (has to be done with a let binding to make the logger live long enough)
-    let _logger = util::setup_logger();
+    let logger = util::setup_logger();
 
-    // Write the raw bytes of MTGOGETTER and MTGO_PREPROCESSOR to disk if they don't exist
+    // Write the raw bytes of MTGOGETTER and MTGO_PREPROCESSOR to disk if they don't exist or are stale
     #[cfg(not(debug_assertions))]
     write_binaries_out().unwrap_or_else(|e| {
         log::error!("Failed to write binaries to bin directory: {e}");
@@ -98,6 +148,11 @@ fn main() {
 
     log::info!("Setup GUI");
     let mut gui = MtgoGui::default();
+    gui.set_logger(logger.clone());
+    if std::env::args().any(|arg| arg == OfflineMode::CLI_FLAG) {
+        log::info!("Starting in offline mode");
+        gui.set_offline_mode(OfflineMode::CliFlag);
+    }
 
     log::info!("Starting GUI");
     gui.run();
@@ -1,4 +1,7 @@
-use crate::{assets, util::center, Message, DEFAULT_APP_WIDTH, MENU_BAR_HEIGHT, MIN_APP_WIDTH};
+use crate::{
+    appdata::currency::Currency, assets, util::center, Message, DEFAULT_APP_WIDTH, MENU_BAR_HEIGHT,
+    MIN_APP_WIDTH,
+};
 use fltk::{
     app::{self, Sender},
     dialog::{self, FileDialog, FileDialogOptions, FileDialogType},
@@ -17,6 +20,8 @@ use std::{io, path::PathBuf};
 use util::ProgressUpdate;
 
 mod about;
+mod exchange_rates;
+pub mod log_viewer;
 mod setup;
 pub mod util;
 
@@ -24,6 +29,9 @@ pub mod util;
 #[derive(Debug, Clone)]
 pub enum MenubarMessage {
     Open,
+    ImportPriceList,
+    WatchFolder,
+    ExchangeRates,
     Quit,
     About,
     Example,
@@ -39,6 +47,10 @@ pub struct McmMenuBar {
 
 impl McmMenuBar {
     pub const PROGRESS_BAR_WIDTH: i32 = 300;
+    /// Path of the menu item that toggles offline mode
+    const OFFLINE_MODE_ITEM: &'static str = "&File/Offline mode\t";
+    /// Path of the menu item that toggles daily snapshots
+    const DAILY_SNAPSHOTS_ITEM: &'static str = "&File/Daily snapshots\t";
 
     /// Creates a new menubar
     ///
@@ -82,6 +94,11 @@ impl McmMenuBar {
     pub fn handle_ev(&mut self, ev: MenubarMessage) {
         match ev {
             MenubarMessage::Open => self.open_full_tradelist(),
+            MenubarMessage::ImportPriceList => self.open_price_list(),
+            MenubarMessage::WatchFolder => self.choose_watch_folder(),
+            MenubarMessage::ExchangeRates => {
+                exchange_rates::show_exchange_rates(self.ev_emitter.clone())
+            }
             MenubarMessage::Quit => app::quit(),
             MenubarMessage::About => about::show_about(),
             MenubarMessage::Example => todo!("example"),
@@ -109,6 +126,46 @@ impl McmMenuBar {
         }
     }
 
+    /// Check or uncheck the offline mode menu item
+    pub fn set_offline_mode_checked(&mut self, checked: bool) {
+        if let Some(mut item) = self.menu.find_item(Self::OFFLINE_MODE_ITEM) {
+            if checked {
+                item.set();
+            } else {
+                item.clear();
+            }
+        }
+    }
+
+    /// Check or uncheck the daily snapshots menu item
+    pub fn set_daily_snapshots_checked(&mut self, checked: bool) {
+        if let Some(mut item) = self.menu.find_item(Self::DAILY_SNAPSHOTS_ITEM) {
+            if checked {
+                item.set();
+            } else {
+                item.clear();
+            }
+        }
+    }
+
+    /// Check the menu item of the display currency and uncheck the others
+    pub fn set_currency_checked(&mut self, currency: Currency) {
+        for c in Currency::ALL {
+            if let Some(mut item) = self.menu.find_item(&Self::currency_item(c)) {
+                if c == currency {
+                    item.set();
+                } else {
+                    item.clear();
+                }
+            }
+        }
+    }
+
+    /// Path of the menu item that selects the display currency
+    fn currency_item(currency: Currency) -> String {
+        format!("&View/Currency/{}\t", currency.name())
+    }
+
     fn open_full_tradelist(&mut self) {
         let mut dlg = FileDialog::new(FileDialogType::BrowseFile);
         dlg.set_option(FileDialogOptions::NoOptions);
@@ -125,4 +182,37 @@ impl McmMenuBar {
             }
         }
     }
+
+    fn choose_watch_folder(&mut self) {
+        let mut dlg = FileDialog::new(FileDialogType::BrowseDir);
+        dlg.set_option(FileDialogOptions::NoOptions);
+        dlg.set_title("Folder to watch for Full Trade List exports");
+        dlg.show();
+        let dirname = dlg.filename();
+        if !dirname.to_string_lossy().to_string().is_empty() {
+            if dirname.is_dir() {
+                log::info!("Watch folder: {:?}", dirname);
+                self.ev_emitter
+                    .send(Message::SetWatchFolder(Some(dirname.into())));
+            } else {
+                dialog::alert(center().0 - 200, center().1 - 100, "Folder does not exist!")
+            }
+        }
+    }
+
+    fn open_price_list(&mut self) {
+        let mut dlg = FileDialog::new(FileDialogType::BrowseFile);
+        dlg.set_option(FileDialogOptions::NoOptions);
+        dlg.set_filter("Price list\t*.{csv,json}");
+        dlg.show();
+        let filename = dlg.filename();
+        if !filename.to_string_lossy().to_string().is_empty() {
+            if filename.is_file() {
+                log::info!("Price list: {:?}", filename);
+                self.ev_emitter.send(Message::GotPriceList(filename.into()));
+            } else {
+                dialog::alert(center().0 - 200, center().1 - 100, "File does not exist!")
+            }
+        }
+    }
 }
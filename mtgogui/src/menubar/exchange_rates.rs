@@ -0,0 +1,157 @@
+use std::{cell::RefCell, rc::Rc};
+
+use fltk::{
+    app::Sender,
+    browser::Browser,
+    button::Button,
+    dialog,
+    frame::Frame,
+    input::FloatInput,
+    prelude::{BrowserExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt},
+    window::Window,
+};
+use fltk_flex::Flex;
+
+use crate::{
+    appdata::{
+        self,
+        currency::{Currency, ExchangeRates},
+    },
+    assets,
+    util::center,
+    Message,
+};
+
+const WINDOW_WIDTH: i32 = 400;
+const WINDOW_HEIGHT: i32 = 400;
+/// The currencies with an exchange rate from tix
+const CURRENCIES: [Currency; 2] = [Currency::Usd, Currency::Eur];
+
+/// Show a window to set the exchange rates from tix to each currency, with the history of previous rates
+///
+/// A new rate takes effect when it's saved, the previous rates are kept to convert historical values.
+/// [Message::ExchangeRatesChanged] is sent when new rates are saved.
+///
+/// # Arguments
+///
+/// * `ev_sender` - Sender to notify the main thread of the new rates
+pub fn show_exchange_rates(ev_sender: Sender<Message>) {
+    let appdata_dir = match appdata::util::appdata_path() {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::error!("Failed to get appdata path: {e}");
+            dialog::alert(
+                center().0 - 200,
+                center().1 - 100,
+                &format!("Failed to get appdata path!\n{e}"),
+            );
+            return;
+        }
+    };
+    let rates = match ExchangeRates::load(&appdata_dir) {
+        Ok(rates) => rates,
+        Err(e) => {
+            log::warn!("Failed to load exchange rates: {e}");
+            ExchangeRates::default()
+        }
+    };
+    let rates = Rc::new(RefCell::new(rates));
+
+    let mut win = Window::default()
+        .with_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .with_pos(
+            center().0 - WINDOW_WIDTH / 2,
+            center().1 - WINDOW_HEIGHT / 2,
+        )
+        .with_label("Exchange rates");
+    win.set_icon(Some(assets::get_logo()));
+    win.make_resizable(true);
+
+    let mut flx_main = Flex::default().size_of_parent().column();
+    flx_main.set_margin(5);
+
+    let mut inputs = Vec::new();
+    for currency in CURRENCIES {
+        let mut flx_rate = Flex::default().row();
+        let lbl = Frame::default().with_label(&format!("1 tix in {}", currency.name()));
+        let mut inp_rate = FloatInput::default();
+        if let Some(rate) = rates.borrow().history(currency).last() {
+            inp_rate.set_value(&rate.rate.to_string());
+        }
+        flx_rate.fixed(&lbl, 120);
+        flx_rate.end();
+        flx_main.fixed(&flx_rate, 30);
+        inputs.push((currency, inp_rate));
+    }
+
+    let mut browser = Browser::default();
+    browser.set_column_widths(&[170, 80, 80]);
+    browser.set_column_char('\t');
+    fill_history(&mut browser, &rates.borrow());
+
+    let mut btn_save = Button::default().with_label("Save");
+    flx_main.fixed(&btn_save, 30);
+
+    flx_main.end();
+    win.end();
+    win.show();
+
+    btn_save.set_callback(move |_| {
+        let now = chrono::Utc::now();
+        let mut rates = rates.borrow_mut();
+        let mut changed = false;
+        for (currency, inp_rate) in &inputs {
+            if inp_rate.value().trim().is_empty() {
+                continue;
+            }
+            let rate = match inp_rate.value().trim().parse::<f64>() {
+                Ok(rate) if rate > 0. => rate,
+                _ => {
+                    dialog::alert(
+                        center().0 - 200,
+                        center().1 - 100,
+                        &format!("The {} rate must be a positive number", currency.name()),
+                    );
+                    return;
+                }
+            };
+            if rates.rate_at(*currency, now) != Some(rate) {
+                rates.set_rate(*currency, rate, now);
+                changed = true;
+            }
+        }
+        if !changed {
+            return;
+        }
+        if let Err(e) = rates.save(&appdata_dir) {
+            log::error!("Failed to save exchange rates: {e}");
+            dialog::alert(
+                center().0 - 200,
+                center().1 - 100,
+                &format!("Failed to save exchange rates!\n{e}"),
+            );
+            return;
+        }
+        fill_history(&mut browser, &rates);
+        ev_sender.send(Message::ExchangeRatesChanged);
+    });
+}
+
+/// Fill the browser with every rate of each currency, newest first
+fn fill_history(browser: &mut Browser, rates: &ExchangeRates) {
+    browser.clear();
+    browser.add("@bEffective from\t@bCurrency\t@bRate");
+    let mut history: Vec<_> = CURRENCIES
+        .iter()
+        .flat_map(|c| rates.history(*c).iter().map(move |r| (*c, r)))
+        .collect();
+    history.sort_by_key(|(_, r)| std::cmp::Reverse(r.effective_from));
+    for (currency, rate) in history {
+        browser.add(&format!(
+            "{date}\t{currency}\t{rate}",
+            date = rate.effective_from.format("%Y-%m-%d %H:%M"),
+            currency = currency.name(),
+            rate = rate.rate
+        ));
+    }
+}
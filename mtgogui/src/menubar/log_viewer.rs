@@ -0,0 +1,193 @@
+use std::{cell::RefCell, rc::Rc};
+
+use flexi_logger::{LogfileSelector, LoggerHandle};
+use fltk::{
+    app::{self, Sender},
+    dialog,
+    enums::{CallbackTrigger, Font},
+    frame::Frame,
+    input::Input,
+    menu::Choice,
+    prelude::{DisplayExt, GroupExt, InputExt, MenuExt, WidgetBase, WidgetExt, WindowExt},
+    text::{TextBuffer, TextDisplay},
+    window::Window,
+};
+use fltk_flex::Flex;
+use log::LevelFilter;
+
+use crate::{
+    assets,
+    logs::{LogLine, LogTail, LEVELS},
+    util::center,
+    Message,
+};
+
+const WINDOW_WIDTH: i32 = 1000;
+const WINDOW_HEIGHT: i32 = 600;
+/// Seconds between reading new lines from the log file
+const TAIL_INTERVAL: f64 = 1.0;
+/// The oldest lines are dropped when there's more lines than this
+const MAX_LINES: usize = 20_000;
+
+/// Show a window that follows the current log file, with a level filter, search and a control for the log level
+///
+/// # Arguments
+///
+/// * `logger` - Handle to the logger, used to find and flush the current log file
+/// * `log_level` - The current log level
+/// * `ev_sender` - Sender for [Message::SetLogLevel] when the log level is changed
+pub fn show_log_viewer(logger: LoggerHandle, log_level: LevelFilter, ev_sender: Sender<Message>) {
+    let log_file = match logger.existing_log_files(&LogfileSelector::none().with_r_current()) {
+        Ok(files) if !files.is_empty() => files[files.len() - 1].clone(),
+        Ok(_) => {
+            dialog::alert(center().0 - 200, center().1 - 100, "No log file found!");
+            return;
+        }
+        Err(e) => {
+            log::error!("Failed to find the log file: {e}");
+            dialog::alert(
+                center().0 - 200,
+                center().1 - 100,
+                &format!("Failed to find the log file!\n{e}"),
+            );
+            return;
+        }
+    };
+    let tail = Rc::new(RefCell::new(LogTail::new(&log_file)));
+    let lines: Rc<RefCell<Vec<LogLine>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let mut win = Window::default()
+        .with_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .with_pos(
+            center().0 - WINDOW_WIDTH / 2,
+            center().1 - WINDOW_HEIGHT / 2,
+        )
+        .with_label(&format!("Logs - {}", log_file.display()));
+    win.set_icon(Some(assets::get_logo()));
+    win.make_resizable(true);
+
+    let mut flx_main = Flex::default().size_of_parent().column();
+    flx_main.set_margin(5);
+
+    let mut flx_controls = Flex::default().row();
+    let lbl_show = Frame::default().with_label("Show");
+    let mut choice_show = Choice::default();
+    let mut inp_search = Input::default();
+    inp_search.set_trigger(CallbackTrigger::Changed);
+    let lbl_level = Frame::default().with_label("Log level");
+    let mut choice_level = Choice::default();
+    for level in LEVELS {
+        choice_show.add_choice(level.as_str());
+        choice_level.add_choice(level.as_str());
+    }
+    choice_show.set_value(LEVELS.len() as i32 - 1);
+    choice_level.set_value(
+        LEVELS
+            .iter()
+            .position(|l| *l == log_level)
+            .unwrap_or_default() as i32,
+    );
+    inp_search.set_tooltip("Search");
+    flx_controls.fixed(&lbl_show, 50);
+    flx_controls.fixed(&choice_show, 90);
+    flx_controls.fixed(&lbl_level, 80);
+    flx_controls.fixed(&choice_level, 90);
+    flx_controls.end();
+    flx_main.fixed(&flx_controls, 30);
+
+    let buf = TextBuffer::default();
+    let mut txt_log = TextDisplay::default();
+    txt_log.set_buffer(buf.clone());
+    txt_log.set_text_font(Font::Courier);
+    txt_log.set_text_size(12);
+
+    flx_main.end();
+    win.end();
+    win.show();
+
+    // The filter of the lines shown
+    let filter = {
+        let choice_show = choice_show.clone();
+        let inp_search = inp_search.clone();
+        move || {
+            let level = LEVELS
+                .get(choice_show.value() as usize)
+                .copied()
+                .unwrap_or(LevelFilter::Trace);
+            (level, inp_search.value().to_lowercase())
+        }
+    };
+
+    let refilter = {
+        let lines = lines.clone();
+        let mut buf = buf.clone();
+        let mut txt_log = txt_log.clone();
+        let filter = filter.clone();
+        move || {
+            let (level, search) = filter();
+            let text: String = lines
+                .borrow()
+                .iter()
+                .filter(|line| line.matches(level, &search))
+                .map(|line| format!("{}\n", line.text))
+                .collect();
+            buf.set_text(&text);
+            txt_log.scroll(txt_log.count_lines(0, buf.length(), true), 0);
+        }
+    };
+
+    choice_show.set_callback({
+        let mut refilter = refilter.clone();
+        move |_| refilter()
+    });
+    inp_search.set_callback({
+        let mut refilter = refilter.clone();
+        move |_| refilter()
+    });
+    choice_level.set_callback(move |c| {
+        if let Some(level) = LEVELS.get(c.value() as usize) {
+            ev_sender.send(Message::SetLogLevel(*level));
+        }
+    });
+
+    app::add_timeout3(0., {
+        let mut buf = buf.clone();
+        let mut refilter = refilter.clone();
+        move |handle| {
+            if !win.shown() {
+                return;
+            }
+            logger.flush();
+            let new_lines = match tail.borrow_mut().read_new_lines() {
+                Ok(new_lines) => new_lines,
+                Err(e) => {
+                    // Don't log the error, it would be appended to the log
+                    buf.append(&format!("Failed to read the log file: {e}\n"));
+                    return;
+                }
+            };
+            if !new_lines.is_empty() {
+                let (level, search) = filter();
+                let text: String = new_lines
+                    .iter()
+                    .filter(|line| line.matches(level, &search))
+                    .map(|line| format!("{}\n", line.text))
+                    .collect();
+                let trimmed = {
+                    let mut lines = lines.borrow_mut();
+                    lines.extend(new_lines);
+                    let excess = lines.len().saturating_sub(MAX_LINES);
+                    lines.drain(..excess);
+                    excess > 0
+                };
+                if trimmed {
+                    refilter();
+                } else if !text.is_empty() {
+                    buf.append(&text);
+                    txt_log.scroll(txt_log.count_lines(0, buf.length(), true), 0);
+                }
+            }
+            app::repeat_timeout3(TAIL_INTERVAL, handle);
+        }
+    });
+}
@@ -1,11 +1,16 @@
-use super::MenubarMessage;
-use crate::Message;
+use super::{McmMenuBar, MenubarMessage};
+use crate::{
+    appdata::currency::Currency,
+    collection::{clipboard::CopyFormat, stats::group::GroupBy, TableMessage},
+    Message,
+};
 use fltk::{
     app::Sender,
     enums::{Color, FrameType, Shortcut},
     menu::{self, MenuFlag},
     prelude::{MenuExt, WidgetExt},
 };
+use mtgoupdater::{mtgo_card::ItemCategory, scryfall::FORMATS};
 
 /// Initialize the menubar, adding all the menu items
 pub(super) fn init_menu_bar(menu: &mut menu::SysMenuBar, s: &Sender<Message>) {
@@ -19,6 +24,62 @@ pub(super) fn init_menu_bar(menu: &mut menu::SysMenuBar, s: &Sender<Message>) {
         MenubarMessage::Open.into(),
     );
 
+    menu.add_emit(
+        "&File/Import price list...\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        MenubarMessage::ImportPriceList.into(),
+    );
+
+    menu.add_emit(
+        "&File/Watch folder/Choose folder...\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        MenubarMessage::WatchFolder.into(),
+    );
+
+    menu.add_emit(
+        "&File/Watch folder/Stop watching\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        Message::SetWatchFolder(None),
+    );
+
+    menu.add_emit(
+        "&File/Exchange rates...\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        MenubarMessage::ExchangeRates.into(),
+    );
+
+    menu.add_emit(
+        McmMenuBar::OFFLINE_MODE_ITEM,
+        Shortcut::None,
+        MenuFlag::Toggle,
+        s.clone(),
+        Message::ToggleOfflineMode,
+    );
+
+    menu.add_emit(
+        McmMenuBar::DAILY_SNAPSHOTS_ITEM,
+        Shortcut::None,
+        MenuFlag::Toggle,
+        s.clone(),
+        Message::ToggleDailySnapshots,
+    );
+
+    menu.add_emit(
+        "&File/Clean up appdata\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        Message::CleanupAppdata,
+    );
+
     menu.add_emit(
         "&File/Quit\t",
         Shortcut::Ctrl | 'q',
@@ -27,6 +88,156 @@ pub(super) fn init_menu_bar(menu: &mut menu::SysMenuBar, s: &Sender<Message>) {
         Message::Quit,
     );
 
+    menu.add_emit(
+        "&Edit/Copy\t",
+        Shortcut::Ctrl | 'c',
+        MenuFlag::Normal,
+        s.clone(),
+        TableMessage::Copy(CopyFormat::Text).into(),
+    );
+
+    menu.add_emit(
+        "&Edit/Copy as .dek\t",
+        Shortcut::Ctrl | Shortcut::Shift | 'c',
+        MenuFlag::Normal,
+        s.clone(),
+        TableMessage::Copy(CopyFormat::Dek).into(),
+    );
+
+    menu.add_emit(
+        "&Edit/Copy as MTGO list\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        TableMessage::Copy(CopyFormat::MtgoList).into(),
+    );
+
+    menu.add_emit(
+        "&View/Show/All\t",
+        Shortcut::None,
+        MenuFlag::Radio | MenuFlag::Value,
+        s.clone(),
+        TableMessage::FilterCategory(None).into(),
+    );
+
+    for category in [
+        ItemCategory::Card,
+        ItemCategory::Sealed,
+        ItemCategory::EventTicket,
+    ] {
+        menu.add_emit(
+            &format!("&View/Show/{}\t", category.name()),
+            Shortcut::None,
+            MenuFlag::Radio,
+            s.clone(),
+            TableMessage::FilterCategory(Some(category)).into(),
+        );
+    }
+
+    menu.add_emit(
+        "&View/Format/Any\t",
+        Shortcut::None,
+        MenuFlag::Radio | MenuFlag::Value,
+        s.clone(),
+        TableMessage::FilterFormat(None).into(),
+    );
+
+    for (format, name) in FORMATS {
+        menu.add_emit(
+            &format!("&View/Format/{name}\t"),
+            Shortcut::None,
+            MenuFlag::Radio,
+            s.clone(),
+            TableMessage::FilterFormat(Some(format.into())).into(),
+        );
+    }
+
+    menu.add_emit(
+        "&View/Group by/Set\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        TableMessage::GroupBy(GroupBy::Set).into(),
+    );
+
+    menu.add_emit(
+        "&View/Group by/Rarity\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        TableMessage::GroupBy(GroupBy::Rarity).into(),
+    );
+
+    menu.add_emit(
+        "&View/Group by/Foil\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        TableMessage::GroupBy(GroupBy::Foil).into(),
+    );
+
+    menu.add_emit(
+        "&View/Vendor arbitrage...\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        TableMessage::ShowArbitrage.into(),
+    );
+
+    menu.add_emit(
+        "&View/Sell list...\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        TableMessage::ShowSellList.into(),
+    );
+
+    for (idx, currency) in Currency::ALL.into_iter().enumerate() {
+        menu.add_emit(
+            &McmMenuBar::currency_item(currency),
+            Shortcut::None,
+            if idx == 0 {
+                MenuFlag::Radio | MenuFlag::Value
+            } else {
+                MenuFlag::Radio
+            },
+            s.clone(),
+            Message::SetDisplayCurrency(currency),
+        );
+    }
+
+    menu.add_emit(
+        "&View/Value history...\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        Message::ShowValueHistory,
+    );
+
+    menu.add_emit(
+        "&Help/Getting started...\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        Message::ShowOnboarding,
+    );
+
+    menu.add_emit(
+        "&Help/Show Logs\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        Message::ShowLogs,
+    );
+
+    menu.add_emit(
+        "&Help/Export Diagnostics...\t",
+        Shortcut::None,
+        MenuFlag::Normal,
+        s.clone(),
+        Message::ExportDiagnostics,
+    );
+
     menu.add_emit(
         "&Help/About\t",
         Shortcut::None,
@@ -5,7 +5,9 @@ use fltk::{
     text::{self, TextAttr, TextBuffer},
 };
 use mtgoupdater::{
-    mtgo_preprocessor_api::run_mtgo_preprocessor_version, mtgogetter_api::mtgogetter_version,
+    bin_check::{MTGOGETTER_COMPATIBLE, MTGO_PREPROCESSOR_COMPATIBLE},
+    mtgo_preprocessor_api::run_mtgo_preprocessor_version,
+    mtgogetter_api::mtgogetter_version,
 };
 
 use crate::{util::RelativeSize, DEFAULT_APP_WIDTH, MENU_BAR_HEIGHT};
@@ -64,15 +66,23 @@ impl ProgressUpdate {
 /// Get the version of the MTGO Getter binary (X.Y.Z) and return it as a string
 ///
 /// # Errors
-/// Returns an error if the MTGO Getter binary cannot be found
+/// Returns an error if the MTGO Getter binary cannot be found or its output doesn't contain a version
 pub fn mtgogetter_version_str() -> Result<String, io::Error> {
     let mtgogetter_version = match mtgogetter_version() {
         Ok(v) => v,
         Err(e) => return Err(e),
     };
     let mtgogetter_version_str = String::from_utf8_lossy(&mtgogetter_version.stdout);
-    let version_pos = mtgogetter_version_str.trim().find("version ").unwrap();
-    Ok(mtgogetter_version_str[version_pos + 8..].to_string())
+    let version_pos = mtgogetter_version_str
+        .trim()
+        .find("version ")
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unexpected MTGO Getter version output: {mtgogetter_version_str:?}"),
+            )
+        })?;
+    Ok(mtgogetter_version_str[version_pos + 8..].trim().to_string())
 }
 
 /// Get the version of the MTGO Preprocessor binary (X.Y.Z) and return it as a string
@@ -89,6 +99,35 @@ pub fn mtgo_preprocessor_version() -> Result<String, io::Error> {
         .to_string())
 }
 
+/// Check that the versions of the MTGO Getter and MTGO Preprocessor binaries are compatible with MTGO Updater
+///
+/// # Returns
+///
+/// A description of each binary that couldn't be run or has an incompatible version, empty if both are fine
+pub fn check_helper_versions() -> Vec<String> {
+    [
+        (
+            "MTGO Getter",
+            mtgogetter_version_str(),
+            MTGOGETTER_COMPATIBLE,
+        ),
+        (
+            "MTGO Preprocessor",
+            mtgo_preprocessor_version(),
+            MTGO_PREPROCESSOR_COMPATIBLE,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, version, range)| {
+        let result = match version {
+            Ok(version) => range.check(name, &version).map(|_| ()),
+            Err(e) => Err(format!("Failed to get the version of {name}: {e}")),
+        };
+        result.err()
+    })
+    .collect()
+}
+
 /// A text buffer and its associated style buffer
 #[derive(Debug)]
 pub struct TextBufferStylePair {
@@ -0,0 +1,317 @@
+//! Notifications shown to the user, from short-lived toasts to modal error dialogs.
+//!
+//! Any part of the GUI, including background threads, reports errors and notable events
+//! by sending a [Message::Notify] with a [Notification] to the main event loop.
+
+use fltk::{
+    app::{self, Sender},
+    button::Button,
+    enums::{Align, Color, Font, FrameType},
+    frame::Frame,
+    prelude::{DisplayExt, GroupExt, WidgetBase, WidgetExt, WindowExt},
+    text::{TextBuffer, TextDisplay, WrapMode},
+    window::Window,
+};
+use fltk_flex::Flex;
+
+use crate::{assets, menubar::MenubarMessage, util::center, Message};
+
+const DIALOG_WIDTH: i32 = 500;
+const DIALOG_HEIGHT: i32 = 300;
+const TOAST_WIDTH: i32 = 350;
+const TOAST_HEIGHT: i32 = 50;
+/// Seconds a toast is shown before it's hidden
+const TOAST_TIMEOUT: f64 = 4.0;
+
+/// How severe a [Notification] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// The title of the dialog window
+    pub fn title(&self) -> &'static str {
+        match self {
+            Severity::Info => "Information",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Severity::Info => Color::DarkGreen,
+            Severity::Warning => Color::DarkYellow,
+            Severity::Error => Color::DarkRed,
+        }
+    }
+}
+
+/// An action the user can take from a notification, e.g. retry the operation that failed
+#[derive(Debug, Clone)]
+pub struct NotificationAction {
+    label: Box<str>,
+    message: Box<Message>,
+}
+
+impl NotificationAction {
+    /// Create an action with a button that sends the message
+    pub fn new(label: &str, message: Message) -> Self {
+        Self {
+            label: label.into(),
+            message: Box::new(message),
+        }
+    }
+
+    /// Retry the failed operation by sending the message again
+    pub fn retry(message: Message) -> Self {
+        Self::new("Retry", message)
+    }
+
+    /// Let the user choose another full trade list
+    pub fn choose_another_file() -> Self {
+        Self::new("Choose another file...", MenubarMessage::Open.into())
+    }
+
+    /// The label of the button
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// A message to the user with optional details and actions
+#[derive(Debug, Clone)]
+pub struct Notification {
+    severity: Severity,
+    summary: Box<str>,
+    details: Option<Box<str>>,
+    actions: Vec<NotificationAction>,
+    toast: bool,
+}
+
+impl Notification {
+    /// An error shown in a modal dialog
+    pub fn error(summary: &str) -> Self {
+        Self::new(Severity::Error, summary, false)
+    }
+
+    /// A warning shown in a modal dialog
+    pub fn warning(summary: &str) -> Self {
+        Self::new(Severity::Warning, summary, false)
+    }
+
+    /// Information shown in a toast that disappears by itself
+    pub fn info(summary: &str) -> Self {
+        Self::new(Severity::Info, summary, true)
+    }
+
+    fn new(severity: Severity, summary: &str, toast: bool) -> Self {
+        Self {
+            severity,
+            summary: summary.into(),
+            details: None,
+            actions: Vec::new(),
+            toast,
+        }
+    }
+
+    /// Add details, e.g. the underlying error, shown below the summary and included when copying
+    pub fn with_details(mut self, details: impl ToString) -> Self {
+        self.details = Some(details.to_string().into());
+        self
+    }
+
+    /// Add an action the user can take, which also makes a toast a modal dialog
+    pub fn with_action(mut self, action: NotificationAction) -> Self {
+        self.actions.push(action);
+        self.toast = false;
+        self
+    }
+
+    /// Show the notification in a modal dialog even if it would be a toast
+    pub fn modal(mut self) -> Self {
+        self.toast = false;
+        self
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    pub fn actions(&self) -> &[NotificationAction] {
+        &self.actions
+    }
+
+    /// Returns true if the notification is shown as a toast rather than a modal dialog
+    pub fn is_toast(&self) -> bool {
+        self.toast
+    }
+
+    /// The text copied with the "Copy details" button
+    pub fn details_text(&self) -> String {
+        match &self.details {
+            Some(details) => format!(
+                "{severity}: {summary}\n\n{details}",
+                severity = self.severity.title(),
+                summary = self.summary
+            ),
+            None => format!(
+                "{severity}: {summary}",
+                severity = self.severity.title(),
+                summary = self.summary
+            ),
+        }
+    }
+
+    /// Log the notification at the level matching its severity
+    pub fn log(&self) {
+        let details = self.details.as_deref().unwrap_or_default();
+        match self.severity {
+            Severity::Info => log::info!("{} {details}", self.summary),
+            Severity::Warning => log::warn!("{} {details}", self.summary),
+            Severity::Error => log::error!("{} {details}", self.summary),
+        }
+    }
+}
+
+/// Show the notification as a toast or a modal dialog
+///
+/// # Arguments
+///
+/// * `notification` - The notification to show
+/// * `ev_sender` - Sender for the messages of the notification actions
+pub fn show(notification: &Notification, ev_sender: &Sender<Message>) {
+    notification.log();
+    if notification.is_toast() {
+        show_toast(notification);
+    } else {
+        show_dialog(notification, ev_sender);
+    }
+}
+
+/// Show a borderless window at the bottom of the screen that hides itself after [TOAST_TIMEOUT]
+fn show_toast(notification: &Notification) {
+    let mut win = Window::default()
+        .with_size(TOAST_WIDTH, TOAST_HEIGHT)
+        .with_pos(
+            center().0 - TOAST_WIDTH / 2,
+            center().1 * 2 - TOAST_HEIGHT * 3,
+        );
+    win.set_border(false);
+    win.set_color(notification.severity().color());
+    let mut frame = Frame::default_fill().with_label(notification.summary());
+    frame.set_label_color(Color::White);
+    frame.set_align(Align::Center | Align::Inside | Align::Wrap);
+    win.end();
+    win.show();
+
+    app::add_timeout3(TOAST_TIMEOUT, move |_| {
+        win.hide();
+        app::delete_widget(win.clone());
+    });
+}
+
+/// Show a modal dialog with the summary, the details, a button to copy the details and a button per action
+fn show_dialog(notification: &Notification, ev_sender: &Sender<Message>) {
+    let mut win = Window::default()
+        .with_size(DIALOG_WIDTH, DIALOG_HEIGHT)
+        .with_pos(
+            center().0 - DIALOG_WIDTH / 2,
+            center().1 - DIALOG_HEIGHT / 2,
+        )
+        .with_label(notification.severity().title());
+    win.set_icon(Some(assets::get_logo()));
+    win.make_resizable(true);
+    win.make_modal(true);
+
+    let mut flx_main = Flex::default().size_of_parent().column();
+    flx_main.set_margin(5);
+
+    let mut summary = Frame::default().with_label(notification.summary());
+    summary.set_frame(FrameType::FlatBox);
+    summary.set_color(notification.severity().color());
+    summary.set_label_color(Color::White);
+    summary.set_label_font(Font::HelveticaBold);
+    summary.set_align(Align::Left | Align::Inside | Align::Wrap);
+    flx_main.fixed(&summary, 50);
+
+    let mut details = TextBuffer::default();
+    details.set_text(notification.details.as_deref().unwrap_or_default());
+    let mut txt_details = TextDisplay::default();
+    txt_details.set_buffer(details);
+    txt_details.wrap_mode(WrapMode::AtBounds, 0);
+
+    let flx_buttons = Flex::default().row();
+    let mut btn_copy = Button::default().with_label("Copy details");
+    let action_buttons: Vec<(Button, Message)> = notification
+        .actions()
+        .iter()
+        .map(|action| {
+            (
+                Button::default().with_label(action.label()),
+                (*action.message).clone(),
+            )
+        })
+        .collect();
+    let mut btn_close = Button::default().with_label("Close");
+    flx_buttons.end();
+    flx_main.fixed(&flx_buttons, 30);
+
+    flx_main.end();
+    win.end();
+    win.show();
+
+    let details_text = notification.details_text();
+    btn_copy.set_callback(move |_| app::copy(&details_text));
+    for (mut btn, message) in action_buttons {
+        let ev_sender = ev_sender.clone();
+        let mut win = win.clone();
+        btn.set_callback(move |_| {
+            win.hide();
+            ev_sender.send(message.clone());
+        });
+    }
+    btn_close.set_callback({
+        let mut win = win.clone();
+        move |_| win.hide()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_notification_details_text() {
+        let notification = Notification::error("Failed to copy the trade list")
+            .with_details("Permission denied (os error 13)");
+
+        assert_eq!(
+            notification.details_text(),
+            "Error: Failed to copy the trade list\n\nPermission denied (os error 13)"
+        );
+        assert_eq!(
+            Notification::info("Imported 3 prices").details_text(),
+            "Information: Imported 3 prices"
+        );
+    }
+
+    #[test]
+    fn test_actions_make_toasts_modal() {
+        let toast = Notification::info("Collection updated");
+        assert!(toast.is_toast());
+
+        let with_action = toast.with_action(NotificationAction::choose_another_file());
+        assert!(!with_action.is_toast());
+        assert_eq!(with_action.actions()[0].label(), "Choose another file...");
+        assert!(!Notification::error("Failed").is_toast());
+    }
+}
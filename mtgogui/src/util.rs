@@ -90,9 +90,9 @@ pub fn setup_logger() -> LoggerHandle {
     // Remove the executable from the path, then the path points at the directory
     appdata_dir.pop();
     // Add the appdata directory to the path
-    appdata_dir.push("appdata");
+    appdata_dir.push(crate::appdata::APP_DATA_DIR);
     // Add the log_files directory to the path
-    appdata_dir.push("log_files");
+    appdata_dir.push(crate::appdata::LOG_FILES_DIR);
 
     // 5 MiB
     const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
@@ -0,0 +1,204 @@
+//! Self-check of the `MTGO Getter` and `MTGO Preprocessor` binaries.
+//!
+//! The binaries are embedded in the GUI and extracted next to it. A binary left over from another release,
+//! or one that was corrupted, is detected by comparing it to the embedded binary and re-extracted. The version each binary reports
+//! is checked against the range of versions that are compatible with this version of `MTGO Updater`.
+
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+/// The versions of `MTGO Getter` that are compatible with this version of `MTGO Updater`
+pub const MTGOGETTER_COMPATIBLE: VersionRange =
+    VersionRange::new(Version::new(0, 1, 0), Version::new(0, 2, 0));
+
+/// The versions of `MTGO Preprocessor` that are compatible with this version of `MTGO Updater`
+pub const MTGO_PREPROCESSOR_COMPATIBLE: VersionRange =
+    VersionRange::new(Version::new(0, 1, 0), Version::new(0, 2, 0));
+
+/// A semantic version, pre-release and build metadata are ignored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    /// Parse a version like `0.1.0`, `v0.1.0` or `0.1.0-rc1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let version = trimmed.strip_prefix('v').unwrap_or(trimmed);
+        let version = version.split(['-', '+']).next().unwrap_or_default();
+        let mut parts = version.split('.').map(|p| p.parse::<u32>());
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Ok(Version::new(major, minor, patch))
+            }
+            _ => Err(format!("Invalid version: {s:?}")),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A range of versions from `min` (inclusive) to `max` (exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionRange {
+    pub min: Version,
+    pub max: Version,
+}
+
+impl VersionRange {
+    pub const fn new(min: Version, max: Version) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns true if the version is in the range
+    pub fn contains(&self, version: &Version) -> bool {
+        self.min <= *version && *version < self.max
+    }
+
+    /// Parse the version and check that it's in the range
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the binary, used in the error message
+    /// * `version` - The version reported by the binary
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if the version is invalid or outside the range
+    pub fn check(&self, name: &str, version: &str) -> Result<Version, String> {
+        let version: Version = version
+            .parse()
+            .map_err(|e| format!("{name} reported an invalid version: {e}"))?;
+        if !self.contains(&version) {
+            return Err(format!(
+                "{name} version {version} is not compatible, expected version {self}"
+            ));
+        }
+        Ok(version)
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ">= {}, < {}", self.min, self.max)
+    }
+}
+
+/// What [extract_binary] did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extraction {
+    /// The binary on disk matched the embedded binary
+    Unchanged,
+    /// The binary didn't exist and was written
+    Written,
+    /// The binary on disk didn't match the embedded binary and was replaced
+    Replaced,
+}
+
+/// Write the embedded binary to the path, unless the binary there has the same contents
+///
+/// On Unix the written binary is made executable.
+///
+/// # Arguments
+///
+/// * `path` - Path to extract the binary to
+/// * `embedded` - The contents of the embedded binary
+///
+/// # Errors
+///
+/// Returns an error if the existing binary cannot be read, or the binary cannot be written
+pub fn extract_binary(path: &Path, embedded: &[u8]) -> io::Result<Extraction> {
+    let extraction = match fs::read(path) {
+        Ok(existing) if existing == embedded => return Ok(Extraction::Unchanged),
+        Ok(_) => Extraction::Replaced,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Extraction::Written,
+        Err(e) => return Err(e),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Write to a temporary file first, so a failed write never leaves a truncated binary behind
+    let tmp_path = path.with_extension("part");
+    fs::write(&tmp_path, embedded)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o755))?;
+    }
+    fs::rename(&tmp_path, path)?;
+    Ok(extraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!("0.1.0".parse(), Ok(Version::new(0, 1, 0)));
+        assert_eq!("v1.12.3\n".parse(), Ok(Version::new(1, 12, 3)));
+        assert_eq!("0.2.0-rc1".parse(), Ok(Version::new(0, 2, 0)));
+        assert!("0.1".parse::<Version>().is_err());
+        assert!("mtgogetter version 0.1.0".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn test_version_range_check() {
+        let range = VersionRange::new(Version::new(0, 1, 0), Version::new(0, 2, 0));
+
+        assert_eq!(
+            range.check("MTGO Getter", "0.1.9"),
+            Ok(Version::new(0, 1, 9))
+        );
+        assert_eq!(
+            range.check("MTGO Getter", "v0.2.0"),
+            Err(
+                "MTGO Getter version 0.2.0 is not compatible, expected version >= 0.1.0, < 0.2.0"
+                    .to_string()
+            )
+        );
+        assert!(range.check("MTGO Getter", "0.0.9").is_err());
+        assert!(range.check("MTGO Getter", "").is_err());
+        assert!(MTGOGETTER_COMPATIBLE.contains(&Version::new(0, 1, 0)));
+        assert!(MTGO_PREPROCESSOR_COMPATIBLE.contains(&Version::new(0, 1, 0)));
+    }
+
+    #[test]
+    fn test_extract_binary() {
+        let tmp_dir = TempDir::new().unwrap();
+        let bin = tmp_dir.child("bin").join("mtgogetter");
+
+        assert_eq!(extract_binary(&bin, b"new").unwrap(), Extraction::Written);
+        assert_eq!(extract_binary(&bin, b"new").unwrap(), Extraction::Unchanged);
+
+        // A stale binary from another release
+        fs::write(&bin, b"old").unwrap();
+        assert_eq!(extract_binary(&bin, b"new").unwrap(), Extraction::Replaced);
+        assert_eq!(fs::read(&bin).unwrap(), b"new");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(bin.metadata().unwrap().permissions().mode() & 0o111, 0o111);
+        }
+    }
+}
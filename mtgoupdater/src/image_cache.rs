@@ -0,0 +1,321 @@
+//! On-disk cache of card images keyed by Scryfall ID.
+//!
+//! Images missing from the cache are fetched with an [ImageFetcher], e.g. from Scryfall with `MTGO Getter`
+//! or from a local mirror. Without a fetcher the cache only serves the images it already has, so it works offline.
+//! The cache has a size limit and evicts the least recently used images when it's exceeded.
+
+use std::{
+    error, fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::mtgogetter_api;
+
+/// File extension of the cached images
+const IMAGE_EXTENSION: &str = "jpg";
+
+/// A source of card images, e.g. the Scryfall API or a local directory
+pub trait ImageFetcher: Send + Sync {
+    /// The name of the fetcher, used in logs and error messages
+    fn name(&self) -> &str;
+
+    /// Fetch the image of the card with the given Scryfall ID and save it to `dest`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image cannot be fetched or saved
+    fn fetch(&self, scryfall_id: &str, dest: &Path) -> Result<(), Box<dyn error::Error>>;
+}
+
+/// An [ImageFetcher] that downloads the images from the Scryfall API with
+/// [download_custom_url](mtgogetter_api::download_custom_url)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScryfallImageFetcher;
+
+impl ScryfallImageFetcher {
+    /// The URL of the normal sized image of the card with the given Scryfall ID
+    pub fn url(scryfall_id: &str) -> String {
+        format!("https://api.scryfall.com/cards/{scryfall_id}?format=image&version=normal")
+    }
+}
+
+impl ImageFetcher for ScryfallImageFetcher {
+    fn name(&self) -> &str {
+        "Scryfall"
+    }
+
+    fn fetch(&self, scryfall_id: &str, dest: &Path) -> Result<(), Box<dyn error::Error>> {
+        let dest_str = match dest.to_str() {
+            Some(dest_str) => dest_str,
+            None => return Err(format!("Invalid path: {dest:?}").into()),
+        };
+        let url = Self::url(scryfall_id);
+        let out = mtgogetter_api::download_custom_url(&url, false, Some(dest_str))?;
+        if !out.status.success() {
+            return Err(format!(
+                "Failed to download image from {url}: {stderr}",
+                stderr = String::from_utf8_lossy(&out.stderr)
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// An [ImageFetcher] that copies the images from a local directory or mirror,
+/// where each image is named by the Scryfall ID of the card, e.g. `<scryfall id>.jpg`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalDirFetcher {
+    dir: PathBuf,
+}
+
+impl LocalDirFetcher {
+    /// Create a new [LocalDirFetcher] that copies images from the given directory
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl ImageFetcher for LocalDirFetcher {
+    fn name(&self) -> &str {
+        "Local directory"
+    }
+
+    fn fetch(&self, scryfall_id: &str, dest: &Path) -> Result<(), Box<dyn error::Error>> {
+        let src = self.dir.join(format!("{scryfall_id}.{IMAGE_EXTENSION}"));
+        fs::copy(&src, dest).map_err(|e| format!("Failed to copy {src:?}: {e}"))?;
+        Ok(())
+    }
+}
+
+/// A size limited cache of card images in a directory
+///
+/// The last access time of an image is tracked with the modification time of its file.
+pub struct ImageCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    fetcher: Option<Box<dyn ImageFetcher>>,
+}
+
+impl ImageCache {
+    /// Create a new [ImageCache]
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory the images are cached in, it's created when the first image is fetched
+    /// * `max_bytes` - The maximum size of the cached images
+    /// * `fetcher` - Fetches the images that are not cached, if [None] only cached images are available
+    pub fn new(
+        dir: impl AsRef<Path>,
+        max_bytes: u64,
+        fetcher: Option<Box<dyn ImageFetcher>>,
+    ) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            max_bytes,
+            fetcher,
+        }
+    }
+
+    /// The directory the images are cached in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The [name](ImageFetcher::name) of the fetcher of missing images, [None] if only cached images are available
+    pub fn fetcher_name(&self) -> Option<&str> {
+        self.fetcher.as_ref().map(|fetcher| fetcher.name())
+    }
+
+    /// Get the path to the cached image of the card with the given Scryfall ID, fetching it if it's not cached
+    ///
+    /// # Returns
+    ///
+    /// The path to the image, or [None] if it's not cached and there's no fetcher
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ID is invalid, or the image cannot be fetched or saved
+    pub fn get(&self, scryfall_id: &str) -> Result<Option<PathBuf>, Box<dyn error::Error>> {
+        if !is_valid_id(scryfall_id) {
+            return Err(format!("Invalid Scryfall ID: {scryfall_id}").into());
+        }
+        let path = self.image_path(scryfall_id);
+        if path.is_file() {
+            // Mark as recently used
+            fs::File::options()
+                .append(true)
+                .open(&path)?
+                .set_modified(SystemTime::now())?;
+            return Ok(Some(path));
+        }
+
+        let fetcher = match &self.fetcher {
+            Some(fetcher) => fetcher,
+            None => return Ok(None),
+        };
+        fs::create_dir_all(&self.dir)?;
+        // Fetch to a temporary file so a failed fetch never leaves a broken image in the cache
+        let tmp_path = path.with_extension("part");
+        if let Err(e) = fetcher.fetch(scryfall_id, &tmp_path) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(format!("{name}: {e}", name = fetcher.name()).into());
+        }
+        fs::rename(&tmp_path, &path)?;
+        self.evict(Some(&path))?;
+        Ok(Some(path))
+    }
+
+    /// Remove the least recently used images until the cache is within its size limit
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - An image that is never removed, e.g. the one that was just fetched
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes removed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory cannot be read or an image cannot be removed
+    pub fn evict(&self, keep: Option<&Path>) -> io::Result<u64> {
+        let mut images = self.images()?;
+        let mut size: u64 = images.iter().map(|(_, len, _)| len).sum();
+        // Oldest first
+        images.sort_by_key(|(_, _, used)| *used);
+
+        let mut removed = 0;
+        for (path, len, _) in images {
+            if size <= self.max_bytes {
+                break;
+            }
+            if keep.is_some_and(|keep| keep == path) {
+                continue;
+            }
+            fs::remove_file(&path)?;
+            size -= len;
+            removed += len;
+        }
+        Ok(removed)
+    }
+
+    /// The total size of the cached images in bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory cannot be read
+    pub fn size(&self) -> io::Result<u64> {
+        Ok(self.images()?.iter().map(|(_, len, _)| len).sum())
+    }
+
+    fn image_path(&self, scryfall_id: &str) -> PathBuf {
+        self.dir.join(format!("{scryfall_id}.{IMAGE_EXTENSION}"))
+    }
+
+    /// The cached images with their size and last access time
+    fn images(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let entries = match self.dir.read_dir() {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut images = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != IMAGE_EXTENSION) {
+                continue;
+            }
+            let metadata = path.metadata()?;
+            images.push((path, metadata.len(), metadata.modified()?));
+        }
+        Ok(images)
+    }
+}
+
+/// A Scryfall ID is a UUID, anything else could escape the cache directory
+fn is_valid_id(scryfall_id: &str) -> bool {
+    !scryfall_id.is_empty()
+        && scryfall_id
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use temp_dir::TempDir;
+
+    const ID_A: &str = "0000579f-7b35-4ed3-b44c-db2a538066fe";
+    const ID_B: &str = "00006596-1166-4a79-8443-ca9f82e6db4e";
+    const ID_C: &str = "0000a54c-a511-4925-92dc-01b937f9afad";
+
+    fn mirror_with_images(ids: &[&str]) -> TempDir {
+        let mirror = TempDir::new().unwrap();
+        for id in ids {
+            fs::write(mirror.child(format!("{id}.jpg")), [0u8; 100]).unwrap();
+        }
+        mirror
+    }
+
+    fn set_last_used(path: &Path, secs_ago: u64) {
+        fs::File::options()
+            .append(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(secs_ago))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_fetch_from_local_dir_and_serve_offline() {
+        let mirror = mirror_with_images(&[ID_A]);
+        let cache_dir = TempDir::new().unwrap();
+
+        let cache = ImageCache::new(
+            cache_dir.path(),
+            1000,
+            Some(Box::new(LocalDirFetcher::new(mirror.path()))),
+        );
+        let path = cache.get(ID_A).unwrap().unwrap();
+        assert_eq!(path, cache_dir.path().join(format!("{ID_A}.jpg")));
+        assert!(cache.get(ID_B).is_err());
+        assert!(!cache_dir.path().join(format!("{ID_B}.part")).exists());
+
+        // Offline, only the cached image is available
+        let offline = ImageCache::new(cache_dir.path(), 1000, None);
+        assert_eq!(offline.get(ID_A).unwrap(), Some(path));
+        assert_eq!(offline.get(ID_B).unwrap(), None);
+        assert!(offline.get("../secrets").is_err());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mirror = mirror_with_images(&[ID_A, ID_B, ID_C]);
+        let cache_dir = TempDir::new().unwrap();
+        let cache = ImageCache::new(
+            cache_dir.path(),
+            250,
+            Some(Box::new(LocalDirFetcher::new(mirror.path()))),
+        );
+
+        let path_a = cache.get(ID_A).unwrap().unwrap();
+        let path_b = cache.get(ID_B).unwrap().unwrap();
+        set_last_used(&path_a, 20);
+        set_last_used(&path_b, 10);
+        // Using A makes B the least recently used
+        cache.get(ID_A).unwrap();
+
+        let path_c = cache.get(ID_C).unwrap().unwrap();
+
+        assert!(path_a.exists());
+        assert!(!path_b.exists());
+        assert!(path_c.exists());
+        assert_eq!(cache.size().unwrap(), 200);
+    }
+}
@@ -7,13 +7,19 @@ use std::fs;
 use std::path::Path;
 use std::sync::OnceLock;
 
+pub mod bin_check;
 pub mod date;
+pub mod image_cache;
 pub mod internal_only;
 pub mod mtgo_card;
 pub mod mtgo_preprocessor_api;
 pub mod mtgogetter_api;
+pub mod price_history;
+pub mod price_list;
+pub mod price_source;
+pub mod scryfall;
 mod util;
-mod zip_util;
+pub mod zip_util;
 
 pub use mtgogetter_api::download_custom_url as get_custom_url;
 pub use mtgogetter_api::download_goatbots_card_definitions as get_goatbots_card_definitions;
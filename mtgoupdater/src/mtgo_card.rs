@@ -1,8 +1,16 @@
+use std::collections::BTreeMap;
+
 use serde_derive::{Deserialize, Serialize};
 
+use crate::{
+    price_source,
+    scryfall::{Legality, ScryfallCard},
+};
+
 /// This is the struct that represents a card in the MTGO collection.
 ///
-/// It is not the same as a paper card which can have additional fields such as released_at and prices in USD and EUR.
+/// It is not the same as a paper card which can have additional fields such as prices in USD and EUR.
+/// The card attributes from Scryfall are only set for cards found in the Scryfall data, see [MtgoCard::apply_scryfall].
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct MtgoCard {
     pub id: u32,
@@ -13,6 +21,118 @@ pub struct MtgoCard {
     pub foil: bool,
     pub goatbots_price: f32,
     pub scryfall_price: Option<f32>,
+    /// Prices from additional [PriceSource](crate::price_source::PriceSource)s by the name of the source
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prices: BTreeMap<Box<str>, f32>,
+    /// Color symbols, e.g. `G` for green, empty for colorless cards
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<Box<str>>,
+    /// e.g. `Creature — Human Druid`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_line: Option<Box<str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mana_value: Option<f32>,
+    /// Legality by format name, e.g. `modern`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub legalities: BTreeMap<Box<str>, Legality>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collector_number: Option<Box<str>>,
+    /// Release date of the printing as `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub released_at: Option<Box<str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scryfall_id: Option<Box<str>>,
+}
+
+impl MtgoCard {
+    /// The MTGO catalog ID of the Event Ticket
+    pub const EVENT_TICKET_ID: u32 = 1;
+
+    /// Returns the [ItemCategory] of the item
+    ///
+    /// Event Tickets are identified by their catalog ID, and boosters by their [Rarity::Booster] rarity.
+    pub fn category(&self) -> ItemCategory {
+        if self.id == Self::EVENT_TICKET_ID {
+            ItemCategory::EventTicket
+        } else if self.rarity == Rarity::Booster {
+            ItemCategory::Sealed
+        } else {
+            ItemCategory::Card
+        }
+    }
+
+    /// Set the card attributes from the Scryfall data of the printing
+    ///
+    /// Attributes that are missing from the Scryfall data, e.g. from an older `MTGO Getter` version, are left unset.
+    pub fn apply_scryfall(&mut self, card: &ScryfallCard) {
+        fn non_empty(s: &str) -> Option<Box<str>> {
+            (!s.is_empty()).then(|| s.into())
+        }
+        self.colors.clone_from(&card.colors);
+        self.type_line = non_empty(&card.type_line);
+        // Older MTGO Getter versions don't save the mana value, so it's only known if there's a type line
+        self.mana_value = self.type_line.as_ref().map(|_| card.cmc);
+        self.legalities.clone_from(&card.legalities);
+        self.collector_number = non_empty(&card.collector_number);
+        self.released_at = non_empty(&card.released_at);
+        self.scryfall_id = non_empty(&card.id);
+    }
+
+    /// Returns the legality of the card in the given format, e.g. `modern`, [None] if it's not known
+    pub fn legality(&self, format: &str) -> Option<Legality> {
+        self.legalities.get(format).copied()
+    }
+
+    /// Returns the price of the item at the price source with the given name
+    ///
+    /// The built-in sources are [GOATBOTS](price_source::GOATBOTS) and [CARDHOARDER](price_source::CARDHOARDER),
+    /// any other name is looked up in the prices from additional sources.
+    pub fn price(&self, source: &str) -> Option<f32> {
+        match source {
+            price_source::GOATBOTS => Some(self.goatbots_price),
+            price_source::CARDHOARDER => self.scryfall_price,
+            _ => self.prices.get(source).copied(),
+        }
+    }
+
+    /// Returns the name and price of every source with a price for the item, starting with the built-in sources
+    pub fn all_prices(&self) -> impl Iterator<Item = (&str, f32)> {
+        [
+            (price_source::GOATBOTS, Some(self.goatbots_price)),
+            (price_source::CARDHOARDER, self.scryfall_price),
+        ]
+        .into_iter()
+        .filter_map(|(source, price)| price.map(|p| (source, p)))
+        .chain(self.prices.iter().map(|(source, p)| (source.as_ref(), *p)))
+    }
+
+    /// Returns the name and price of every [PriceSide::Buy](price_source::PriceSide::Buy) source with a price for the item
+    ///
+    /// These are the prices the item can be sold for, unlike the sell prices of a custom price list.
+    pub fn buy_prices(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.all_prices().filter(|(source, _)| {
+            price_source::PriceSide::of(source) == price_source::PriceSide::Buy
+        })
+    }
+}
+
+/// The category of an MTGO item, distinguishing cards from sealed product and the Event Ticket currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ItemCategory {
+    Card,
+    Sealed,
+    EventTicket,
+}
+
+impl ItemCategory {
+    /// Returns the name of the category
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemCategory::Card => "Cards",
+            ItemCategory::Sealed => "Sealed",
+            ItemCategory::EventTicket => "Event Tickets",
+        }
+    }
 }
 
 /// Represents the rarity of an MTGO item (e.g. card, booster, event ticket)
@@ -122,5 +242,97 @@ mod tests {
         assert_eq!(deserialized[1].rarity, Rarity::Common);
         assert_eq!(deserialized[1].goatbots_price, 0.002);
         assert_eq!(deserialized[1].scryfall_price, Some(0.05));
+        assert!(deserialized[1].colors.is_empty());
+        assert_eq!(deserialized[1].type_line, None);
+        assert!(deserialized[1].legalities.is_empty());
+    }
+
+    #[test]
+    fn test_scryfall_attributes_roundtrip() {
+        let mut card = MtgoCard {
+            id: 31745,
+            quantity: 1,
+            name: "Noble Hierarch".into(),
+            ..Default::default()
+        };
+        let plain_json = serde_json::to_string(&card).unwrap();
+        card.apply_scryfall(&ScryfallCard {
+            id: "6adfe928-1305-444d-b709-1e714544daaf".into(),
+            mtgo_id: 31745,
+            type_line: "Creature — Human Druid".into(),
+            cmc: 1.,
+            colors: vec!["G".into()],
+            collector_number: "87".into(),
+            released_at: "2009-02-06".into(),
+            legalities: BTreeMap::from([("modern".into(), Legality::Legal)]),
+            ..Default::default()
+        });
+
+        let deserialized: MtgoCard =
+            serde_json::from_str(&serde_json::to_string(&card).unwrap()).unwrap();
+
+        assert!(!plain_json.contains("type_line"));
+        assert_eq!(deserialized, card);
+        assert_eq!(card.mana_value, Some(1.));
+        assert_eq!(card.collector_number.as_deref(), Some("87"));
+        assert_eq!(card.legality("modern"), Some(Legality::Legal));
+        assert_eq!(card.legality("pauper"), None);
+    }
+
+    #[test]
+    fn test_item_category() {
+        let ticket = MtgoCard {
+            id: MtgoCard::EVENT_TICKET_ID,
+            name: "Event Ticket".into(),
+            rarity: Rarity::None,
+            ..Default::default()
+        };
+        let booster = MtgoCard {
+            id: 74160,
+            name: "Modern Horizons 3 Booster".into(),
+            rarity: Rarity::Booster,
+            ..Default::default()
+        };
+        let swamp = MtgoCard {
+            id: 235,
+            name: "Swamp".into(),
+            ..Default::default()
+        };
+
+        assert_eq!(ticket.category(), ItemCategory::EventTicket);
+        assert_eq!(booster.category(), ItemCategory::Sealed);
+        assert_eq!(swamp.category(), ItemCategory::Card);
+    }
+
+    #[test]
+    fn test_price_by_source() {
+        let mut card = MtgoCard {
+            id: 235,
+            goatbots_price: 0.002,
+            scryfall_price: None,
+            ..Default::default()
+        };
+        card.prices.insert("Manatraders".into(), 0.004);
+
+        assert_eq!(card.price(price_source::GOATBOTS), Some(0.002));
+        assert_eq!(card.price(price_source::CARDHOARDER), None);
+        assert_eq!(card.price("Manatraders"), Some(0.004));
+        assert_eq!(card.price("Unknown"), None);
+        assert_eq!(
+            card.all_prices().collect::<Vec<_>>(),
+            vec![(price_source::GOATBOTS, 0.002), ("Manatraders", 0.004)]
+        );
+
+        // The sell prices of a custom price list are not what the card can be sold for
+        card.prices.insert("Our Bot buy".into(), 0.001);
+        card.prices.insert("Our Bot sell".into(), 0.003);
+        assert_eq!(
+            card.buy_prices().collect::<Vec<_>>(),
+            vec![
+                (price_source::GOATBOTS, 0.002),
+                ("Manatraders", 0.004),
+                ("Our Bot buy", 0.001)
+            ]
+        );
     }
 }
@@ -0,0 +1,966 @@
+//! Compact columnar store for the daily price and quantity history of a collection.
+//!
+//! Storing a full JSON snapshot of the collection every day (see [zip_price_history](crate::zip_price_history)) is large and slow to query,
+//! as every snapshot has to be decompressed and parsed to get the history of a single card.
+//! Instead the [PriceHistoryStore] keeps a dictionary of card IDs and one column per value (quantity, Goatbots price, Scryfall price) for each day,
+//! where each value is stored as the difference from the previous day. Prices rarely change from one day to the next, so most values are encoded in a single byte.
+//!
+//! # File layout
+//!
+//! The file starts with the [MAGIC] bytes and the format [VERSION], followed by an append-only sequence of records:
+//!
+//! * `D` - Dictionary record: the number of new card IDs followed by the IDs, each as the delta from the previous ID.
+//!   New IDs are appended to the dictionary, and the position in the dictionary is the column index of the card.
+//! * `S` - Day record: the date (days since the UNIX epoch), the number of cards `n` in the dictionary at the time of writing,
+//!   then `n` quantity deltas, `n` Goatbots price deltas, and `n` Scryfall price deltas.
+//!   A day record with the same date as the previous day record replaces the values of that day.
+//!
+//! Records are only ever appended, so if writing is interrupted only the last record can be incomplete.
+//! An incomplete record at the end of the file is removed when the store is opened, see [PriceHistoryStore::removed_bytes].
+//! The ID deltas of a dictionary record must be positive, and the number of cards in a day record must match the dictionary,
+//! so a corrupt record in the middle of the file is reported as invalid data instead of being mistaken for an incomplete record.
+//!
+//! All integers are LEB128 varints, and deltas are zigzag encoded. Prices are stored in thousandths of a tix, and a missing price is stored as `-1`.
+
+use std::{
+    collections::HashMap,
+    error,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+
+use crate::{date, mtgo_card::MtgoCard};
+
+/// Magic bytes at the start of a price history store file
+pub const MAGIC: &[u8; 4] = b"MCMH";
+/// Version of the file format
+pub const VERSION: u8 = 1;
+/// Length of the [MAGIC] bytes and the [VERSION]
+const HEADER_LEN: u64 = 5;
+
+const RECORD_DICTIONARY: u8 = b'D';
+const RECORD_DAY: u8 = b'S';
+
+/// Prefix and suffix of the collection snapshot file names, e.g. `mtgo-cards_2023-11-06T083944Z.json`
+const SNAPSHOT_PREFIX: &str = "mtgo-cards_";
+const SNAPSHOT_SUFFIX: &str = ".json";
+
+/// Prices are stored as integers in thousandths of a tix
+const PRICE_SCALE: f32 = 1000.;
+/// Stored in place of a price when the card has no price, or is not in the collection that day
+const NO_PRICE: i64 = -1;
+
+/// The quantity and prices of a card on a given day
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricePoint {
+    pub date: NaiveDate,
+    pub quantity: u32,
+    pub goatbots_price: Option<f32>,
+    pub scryfall_price: Option<f32>,
+}
+
+/// The total value of the collection on a given day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyValue {
+    pub date: NaiveDate,
+    /// Value at Goatbots prices in tix
+    pub goatbots_value: f64,
+    /// Value at Cardhoarder (Scryfall) prices in tix
+    pub scryfall_value: f64,
+}
+
+/// The values of every card in the dictionary on a single day, indexed by dictionary position
+#[derive(Debug, Clone, Default, PartialEq)]
+struct DayColumns {
+    quantity: Vec<i64>,
+    goatbots: Vec<i64>,
+    scryfall: Vec<i64>,
+}
+
+impl DayColumns {
+    /// Extend the columns to cover `len` cards, new cards are absent
+    fn resize(&mut self, len: usize) {
+        self.quantity.resize(len, 0);
+        self.goatbots.resize(len, NO_PRICE);
+        self.scryfall.resize(len, NO_PRICE);
+    }
+
+    /// Add the deltas to the values
+    fn apply(&mut self, deltas: &DayColumns) {
+        self.resize(deltas.quantity.len().max(self.quantity.len()));
+        for (col, delta_col) in [
+            (&mut self.quantity, &deltas.quantity),
+            (&mut self.goatbots, &deltas.goatbots),
+            (&mut self.scryfall, &deltas.scryfall),
+        ] {
+            col.iter_mut().zip(delta_col).for_each(|(v, d)| *v += d);
+        }
+    }
+}
+
+/// A decoded record from the store file
+enum Record {
+    Dictionary(Vec<u32>),
+    Day { date: NaiveDate, deltas: DayColumns },
+}
+
+/// On-disk store of the daily quantity and prices of every card in a collection
+#[derive(Debug)]
+pub struct PriceHistoryStore {
+    path: PathBuf,
+    dictionary: Vec<u32>,
+    index: HashMap<u32, usize>,
+    days: Vec<NaiveDate>,
+    last: DayColumns,
+    removed_bytes: u64,
+}
+
+impl PriceHistoryStore {
+    /// Open the store at the given path, creating an empty store if the file doesn't exist
+    ///
+    /// An incomplete record at the end of the file, e.g. from a write that was interrupted, is removed,
+    /// see [PriceHistoryStore::removed_bytes].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or created, or with [io::ErrorKind::InvalidData]
+    /// if it is not a valid store file or has a corrupt record before the last one
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            let mut header = MAGIC.to_vec();
+            header.push(VERSION);
+            fs::write(&path, header)?;
+        }
+
+        let mut store = Self {
+            path,
+            dictionary: Vec::new(),
+            index: HashMap::new(),
+            days: Vec::new(),
+            last: DayColumns::default(),
+            removed_bytes: 0,
+        };
+        let mut reader = CountingReader::new(store.reader()?, HEADER_LEN);
+        // The dictionary records are written together with the day record that follows them,
+        // so they're only added once that day record is complete
+        let mut pending_ids = Vec::new();
+        let mut complete_len = HEADER_LEN;
+        let mut dictionary_len = 0;
+        loop {
+            match read_record(&mut reader, &mut dictionary_len) {
+                Ok(Some(Record::Dictionary(ids))) => pending_ids.extend(ids),
+                Ok(Some(Record::Day { date, deltas })) => {
+                    store.extend_dictionary(std::mem::take(&mut pending_ids));
+                    store.last.apply(&deltas);
+                    if store.days.last() != Some(&date) {
+                        store.days.push(date);
+                    }
+                    complete_len = reader.pos;
+                }
+                Ok(None) => break,
+                // The records read so far are valid, so the end of the file was reached within the last record
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+        }
+        store.last.resize(store.dictionary.len());
+
+        let file_len = fs::metadata(&store.path)?.len();
+        if complete_len < file_len {
+            OpenOptions::new()
+                .write(true)
+                .open(&store.path)?
+                .set_len(complete_len)?;
+            store.removed_bytes = file_len - complete_len;
+        }
+        Ok(store)
+    }
+
+    /// The number of bytes of an incomplete record that were removed from the end of the file when the store was opened
+    pub fn removed_bytes(&self) -> u64 {
+        self.removed_bytes
+    }
+
+    /// The path to the store file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The days stored, in ascending order
+    pub fn days(&self) -> &[NaiveDate] {
+        &self.days
+    }
+
+    /// The IDs of all the cards that have been in the collection on any of the stored days
+    pub fn card_ids(&self) -> &[u32] {
+        &self.dictionary
+    }
+
+    /// Append the quantity and prices of the given cards as the values for the given day
+    ///
+    /// Cards that are not in `cards` are stored as not in the collection that day.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [io::ErrorKind::InvalidInput] if the day is not after the last stored day,
+    /// or an error if writing to the file fails
+    pub fn append_day(&mut self, day: NaiveDate, cards: &[MtgoCard]) -> io::Result<()> {
+        if let Some(last_day) = self.days.last() {
+            if day <= *last_day {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Cannot append {day}, the store already contains {last_day}"),
+                ));
+            }
+        }
+        self.write_day(day, cards)
+    }
+
+    /// Store the quantity and prices of the given cards as the values for the given day,
+    /// replacing the stored values if it's the last stored day
+    ///
+    /// Used to keep the newest values of today when the collection is processed several times on the same day.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [io::ErrorKind::InvalidInput] if the day is before the last stored day,
+    /// or an error if writing to the file fails
+    pub fn record_day(&mut self, day: NaiveDate, cards: &[MtgoCard]) -> io::Result<()> {
+        if let Some(last_day) = self.days.last() {
+            if day < *last_day {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Cannot record {day}, the store already contains {last_day}"),
+                ));
+            }
+        }
+        self.write_day(day, cards)
+    }
+
+    /// Append a day record, with a dictionary record for the new cards, and update the in-memory state
+    fn write_day(&mut self, day: NaiveDate, cards: &[MtgoCard]) -> io::Result<()> {
+        let mut buf = Vec::new();
+
+        // Dictionary record for the cards that have never been stored before
+        let mut new_ids: Vec<u32> = cards
+            .iter()
+            .map(|c| c.id)
+            .filter(|id| !self.index.contains_key(id))
+            .collect();
+        new_ids.sort_unstable();
+        new_ids.dedup();
+        if !new_ids.is_empty() {
+            buf.push(RECORD_DICTIONARY);
+            write_varint(&mut buf, new_ids.len() as u64);
+            let mut prev = 0;
+            for id in &new_ids {
+                write_varint(&mut buf, (id - prev) as u64);
+                prev = *id;
+            }
+            self.extend_dictionary(new_ids);
+        }
+
+        let mut today = DayColumns::default();
+        today.resize(self.dictionary.len());
+        for card in cards {
+            let idx = self.index[&card.id];
+            today.quantity[idx] += card.quantity as i64;
+            today.goatbots[idx] = encode_price(Some(card.goatbots_price));
+            today.scryfall[idx] = encode_price(card.scryfall_price);
+        }
+
+        // Day record with each column delta-encoded against the previous day
+        self.last.resize(self.dictionary.len());
+        buf.push(RECORD_DAY);
+        write_varint(&mut buf, days_since_epoch(day));
+        write_varint(&mut buf, self.dictionary.len() as u64);
+        for (col, prev_col) in [
+            (&today.quantity, &self.last.quantity),
+            (&today.goatbots, &self.last.goatbots),
+            (&today.scryfall, &self.last.scryfall),
+        ] {
+            for (value, prev) in col.iter().zip(prev_col) {
+                write_varint(&mut buf, zigzag(value - prev));
+            }
+        }
+
+        // A single write so a failure never leaves half a record behind in the common case
+        OpenOptions::new()
+            .append(true)
+            .open(&self.path)?
+            .write_all(&buf)?;
+
+        self.last = today;
+        if self.days.last() != Some(&day) {
+            self.days.push(day);
+        }
+        Ok(())
+    }
+
+    /// Read the total value of the collection on each of the stored days
+    ///
+    /// Cards without a price at a vendor don't count towards the value at that vendor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store file cannot be read or is invalid
+    pub fn daily_values(&self) -> io::Result<Vec<DailyValue>> {
+        let mut values = Vec::with_capacity(self.days.len());
+        let mut state = DayColumns::default();
+        let mut reader = self.reader()?;
+        let mut dictionary_len = 0;
+        while let Some(record) = read_record(&mut reader, &mut dictionary_len)? {
+            if let Record::Day { date, deltas } = record {
+                state.apply(&deltas);
+                if values.last().is_some_and(|v: &DailyValue| v.date == date) {
+                    values.pop();
+                }
+                let value_at = |prices: &[i64]| -> f64 {
+                    state
+                        .quantity
+                        .iter()
+                        .zip(prices)
+                        .filter_map(|(q, p)| decode_price(*p).map(|p| *q as f64 * p as f64))
+                        .sum()
+                };
+                values.push(DailyValue {
+                    date,
+                    goatbots_value: value_at(&state.goatbots),
+                    scryfall_value: value_at(&state.scryfall),
+                });
+            }
+        }
+        Ok(values)
+    }
+
+    /// Read the time series of quantity and prices of the card with the given ID
+    ///
+    /// Only days where the card was in the collection are included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store file cannot be read or is invalid
+    pub fn card_series(&self, id: u32) -> io::Result<Vec<PricePoint>> {
+        let idx = match self.index.get(&id) {
+            Some(idx) => *idx,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut series = Vec::new();
+        let (mut quantity, mut goatbots, mut scryfall) = (0, NO_PRICE, NO_PRICE);
+        let mut reader = self.reader()?;
+        let mut dictionary_len = 0;
+        while let Some(record) = read_record(&mut reader, &mut dictionary_len)? {
+            if let Record::Day { date, deltas } = record {
+                if idx >= deltas.quantity.len() {
+                    continue;
+                }
+                quantity += deltas.quantity[idx];
+                goatbots += deltas.goatbots[idx];
+                scryfall += deltas.scryfall[idx];
+                if series.last().is_some_and(|p: &PricePoint| p.date == date) {
+                    series.pop();
+                }
+                if quantity > 0 {
+                    series.push(PricePoint {
+                        date,
+                        quantity: quantity as u32,
+                        goatbots_price: decode_price(goatbots),
+                        scryfall_price: decode_price(scryfall),
+                    });
+                }
+            }
+        }
+        Ok(series)
+    }
+
+    /// Migrate the collection snapshots (pattern `mtgo-cards_YYYY-MM-DDTHHMMSSZ.json`) from a ZIP-file created by [zip_price_history](crate::zip_price_history)
+    ///
+    /// If there are several snapshots on the same day, the newest one is used.
+    /// Days that are not after the last stored day are skipped, so migrating the same archive twice is a no-op.
+    ///
+    /// # Returns
+    ///
+    /// The number of days added to the store
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ZIP-file cannot be read, a snapshot cannot be parsed, or writing to the store fails
+    pub fn migrate_zip_archive(
+        &mut self,
+        zip_file: impl AsRef<Path>,
+    ) -> Result<usize, Box<dyn error::Error>> {
+        let mut archive = zip::ZipArchive::new(File::open(zip_file)?)?;
+        let days = self.snapshots_to_migrate(archive.file_names());
+
+        for (day, name) in &days {
+            let mut json = String::new();
+            archive.by_name(name)?.read_to_string(&mut json)?;
+            let cards: Vec<MtgoCard> = serde_json::from_str(&json)?;
+            self.append_day(*day, &cards)?;
+        }
+        Ok(days.len())
+    }
+
+    /// Migrate the collection snapshots (pattern `mtgo-cards_YYYY-MM-DDTHHMMSSZ.json`) in the given directory,
+    /// i.e. the snapshots that have not been archived yet
+    ///
+    /// Same as [migrate_zip_archive](Self::migrate_zip_archive), so the archive should be migrated first as it has the older snapshots.
+    ///
+    /// # Returns
+    ///
+    /// The number of days added to the store
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read, a snapshot cannot be parsed, or writing to the store fails
+    pub fn migrate_snapshot_dir(
+        &mut self,
+        dir: impl AsRef<Path>,
+    ) -> Result<usize, Box<dyn error::Error>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            if let Ok(name) = entry?.file_name().into_string() {
+                names.push(name);
+            }
+        }
+        let days = self.snapshots_to_migrate(names.iter().map(String::as_str));
+
+        for (day, name) in &days {
+            let json = fs::read_to_string(dir.as_ref().join(name))?;
+            let cards: Vec<MtgoCard> = serde_json::from_str(&json)?;
+            self.append_day(*day, &cards)?;
+        }
+        Ok(days.len())
+    }
+
+    /// The newest snapshot of each day after the last stored day, in ascending order
+    fn snapshots_to_migrate<'a>(
+        &self,
+        names: impl Iterator<Item = &'a str>,
+    ) -> Vec<(NaiveDate, String)> {
+        let mut snapshots: HashMap<NaiveDate, (chrono::DateTime<chrono::Utc>, &str)> =
+            HashMap::new();
+        for name in names {
+            let timestamp = match name
+                .strip_prefix(SNAPSHOT_PREFIX)
+                .and_then(|n| n.strip_suffix(SNAPSHOT_SUFFIX))
+                .map(date::parse_naive_date)
+            {
+                Some(Ok(timestamp)) => timestamp,
+                _ => continue,
+            };
+            let day = timestamp.date_naive();
+            match snapshots.get(&day) {
+                Some((newest, _)) if *newest >= timestamp => (),
+                _ => {
+                    snapshots.insert(day, (timestamp, name));
+                }
+            }
+        }
+
+        let mut days: Vec<(NaiveDate, String)> = snapshots
+            .into_iter()
+            .map(|(day, (_, name))| (day, name.to_string()))
+            .filter(|(day, _)| self.days.last().is_none_or(|last| day > last))
+            .collect();
+        days.sort();
+        days
+    }
+
+    fn reader(&self) -> io::Result<BufReader<File>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut header = [0u8; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC || header[4] != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{:?} is not a version {VERSION} price history store",
+                    self.path
+                ),
+            ));
+        }
+        Ok(reader)
+    }
+
+    fn extend_dictionary(&mut self, ids: Vec<u32>) {
+        for id in ids {
+            self.index.insert(id, self.dictionary.len());
+            self.dictionary.push(id);
+        }
+    }
+}
+
+/// Read the next record, returns [None] at the end of the file
+///
+/// `dictionary_len` is the number of card IDs in the dictionary records read so far, it's updated when a dictionary record is read.
+///
+/// # Errors
+///
+/// Returns an error with [io::ErrorKind::InvalidData] if the record is corrupt,
+/// or with [io::ErrorKind::UnexpectedEof] if the file ends within the record
+fn read_record(reader: &mut impl Read, dictionary_len: &mut usize) -> io::Result<Option<Record>> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+    let mut tag = [0u8; 1];
+    if reader.read(&mut tag)? == 0 {
+        return Ok(None);
+    }
+    match tag[0] {
+        RECORD_DICTIONARY => {
+            // The count is not trusted for the allocation, a corrupt file could claim any number of IDs
+            let count = read_varint(reader)?;
+            let mut ids = Vec::new();
+            let mut prev = 0u64;
+            for i in 0..count {
+                let delta = read_varint(reader)?;
+                // The IDs are written in ascending order without duplicates
+                if delta == 0 && i > 0 {
+                    return Err(invalid("Duplicate card ID in price history store"));
+                }
+                prev = prev
+                    .checked_add(delta)
+                    .filter(|id| *id <= u32::MAX as u64)
+                    .ok_or_else(|| invalid("Card ID out of range in price history store"))?;
+                ids.push(prev as u32);
+            }
+            *dictionary_len += ids.len();
+            Ok(Some(Record::Dictionary(ids)))
+        }
+        RECORD_DAY => {
+            let date = date_from_days_since_epoch(read_varint(reader)?);
+            let n = read_varint(reader)? as usize;
+            // Every day record has a value for every card in the dictionary at the time of writing
+            if n != *dictionary_len {
+                return Err(invalid(&format!(
+                    "Day record with {n} cards in price history store, the dictionary has {dictionary_len} cards"
+                )));
+            }
+            let mut read_column = || -> io::Result<Vec<i64>> {
+                (0..n).map(|_| read_varint(reader).map(unzigzag)).collect()
+            };
+            let quantity = read_column()?;
+            let goatbots = read_column()?;
+            let scryfall = read_column()?;
+            Ok(Some(Record::Day {
+                date,
+                deltas: DayColumns {
+                    quantity,
+                    goatbots,
+                    scryfall,
+                },
+            }))
+        }
+        tag => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unknown record type in price history store: {tag:#x}"),
+        )),
+    }
+}
+
+/// Counts the bytes read, to find the end of the last complete record
+struct CountingReader<R> {
+    inner: R,
+    pos: u64,
+}
+
+impl<R> CountingReader<R> {
+    fn new(inner: R, pos: u64) -> Self {
+        Self { inner, pos }
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn read_varint(reader: &mut impl Read) -> io::Result<u64> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    let mut byte = [0u8; 1];
+    loop {
+        reader.read_exact(&mut byte)?;
+        if shift >= 64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Varint in price history store is too long",
+            ));
+        }
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn encode_price(price: Option<f32>) -> i64 {
+    match price {
+        Some(price) if price >= 0. => (price * PRICE_SCALE).round() as i64,
+        _ => NO_PRICE,
+    }
+}
+
+fn decode_price(value: i64) -> Option<f32> {
+    if value < 0 {
+        None
+    } else {
+        Some(value as f32 / PRICE_SCALE)
+    }
+}
+
+fn unix_epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).expect("Invalid UNIX epoch date")
+}
+
+fn days_since_epoch(day: NaiveDate) -> u64 {
+    day.signed_duration_since(unix_epoch()).num_days() as u64
+}
+
+fn date_from_days_since_epoch(days: u64) -> NaiveDate {
+    unix_epoch() + chrono::Duration::days(days as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    fn card(id: u32, quantity: u32, goatbots_price: f32, scryfall_price: Option<f32>) -> MtgoCard {
+        MtgoCard {
+            id,
+            quantity,
+            goatbots_price,
+            scryfall_price,
+            ..Default::default()
+        }
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 11, d).unwrap()
+    }
+
+    #[test]
+    fn test_varint_zigzag_roundtrip() {
+        for value in [0, 1, -1, 63, -64, 1000, -123456, i64::MAX / 2, i64::MIN / 2] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, zigzag(value));
+            assert_eq!(unzigzag(read_varint(&mut buf.as_slice()).unwrap()), value);
+        }
+        // Small deltas are a single byte
+        let mut buf = Vec::new();
+        write_varint(&mut buf, zigzag(-3));
+        assert_eq!(buf.len(), 1);
+    }
+
+    #[test]
+    fn test_append_and_read_series() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.child("history.mcmh");
+
+        let mut store = PriceHistoryStore::open(&path).unwrap();
+        store
+            .append_day(
+                day(1),
+                &[card(235, 4, 0.002, Some(0.01)), card(1, 100, 0., None)],
+            )
+            .unwrap();
+        store
+            .append_day(
+                day(2),
+                &[card(235, 5, 0.003, Some(0.01)), card(66, 1, 12.5, None)],
+            )
+            .unwrap();
+        store
+            .append_day(day(3), &[card(66, 2, 11.0, Some(13.2))])
+            .unwrap();
+
+        // Reopen to make sure everything is read back from disk
+        let store = PriceHistoryStore::open(&path).unwrap();
+        assert_eq!(store.days(), &[day(1), day(2), day(3)]);
+        assert_eq!(store.card_ids(), &[1, 235, 66]);
+
+        assert_eq!(
+            store.card_series(235).unwrap(),
+            vec![
+                PricePoint {
+                    date: day(1),
+                    quantity: 4,
+                    goatbots_price: Some(0.002),
+                    scryfall_price: Some(0.01),
+                },
+                PricePoint {
+                    date: day(2),
+                    quantity: 5,
+                    goatbots_price: Some(0.003),
+                    scryfall_price: Some(0.01),
+                },
+            ]
+        );
+        assert_eq!(
+            store.card_series(66).unwrap(),
+            vec![
+                PricePoint {
+                    date: day(2),
+                    quantity: 1,
+                    goatbots_price: Some(12.5),
+                    scryfall_price: None,
+                },
+                PricePoint {
+                    date: day(3),
+                    quantity: 2,
+                    goatbots_price: Some(11.0),
+                    scryfall_price: Some(13.2),
+                },
+            ]
+        );
+        assert!(store.card_series(999).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_daily_values() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut store = PriceHistoryStore::open(tmp_dir.child("history.mcmh")).unwrap();
+        store
+            .append_day(
+                day(1),
+                &[card(235, 4, 0.5, Some(1.)), card(66, 1, 10., None)],
+            )
+            .unwrap();
+        store
+            .append_day(day(2), &[card(66, 2, 11., Some(12.))])
+            .unwrap();
+
+        let values = store.daily_values().unwrap();
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].date, day(1));
+        assert!((values[0].goatbots_value - 12.).abs() < 1e-6);
+        assert!((values[0].scryfall_value - 4.).abs() < 1e-6);
+        assert!((values[1].goatbots_value - 22.).abs() < 1e-6);
+        assert!((values[1].scryfall_value - 24.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_append_day_must_be_after_last_day() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut store = PriceHistoryStore::open(tmp_dir.child("history.mcmh")).unwrap();
+        store.append_day(day(2), &[card(1, 1, 0., None)]).unwrap();
+
+        let err = store
+            .append_day(day(2), &[card(1, 2, 0., None)])
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = store
+            .append_day(day(1), &[card(1, 2, 0., None)])
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_migrate_zip_archive() {
+        let tmp_dir = TempDir::new().unwrap();
+        let snapshot = |name: &str, cards: &[MtgoCard]| {
+            let path = tmp_dir.child(name);
+            fs::write(&path, serde_json::to_string(cards).unwrap()).unwrap();
+            path
+        };
+        let mut archive = crate::zip_util::Archive::new(tmp_dir.child("history.zip"));
+        archive.move_file(snapshot(
+            "mtgo-cards_2023-11-01T083944Z.json",
+            &[card(235, 1, 0.002, None)],
+        ));
+        // Two snapshots on the same day, the newest is used
+        archive.move_file(snapshot(
+            "mtgo-cards_2023-11-02T083944Z.json",
+            &[card(235, 2, 0.002, None)],
+        ));
+        archive.move_file(snapshot(
+            "mtgo-cards_2023-11-02T203944Z.json",
+            &[card(235, 3, 0.004, None)],
+        ));
+        let archived = archive.archive().unwrap();
+
+        let mut store = PriceHistoryStore::open(tmp_dir.child("history.mcmh")).unwrap();
+        assert_eq!(store.migrate_zip_archive(archived.location()).unwrap(), 2);
+        // Migrating again doesn't add anything
+        assert_eq!(store.migrate_zip_archive(archived.location()).unwrap(), 0);
+
+        let series = store.card_series(235).unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[1].date, day(2));
+        assert_eq!(series[1].quantity, 3);
+        assert_eq!(series[1].goatbots_price, Some(0.004));
+    }
+
+    #[test]
+    fn test_record_day_replaces_last_day() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.child("history.mcmh");
+        let mut store = PriceHistoryStore::open(&path).unwrap();
+        store.record_day(day(1), &[card(1, 1, 1., None)]).unwrap();
+        store.record_day(day(2), &[card(1, 2, 1., None)]).unwrap();
+        // Processed again later the same day
+        store
+            .record_day(day(2), &[card(1, 3, 2., None), card(7, 1, 5., None)])
+            .unwrap();
+        assert_eq!(
+            store
+                .record_day(day(1), &[card(1, 1, 1., None)])
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        let store = PriceHistoryStore::open(&path).unwrap();
+        assert_eq!(store.days(), &[day(1), day(2)]);
+        let series = store.card_series(1).unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[1].quantity, 3);
+        assert_eq!(series[1].goatbots_price, Some(2.));
+        assert_eq!(store.card_series(7).unwrap().len(), 1);
+        let values = store.daily_values().unwrap();
+        assert_eq!(values.len(), 2);
+        assert!((values[1].goatbots_value - 11.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_migrate_snapshot_dir() {
+        let tmp_dir = TempDir::new().unwrap();
+        let snapshot_dir = tmp_dir.child("collection-history");
+        fs::create_dir(&snapshot_dir).unwrap();
+        let snapshot = |name: &str, cards: &[MtgoCard]| {
+            fs::write(
+                snapshot_dir.join(name),
+                serde_json::to_string(cards).unwrap(),
+            )
+            .unwrap();
+        };
+        snapshot(
+            "mtgo-cards_2023-11-01T083944Z.json",
+            &[card(235, 1, 0.002, None)],
+        );
+        snapshot(
+            "mtgo-cards_2023-11-03T083944Z.json",
+            &[card(235, 2, 0.002, None)],
+        );
+        snapshot(
+            "mtgo-cards_2023-11-03T203944Z.json",
+            &[card(235, 3, 0.004, None)],
+        );
+        fs::write(snapshot_dir.join("notes.txt"), "not a snapshot").unwrap();
+
+        let mut store = PriceHistoryStore::open(tmp_dir.child("history.mcmh")).unwrap();
+        assert_eq!(store.migrate_snapshot_dir(&snapshot_dir).unwrap(), 2);
+        assert_eq!(store.migrate_snapshot_dir(&snapshot_dir).unwrap(), 0);
+
+        assert_eq!(store.days(), &[day(1), day(3)]);
+        let series = store.card_series(235).unwrap();
+        assert_eq!(series[1].quantity, 3);
+        assert_eq!(series[1].goatbots_price, Some(0.004));
+    }
+
+    #[test]
+    fn test_open_truncates_incomplete_record() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.child("history.mcmh");
+        let mut store = PriceHistoryStore::open(&path).unwrap();
+        store.append_day(day(1), &[card(1, 1, 1., None)]).unwrap();
+        let complete_len = fs::metadata(&path).unwrap().len();
+        store
+            .append_day(day(2), &[card(1, 2, 1., None), card(300, 1, 2., None)])
+            .unwrap();
+        // Cut off in the middle of the last day record, after its dictionary record
+        let full_len = fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(full_len - 2)
+            .unwrap();
+
+        let mut store = PriceHistoryStore::open(&path).unwrap();
+        assert_eq!(store.days(), &[day(1)]);
+        assert_eq!(store.card_ids(), &[1]);
+        assert_eq!(fs::metadata(&path).unwrap().len(), complete_len);
+        assert_eq!(store.removed_bytes(), full_len - 2 - complete_len);
+
+        // The store can be appended to again
+        store
+            .append_day(day(2), &[card(1, 2, 1., None), card(300, 1, 2., None)])
+            .unwrap();
+        let store = PriceHistoryStore::open(&path).unwrap();
+        assert_eq!(store.days(), &[day(1), day(2)]);
+        assert_eq!(store.card_series(300).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_open_rejects_corrupt_record() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.child("history.mcmh");
+        let mut store = PriceHistoryStore::open(&path).unwrap();
+        store.append_day(day(1), &[card(1, 1, 1., None)]).unwrap();
+        store
+            .append_day(day(2), &[card(1, 2, 1., None), card(300, 1, 2., None)])
+            .unwrap();
+        let bytes = fs::read(&path).unwrap();
+        let dictionary_start = HEADER_LEN as usize;
+        assert_eq!(bytes[dictionary_start], RECORD_DICTIONARY);
+        let day_start = dictionary_start + 3;
+        assert_eq!(bytes[day_start], RECORD_DAY);
+        let date_len = bytes[day_start + 1..]
+            .iter()
+            .position(|b| b & 0x80 == 0)
+            .unwrap()
+            + 1;
+
+        // A count in the first records that reads into the following records must not remove them
+        for (pos, corrupt_count) in [(dictionary_start + 1, 100), (day_start + 1 + date_len, 100)] {
+            let mut corrupt = bytes.clone();
+            corrupt[pos] = corrupt_count;
+            fs::write(&path, &corrupt).unwrap();
+
+            let err = PriceHistoryStore::open(&path).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(fs::read(&path).unwrap(), corrupt);
+        }
+    }
+
+    #[test]
+    fn test_open_invalid_file() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.child("not-a-store.mcmh");
+        fs::write(&path, "[]").unwrap();
+
+        assert!(PriceHistoryStore::open(&path).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+//! Custom price lists with buy and sell prices, e.g. the price sheets of a trading bot.
+//!
+//! A price list is imported from CSV or JSON. Each entry identifies a card by its ID, or by name, set and foil,
+//! and has a buy and/or sell price. The list is resolved against the collection into two [SourcePrices],
+//! named `<list> buy` and `<list> sell`, which are shown as any other price source.
+
+use std::{collections::HashMap, error, fs, path::Path};
+
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    mtgo_card::MtgoCard,
+    price_source::{self, SourcePrices},
+};
+
+/// A single entry in a [CustomPriceList]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PriceListEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<Box<str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set: Option<Box<str>>,
+    #[serde(default)]
+    pub foil: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buy: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sell: Option<f32>,
+}
+
+impl PriceListEntry {
+    /// Returns true if the entry identifies the given card
+    ///
+    /// Entries with an ID only match by ID, otherwise name and set are compared case-insensitively, and foil must match.
+    pub fn matches(&self, card: &MtgoCard) -> bool {
+        match (self.id, &self.name, &self.set) {
+            (Some(id), _, _) => id == card.id,
+            (None, Some(name), Some(set)) => {
+                self.foil == card.foil
+                    && name.eq_ignore_ascii_case(&card.name)
+                    && set.eq_ignore_ascii_case(&card.set)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A named list of buy and sell prices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomPriceList {
+    name: Box<str>,
+    entries: Vec<PriceListEntry>,
+}
+
+impl CustomPriceList {
+    /// Create a new [CustomPriceList] with the given name and entries
+    pub fn new(name: &str, entries: Vec<PriceListEntry>) -> Self {
+        Self {
+            name: name.into(),
+            entries,
+        }
+    }
+
+    /// The name of the price list
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The entries of the price list
+    pub fn entries(&self) -> &[PriceListEntry] {
+        &self.entries
+    }
+
+    /// Name of the price source with the buy prices
+    pub fn buy_source(&self) -> String {
+        format!("{} buy", self.name)
+    }
+
+    /// Name of the price source with the sell prices, a [PriceSide::Sell](price_source::PriceSide::Sell) source
+    pub fn sell_source(&self) -> String {
+        format!("{}{}", self.name, price_source::SELL_SOURCE_SUFFIX)
+    }
+
+    /// Read a price list from a `.csv` or `.json` file, the name of the list is the file name without extension
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, has another extension, or is not a valid price list
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn error::Error>> {
+        let path = path.as_ref();
+        let name = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(name) => name,
+            None => return Err(format!("Invalid price list file name: {path:?}").into()),
+        };
+        let contents = fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Ok(Self::parse_csv(name, &contents)?),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(Self::parse_json(name, &contents)?),
+            _ => Err(format!("Price lists must be .csv or .json files: {path:?}").into()),
+        }
+    }
+
+    /// Parse a JSON price list, an array of [PriceListEntry] objects
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is not an array of entries, or an entry is invalid
+    pub fn parse_json(name: &str, json: &str) -> Result<Self, String> {
+        let entries: Vec<PriceListEntry> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid JSON price list: {e}"))?;
+        for (idx, entry) in entries.iter().enumerate() {
+            validate_entry(entry).map_err(|e| format!("Entry {}: {e}", idx + 1))?;
+        }
+        Ok(Self::new(name, entries))
+    }
+
+    /// Parse a CSV price list
+    ///
+    /// The first line is a header with the columns `id`, `name`, `set`, `foil`, `buy` and `sell` in any order (case-insensitive).
+    /// Either `id` or `name` and `set` are required, as well as `buy` or `sell`. Other columns are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the line number if the header or a line is invalid
+    pub fn parse_csv(name: &str, csv: &str) -> Result<Self, String> {
+        let mut lines = csv
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty());
+        let header: Vec<String> = match lines.next() {
+            Some((_, header)) => split_csv_line(header)
+                .into_iter()
+                .map(|h| h.to_lowercase())
+                .collect(),
+            None => return Err("The price list is empty".into()),
+        };
+        let column = |title: &str| header.iter().position(|h| h == title);
+        let (id_col, name_col, set_col, foil_col, buy_col, sell_col) = (
+            column("id"),
+            column("name"),
+            column("set"),
+            column("foil"),
+            column("buy"),
+            column("sell"),
+        );
+        if id_col.is_none() && (name_col.is_none() || set_col.is_none()) {
+            return Err("The header must have an `id` column or `name` and `set` columns".into());
+        }
+        if buy_col.is_none() && sell_col.is_none() {
+            return Err("The header must have a `buy` and/or `sell` column".into());
+        }
+
+        let mut entries = Vec::new();
+        for (line_idx, line) in lines {
+            let fields = split_csv_line(line);
+            let field = |col: Option<usize>| {
+                col.and_then(|c| fields.get(c))
+                    .map(|f| f.as_str())
+                    .filter(|f| !f.is_empty())
+            };
+            let parse_err = |what: &str| format!("Invalid {what} on line {}: {line}", line_idx + 1);
+
+            let entry = PriceListEntry {
+                id: match field(id_col) {
+                    Some(id) => Some(id.parse().map_err(|_| parse_err("ID"))?),
+                    None => None,
+                },
+                name: field(name_col).map(Into::into),
+                set: field(set_col).map(Into::into),
+                foil: match field(foil_col).map(str::to_lowercase).as_deref() {
+                    None | Some("false" | "no" | "0") => false,
+                    Some("true" | "yes" | "1") => true,
+                    Some(_) => return Err(parse_err("foil")),
+                },
+                buy: match field(buy_col) {
+                    Some(buy) => Some(buy.parse().map_err(|_| parse_err("buy price"))?),
+                    None => None,
+                },
+                sell: match field(sell_col) {
+                    Some(sell) => Some(sell.parse().map_err(|_| parse_err("sell price"))?),
+                    None => None,
+                },
+            };
+            validate_entry(&entry).map_err(|e| format!("{e} on line {}: {line}", line_idx + 1))?;
+            entries.push(entry);
+        }
+        Ok(Self::new(name, entries))
+    }
+
+    /// Save the price list as JSON to the given path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn error::Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Load a price list saved with [CustomPriceList::save]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn error::Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Resolve the entries against the cards, into the buy and sell prices by card ID
+    ///
+    /// An entry matching the card by ID takes precedence over an entry matching it by name and set,
+    /// and of several entries with the same ID, or the same name, set and foil, the last one is used.
+    ///
+    /// # Returns
+    ///
+    /// The buy prices and the sell prices, see [CustomPriceList::buy_source] and [CustomPriceList::sell_source]
+    pub fn resolve(
+        &self,
+        cards: &[MtgoCard],
+        updated_at: DateTime<Utc>,
+    ) -> (SourcePrices, SourcePrices) {
+        // Index the name entries so resolving is linear in the size of the collection
+        let mut by_name: HashMap<(String, String, bool), &PriceListEntry> = HashMap::new();
+        let mut by_id: HashMap<u32, &PriceListEntry> = HashMap::new();
+        for entry in &self.entries {
+            match (entry.id, &entry.name, &entry.set) {
+                (Some(id), _, _) => {
+                    by_id.insert(id, entry);
+                }
+                (None, Some(name), Some(set)) => {
+                    by_name.insert((name.to_lowercase(), set.to_lowercase(), entry.foil), entry);
+                }
+                _ => (),
+            }
+        }
+
+        let (mut buy, mut sell) = (HashMap::new(), HashMap::new());
+        for card in cards {
+            let entry = by_id.get(&card.id).or_else(|| {
+                by_name.get(&(card.name.to_lowercase(), card.set.to_lowercase(), card.foil))
+            });
+            if let Some(entry) = entry {
+                if let Some(price) = entry.buy {
+                    buy.insert(card.id, price);
+                }
+                if let Some(price) = entry.sell {
+                    sell.insert(card.id, price);
+                }
+            }
+        }
+        (
+            SourcePrices::new(&self.buy_source(), updated_at, buy),
+            SourcePrices::new(&self.sell_source(), updated_at, sell),
+        )
+    }
+}
+
+/// Check that the entry identifies a card and has at least one price, and that its prices are finite and not negative
+fn validate_entry(entry: &PriceListEntry) -> Result<(), String> {
+    if entry.id.is_none() && (entry.name.is_none() || entry.set.is_none()) {
+        return Err("Missing ID or name and set".into());
+    }
+    if entry.buy.is_none() && entry.sell.is_none() {
+        return Err("Missing buy and sell price".into());
+    }
+    if [entry.buy, entry.sell]
+        .iter()
+        .flatten()
+        .any(|p| !p.is_finite())
+    {
+        return Err("Price is not a number".into());
+    }
+    if entry.buy.unwrap_or_default() < 0. || entry.sell.unwrap_or_default() < 0. {
+        return Err("Negative price".into());
+    }
+    Ok(())
+}
+
+/// Split a CSV line into fields, supporting double-quoted fields with commas and escaped (`""`) quotes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use temp_dir::TempDir;
+
+    fn card(id: u32, name: &str, set: &str, foil: bool) -> MtgoCard {
+        MtgoCard {
+            id,
+            name: name.into(),
+            set: set.into(),
+            foil,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_and_resolve() {
+        let csv = "Name,Set,Foil,Buy,Sell\n\
+                   \"Jace, the Mind Sculptor\",A25,false,20.5,22\n\
+                   Swamp,prm,yes,,0.01\n";
+        let list = CustomPriceList::parse_csv("Our Bot", csv).unwrap();
+        assert_eq!(list.entries().len(), 2);
+        assert_eq!(
+            list.entries()[0].name.as_deref(),
+            Some("Jace, the Mind Sculptor")
+        );
+
+        let cards = vec![
+            card(1, "Jace, the Mind Sculptor", "A25", false),
+            card(2, "Jace, the Mind Sculptor", "A25", true),
+            card(3, "Swamp", "PRM", true),
+        ];
+        let (buy, sell) = list.resolve(&cards, Utc::now());
+
+        assert_eq!(buy.source(), "Our Bot buy");
+        assert_eq!(sell.source(), "Our Bot sell");
+        assert_eq!(buy.get(1), Some(20.5));
+        assert_eq!(sell.get(1), Some(22.));
+        // Foil doesn't match the non-foil entry
+        assert_eq!(buy.get(2), None);
+        assert_eq!(buy.get(3), None);
+        assert_eq!(sell.get(3), Some(0.01));
+    }
+
+    #[test]
+    fn test_parse_csv_errors() {
+        assert_eq!(
+            CustomPriceList::parse_csv("x", "name,buy\nSwamp,1").unwrap_err(),
+            "The header must have an `id` column or `name` and `set` columns"
+        );
+        assert_eq!(
+            CustomPriceList::parse_csv("x", "id,buy\n235,abc").unwrap_err(),
+            "Invalid buy price on line 2: 235,abc"
+        );
+        assert_eq!(
+            CustomPriceList::parse_csv("x", "id,buy,sell\n235,,").unwrap_err(),
+            "Missing buy and sell price on line 2: 235,,"
+        );
+        assert_eq!(
+            CustomPriceList::parse_csv("x", "id,buy,sell\n235,NaN,1").unwrap_err(),
+            "Price is not a number on line 2: 235,NaN,1"
+        );
+        assert_eq!(
+            CustomPriceList::parse_csv("x", "id,sell\n235,inf").unwrap_err(),
+            "Price is not a number on line 2: 235,inf"
+        );
+    }
+
+    #[test]
+    fn test_resolve_id_entry_takes_precedence() {
+        let csv = "id,name,set,sell
+                   235,,,0.5
+                   ,Swamp,PRM,0.1
+                   ,Swamp,PRM,0.2
+                   235,,,0.3
+";
+        let list = CustomPriceList::parse_csv("Our Bot", csv).unwrap();
+
+        let (_, sell) = list.resolve(
+            &[
+                card(235, "Swamp", "PRM", false),
+                card(66, "Swamp", "PRM", false),
+            ],
+            Utc::now(),
+        );
+
+        assert_eq!(sell.get(235), Some(0.3));
+        assert_eq!(sell.get(66), Some(0.2));
+    }
+
+    #[test]
+    fn test_json_from_file_save_load() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.child("bot-prices.json");
+        fs::write(
+            &path,
+            r#"[{"id": 235, "buy": 0.001, "sell": 0.002}, {"name": "Swamp", "set": "PRM", "sell": 0.5}]"#,
+        )
+        .unwrap();
+
+        let list = CustomPriceList::from_file(&path).unwrap();
+        assert_eq!(list.name(), "bot-prices");
+        assert_eq!(list.entries()[0].id, Some(235));
+        assert!(list.entries()[1].matches(&card(66, "swamp", "prm", false)));
+
+        let saved = tmp_dir.child("saved.json");
+        list.save(&saved).unwrap();
+        assert_eq!(CustomPriceList::load(&saved).unwrap(), list);
+
+        fs::write(tmp_dir.child("prices.txt"), "").unwrap();
+        assert!(CustomPriceList::from_file(tmp_dir.child("prices.txt")).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+//! Pluggable sources of card prices.
+//!
+//! The Goatbots and Cardhoarder prices are part of the output of the MTGO Preprocessor and always available.
+//! Additional vendors implement [PriceSource], and their prices are stored by source name in [MtgoCard::prices].
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    error, fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::{mtgo_card::MtgoCard, mtgogetter_api};
+
+/// Name of the built-in Goatbots price source, see [MtgoCard::goatbots_price]
+pub const GOATBOTS: &str = "Goatbots";
+/// Name of the built-in Cardhoarder price source (prices from Scryfall), see [MtgoCard::scryfall_price]
+pub const CARDHOARDER: &str = "Cardhoarder";
+/// Suffix of the names of the [PriceSide::Sell] sources, see [CustomPriceList::sell_source](crate::price_list::CustomPriceList::sell_source)
+pub const SELL_SOURCE_SUFFIX: &str = " sell";
+
+/// Which side of a trade the prices of a source are on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceSide {
+    /// What the source pays for a card, i.e. what the card can be sold for. The vendors and custom buy prices.
+    Buy,
+    /// What the source asks for a card, i.e. the sell prices of a custom price list
+    Sell,
+}
+
+impl PriceSide {
+    /// Returns the side of the price source with the given name
+    ///
+    /// Sources are identified by name in [MtgoCard::prices], so the sell-side sources are the ones
+    /// named with the [SELL_SOURCE_SUFFIX], and every other source is buy-side.
+    pub fn of(source: &str) -> Self {
+        if source.ends_with(SELL_SOURCE_SUFFIX) {
+            PriceSide::Sell
+        } else {
+            PriceSide::Buy
+        }
+    }
+}
+
+/// The prices from a single [PriceSource] by card ID, and the time they were fetched
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourcePrices {
+    source: Box<str>,
+    updated_at: DateTime<Utc>,
+    prices: HashMap<u32, f32>,
+}
+
+impl SourcePrices {
+    /// Create a new [SourcePrices] for the source with the given name
+    pub fn new(source: &str, updated_at: DateTime<Utc>, prices: HashMap<u32, f32>) -> Self {
+        Self {
+            source: source.into(),
+            updated_at,
+            prices,
+        }
+    }
+
+    /// The name of the source
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The time the prices were fetched
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    /// The price of the card with the given ID
+    pub fn get(&self, id: u32) -> Option<f32> {
+        self.prices.get(&id).copied()
+    }
+
+    /// The number of cards with a price
+    pub fn len(&self) -> usize {
+        self.prices.len()
+    }
+
+    /// Returns true if the source has no prices
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty()
+    }
+
+    /// Add the prices to the [MtgoCard::prices] of the cards under the name of the source
+    ///
+    /// # Returns
+    ///
+    /// The number of cards that got a price
+    pub fn apply(&self, cards: &mut [MtgoCard]) -> usize {
+        let mut priced = 0;
+        for card in cards.iter_mut() {
+            match self.get(card.id) {
+                Some(price) => {
+                    card.prices.insert(self.source.clone(), price);
+                    priced += 1;
+                }
+                None => {
+                    card.prices.remove(&self.source);
+                }
+            }
+        }
+        priced
+    }
+}
+
+/// A source of per-card prices, e.g. a vendor price list
+pub trait PriceSource {
+    /// The name of the source, used as column title and key in [MtgoCard::prices]
+    fn name(&self) -> &str;
+
+    /// Fetch the current prices from the source
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the prices cannot be fetched or parsed
+    fn fetch(&self) -> Result<SourcePrices, Box<dyn error::Error>>;
+}
+
+/// A [PriceSource] that downloads a CSV price list with [download_custom_url](mtgogetter_api::download_custom_url)
+///
+/// The CSV must have the card ID in the first column and the price in tix in the second column, e.g. `235,0.002`.
+/// A header line and additional columns are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvPriceSource {
+    name: Box<str>,
+    url: Box<str>,
+    save_as: PathBuf,
+}
+
+impl CsvPriceSource {
+    /// Create a new [CsvPriceSource]
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the source
+    /// * `url` - The URL of the CSV price list
+    /// * `save_as` - Path the downloaded price list is saved to, so it can be read again in offline mode
+    pub fn new(name: &str, url: &str, save_as: impl AsRef<Path>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            save_as: save_as.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The URL of the CSV price list
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Read the prices from the last downloaded price list without downloading it again
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the price list has not been downloaded or cannot be parsed
+    pub fn read_cached(&self) -> Result<SourcePrices, Box<dyn error::Error>> {
+        let updated_at: DateTime<Utc> = fs::metadata(&self.save_as)?.modified()?.into();
+        let csv = fs::read_to_string(&self.save_as)?;
+        Ok(parse_csv_prices(&self.name, &csv, updated_at)?)
+    }
+}
+
+impl PriceSource for CsvPriceSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fetch(&self) -> Result<SourcePrices, Box<dyn error::Error>> {
+        let save_as = match self.save_as.to_str() {
+            Some(save_as) => save_as,
+            None => return Err(format!("Invalid path: {:?}", self.save_as).into()),
+        };
+        let out = mtgogetter_api::download_custom_url(&self.url, false, Some(save_as))?;
+        if !out.status.success() {
+            return Err(format!(
+                "Failed to download {name} prices from {url}: {stderr}",
+                name = self.name,
+                url = self.url,
+                stderr = String::from_utf8_lossy(&out.stderr)
+            )
+            .into());
+        }
+        let csv = fs::read_to_string(&self.save_as)?;
+        Ok(parse_csv_prices(&self.name, &csv, Utc::now())?)
+    }
+}
+
+/// Parse a CSV price list with the card ID in the first column and the price in the second column
+///
+/// Empty lines and a header line (a first line without a numeric ID) are skipped.
+///
+/// # Errors
+///
+/// Returns an error with the line number if a line cannot be parsed, or its price is negative or not a finite number
+pub fn parse_csv_prices(
+    source: &str,
+    csv: &str,
+    updated_at: DateTime<Utc>,
+) -> Result<SourcePrices, String> {
+    let mut prices = HashMap::new();
+    for (line_idx, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split(',').map(|f| f.trim().trim_matches('"'));
+        let id = match fields.next().map(str::parse::<u32>) {
+            Some(Ok(id)) => id,
+            _ if line_idx == 0 => continue,
+            _ => return Err(format!("Invalid card ID on line {}: {line}", line_idx + 1)),
+        };
+        match fields.next().map(str::parse::<f32>) {
+            Some(Ok(price)) if price.is_finite() && price >= 0. => {
+                prices.insert(id, price);
+            }
+            _ => return Err(format!("Invalid price on line {}: {line}", line_idx + 1)),
+        }
+    }
+    Ok(SourcePrices::new(source, updated_at, prices))
+}
+
+/// Returns the names of all the price sources with a price for any of the cards
+///
+/// The built-in sources come first, followed by the additional sources in alphabetical order.
+pub fn source_names(cards: &[MtgoCard]) -> Vec<Box<str>> {
+    let additional: BTreeSet<&str> = cards
+        .iter()
+        .flat_map(|c| c.prices.keys().map(|k| k.as_ref()))
+        .collect();
+    [GOATBOTS, CARDHOARDER]
+        .into_iter()
+        .chain(additional)
+        .map(Into::into)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_csv_prices() {
+        let csv = "id,price,name\n235,0.002,Swamp\n\n\"66\", 12.5\n";
+        let prices = parse_csv_prices("Manatraders", csv, Utc::now()).unwrap();
+
+        assert_eq!(prices.source(), "Manatraders");
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices.get(235), Some(0.002));
+        assert_eq!(prices.get(66), Some(12.5));
+        assert_eq!(prices.get(1), None);
+
+        let err = parse_csv_prices("Manatraders", "235,0.002\n66,abc", Utc::now()).unwrap_err();
+        assert_eq!(err, "Invalid price on line 2: 66,abc");
+        let err = parse_csv_prices("Manatraders", "235,inf", Utc::now()).unwrap_err();
+        assert_eq!(err, "Invalid price on line 1: 235,inf");
+    }
+
+    #[test]
+    fn test_apply_prices_and_source_names() {
+        let mut cards = vec![
+            MtgoCard {
+                id: 235,
+                ..Default::default()
+            },
+            MtgoCard {
+                id: 66,
+                ..Default::default()
+            },
+        ];
+        cards[1].prices.insert("Manatraders".into(), 1.0);
+        let prices = parse_csv_prices("Manatraders", "235,0.002", Utc::now()).unwrap();
+
+        assert_eq!(prices.apply(&mut cards), 1);
+        assert_eq!(cards[0].price("Manatraders"), Some(0.002));
+        // Stale prices are removed
+        assert_eq!(cards[1].price("Manatraders"), None);
+
+        cards[1].prices.insert("Arbitrage Bot".into(), 0.5);
+        assert_eq!(
+            source_names(&cards),
+            vec![
+                GOATBOTS.into(),
+                CARDHOARDER.into(),
+                "Arbitrage Bot".into(),
+                "Manatraders".into()
+            ]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Card details from the Scryfall bulk data saved by `MTGO Getter`.
+//!
+//! `MTGO Getter` only keeps the MTGO cards and the fields that are relevant to the collection manager,
+//! such as the oracle text, legalities and set information of each printing.
+
+use std::{collections::BTreeMap, collections::HashMap, io, path::Path};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::mtgo_card::MtgoCard;
+
+/// The formats shown in the card details, the stats and the format filter, as the Scryfall format name and the display name
+pub const FORMATS: [(&str, &str); 6] = [
+    ("standard", "Standard"),
+    ("pioneer", "Pioneer"),
+    ("modern", "Modern"),
+    ("legacy", "Legacy"),
+    ("vintage", "Vintage"),
+    ("pauper", "Pauper"),
+];
+
+/// The legality of a card in a format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Legality {
+    Legal,
+    NotLegal,
+    Restricted,
+    Banned,
+    /// Any legality that Scryfall might add in the future
+    #[serde(other)]
+    Unknown,
+}
+
+impl Legality {
+    /// Returns true if the card can be played in the format, i.e. it's legal or restricted
+    pub fn is_playable(&self) -> bool {
+        matches!(self, Legality::Legal | Legality::Restricted)
+    }
+
+    /// A short description of the legality, e.g. `Not legal`
+    pub fn description(&self) -> &'static str {
+        match self {
+            Legality::Legal => "Legal",
+            Legality::NotLegal => "Not legal",
+            Legality::Restricted => "Restricted",
+            Legality::Banned => "Banned",
+            Legality::Unknown => "Unknown",
+        }
+    }
+}
+
+/// A printing of a card on MTGO as described by Scryfall
+///
+/// Every field except the MTGO ID is optional, as older `MTGO Getter` versions only saved a few of them.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ScryfallCard {
+    /// The Scryfall ID of the printing
+    #[serde(default)]
+    pub id: Box<str>,
+    pub mtgo_id: u32,
+    #[serde(default)]
+    pub mtgo_foil_id: Option<u32>,
+    #[serde(default)]
+    pub name: Box<str>,
+    #[serde(default)]
+    pub released_at: Box<str>,
+    #[serde(default)]
+    pub set: Box<str>,
+    #[serde(default)]
+    pub set_name: Box<str>,
+    #[serde(default)]
+    pub collector_number: Box<str>,
+    #[serde(default)]
+    pub type_line: Box<str>,
+    #[serde(default)]
+    pub mana_cost: Box<str>,
+    #[serde(default)]
+    pub cmc: f32,
+    #[serde(default)]
+    pub colors: Vec<Box<str>>,
+    #[serde(default)]
+    pub oracle_text: Box<str>,
+    /// Legality by format name, e.g. `modern`
+    #[serde(default)]
+    pub legalities: BTreeMap<Box<str>, Legality>,
+}
+
+impl ScryfallCard {
+    /// Returns the legality of the card in the given format, e.g. `modern`
+    pub fn legality(&self, format: &str) -> Option<Legality> {
+        self.legalities.get(format).copied()
+    }
+}
+
+/// The Scryfall cards indexed by their MTGO IDs, both the regular and the foil ID
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScryfallCards {
+    cards: Vec<ScryfallCard>,
+    by_mtgo_id: HashMap<u32, usize>,
+}
+
+impl ScryfallCards {
+    /// Create a new index of the given cards
+    pub fn new(cards: Vec<ScryfallCard>) -> Self {
+        let mut by_mtgo_id = HashMap::with_capacity(cards.len());
+        for (idx, card) in cards.iter().enumerate() {
+            by_mtgo_id.insert(card.mtgo_id, idx);
+            if let Some(foil_id) = card.mtgo_foil_id {
+                by_mtgo_id.insert(foil_id, idx);
+            }
+        }
+        Self { cards, by_mtgo_id }
+    }
+
+    /// Parse the JSON array of cards saved by `MTGO Getter`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is not an array of cards
+    pub fn from_json_str(json: &str) -> serde_json::Result<Self> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Read the JSON file of cards saved by `MTGO Getter`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not an array of cards
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Returns the card with the given MTGO ID, foil IDs included
+    pub fn get(&self, mtgo_id: u32) -> Option<&ScryfallCard> {
+        self.by_mtgo_id.get(&mtgo_id).map(|idx| &self.cards[*idx])
+    }
+
+    /// Set the card attributes of each of the given cards that has Scryfall data, see [MtgoCard::apply_scryfall]
+    ///
+    /// # Returns
+    ///
+    /// The number of cards that had Scryfall data
+    pub fn apply(&self, cards: &mut [MtgoCard]) -> usize {
+        let mut applied = 0;
+        for card in cards {
+            if let Some(scryfall) = self.get(card.id) {
+                card.apply_scryfall(scryfall);
+                applied += 1;
+            }
+        }
+        applied
+    }
+
+    /// Returns the number of cards
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns true if there are no cards
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_scryfall_bulk_data() {
+        let cards = ScryfallCards::from_file(Path::new(
+            "../test/test-data/scryfall/default-cards-small-5cards.json",
+        ))
+        .unwrap();
+
+        let fury_sliver = cards.get(25527).unwrap();
+        assert_eq!(cards.get(25528), Some(fury_sliver));
+        assert_eq!(fury_sliver.name.as_ref(), "Fury Sliver");
+        assert_eq!(fury_sliver.type_line.as_ref(), "Creature — Sliver");
+        assert_eq!(fury_sliver.mana_cost.as_ref(), "{5}{R}");
+        assert_eq!(fury_sliver.cmc, 6.);
+        assert_eq!(fury_sliver.set_name.as_ref(), "Time Spiral");
+        assert_eq!(fury_sliver.released_at.as_ref(), "2006-10-06");
+        assert_eq!(fury_sliver.legality("modern"), Some(Legality::Legal));
+        assert_eq!(fury_sliver.legality("standard"), Some(Legality::NotLegal));
+        assert_eq!(
+            fury_sliver.legality("paupercommander"),
+            Some(Legality::Restricted)
+        );
+    }
+
+    #[test]
+    fn test_parse_minimal_mtgogetter_output() {
+        let json =
+            std::fs::read_to_string("../test/test-data/mtgogetter-out/scryfall-card.json").unwrap();
+        let cards = ScryfallCards::from_json_str(&format!("[{json}]")).unwrap();
+
+        assert_eq!(cards.len(), 1);
+        let card = cards.get(25527).unwrap();
+        assert_eq!(card.name.as_ref(), "Fury Sliver");
+        assert!(card.legalities.is_empty());
+        assert_eq!(cards.get(1), None);
+    }
+
+    #[test]
+    fn test_apply_card_attributes() {
+        let cards = ScryfallCards::from_file(Path::new(
+            "../test/test-data/scryfall/default-cards-small-5cards.json",
+        ))
+        .unwrap();
+        let json = std::fs::read_to_string(
+            "../test/test-data/mtgoparser-out/collection-small-5cards.json",
+        )
+        .unwrap();
+        let mut collection: Vec<MtgoCard> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(cards.apply(&mut collection), 3);
+
+        let noble_hierarch = collection.iter().find(|c| c.id == 31745).unwrap();
+        assert_eq!(noble_hierarch.colors, vec![Box::from("G")]);
+        assert_eq!(
+            noble_hierarch.type_line.as_deref(),
+            Some("Creature — Human Druid")
+        );
+        assert_eq!(noble_hierarch.mana_value, Some(1.));
+        assert_eq!(noble_hierarch.collector_number.as_deref(), Some("87"));
+        assert_eq!(noble_hierarch.released_at.as_deref(), Some("2009-02-06"));
+        assert_eq!(
+            noble_hierarch.scryfall_id.as_deref(),
+            Some("6adfe928-1305-444d-b709-1e714544daaf")
+        );
+        assert_eq!(noble_hierarch.legality("modern"), Some(Legality::Legal));
+        let ticket = collection
+            .iter()
+            .find(|c| c.id == MtgoCard::EVENT_TICKET_ID)
+            .unwrap();
+        assert_eq!(ticket.type_line, None);
+    }
+}
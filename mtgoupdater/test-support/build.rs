@@ -0,0 +1,28 @@
+//! Compiles the fake binary with `rustc`.
+//!
+//! Binaries of a dependency are not built by Cargo when testing the dependent crate, so the fake binary is
+//! compiled here and its path is exposed to the library as `MCM_FAKE_BIN`.
+
+use std::{env, path::PathBuf, process::Command};
+
+const FAKE_BIN_SRC: &str = "fake/main.rs";
+const SCRIPT_SRC: &str = "src/script.rs";
+
+fn main() {
+    println!("cargo:rerun-if-changed={FAKE_BIN_SRC}");
+    println!("cargo:rerun-if-changed={SCRIPT_SRC}");
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is not set"));
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let fake_bin = out_dir.join(format!("fake_bin{}", env::consts::EXE_SUFFIX));
+
+    let status = Command::new(rustc)
+        .args(["--edition", "2021", "--crate-name", "fake_bin", "-o"])
+        .arg(&fake_bin)
+        .arg(FAKE_BIN_SRC)
+        .status()
+        .expect("Failed to run rustc");
+    assert!(status.success(), "Failed to compile {FAKE_BIN_SRC}");
+
+    println!("cargo:rustc-env=MCM_FAKE_BIN={}", fake_bin.display());
+}
@@ -0,0 +1,93 @@
+//! Stand-in for the `MTGO Getter` and `MTGO Preprocessor` binaries.
+//!
+//! Records its arguments in the log of invocations next to it, and responds as scripted in the script next to it.
+//! Compiled by the build script of `mtgoupdater-test-support`, see [script] for the script format.
+
+#[allow(dead_code)]
+#[path = "../src/script.rs"]
+mod script;
+
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::Path,
+    process, thread,
+};
+
+fn main() {
+    process::exit(run());
+}
+
+fn run() -> i32 {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return fail(&format!("Failed to get the path of the fake binary: {e}")),
+    };
+    if let Err(e) = record_call(&exe, &args) {
+        return fail(&format!("Failed to record the invocation: {e}"));
+    }
+
+    let rules = match fs::read_to_string(script::script_path(&exe)) {
+        Ok(text) => match script::decode(&text) {
+            Ok(rules) => rules,
+            Err(e) => return fail(&e),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return fail(&format!("Failed to read the script: {e}")),
+    };
+    let Some(rule) = script::find_rule(&rules, &args) else {
+        eprintln!(
+            "fake {name}: no response scripted for {args:?}",
+            name = exe.file_stem().unwrap_or_default().to_string_lossy()
+        );
+        return script::NOT_SCRIPTED_EXIT_CODE;
+    };
+    let response = &rule.response;
+
+    if let Some(hang) = response.hang {
+        thread::sleep(hang);
+    }
+    for copy in &response.copies {
+        let Some(dest) = copy.dest(&args) else {
+            return fail(&format!("Missing {flag} argument to copy {src:?} to", flag = copy.flag, src = copy.src));
+        };
+        if let Err(e) = fs::copy(&copy.src, &dest) {
+            return fail(&format!("Failed to copy {src:?} to {dest:?}: {e}", src = copy.src));
+        }
+    }
+
+    let mut stdout = io::stdout().lock();
+    if let Err(e) = stdout.write_all(response.stdout.as_bytes()) {
+        return fail(&format!("Failed to write to stdout: {e}"));
+    }
+    if let Some(path) = &response.stdout_file {
+        match fs::read(path) {
+            Ok(contents) => {
+                if let Err(e) = stdout.write_all(&contents) {
+                    return fail(&format!("Failed to write to stdout: {e}"));
+                }
+            }
+            Err(e) => return fail(&format!("Failed to read {path:?}: {e}")),
+        }
+    }
+    if let Err(e) = stdout.flush() {
+        return fail(&format!("Failed to write to stdout: {e}"));
+    }
+    eprint!("{}", response.stderr);
+    response.exit_code
+}
+
+/// Append the arguments to the log of invocations
+fn record_call(exe: &Path, args: &[String]) -> io::Result<()> {
+    let mut calls = fs::File::options()
+        .create(true)
+        .append(true)
+        .open(script::calls_path(exe))?;
+    calls.write_all(script::encode_call(args).as_bytes())
+}
+
+fn fail(msg: &str) -> i32 {
+    eprintln!("fake binary error: {msg}");
+    script::FAKE_ERROR_EXIT_CODE
+}
@@ -0,0 +1,274 @@
+//! Fake `MTGO Getter` and `MTGO Preprocessor` binaries for testing `MTGO Updater` offline, without the Go and C++ builds.
+//!
+//! The fakes are small Rust binaries that record how they were invoked, and respond with scripted stdout, stderr
+//! and exit codes. They can copy fixture files from `test/test-data` to the paths given in their arguments,
+//! like `MTGO Getter` saving downloads, and hang to simulate slow downloads.
+//!
+//! `MTGO Updater` only lets the paths to the binaries be set once per process,
+//! so every test in a test binary shares the same fakes and a [FakeSession] serializes the tests using them.
+//!
+//! # Example
+//!
+//! ```
+//! use mtgoupdater_test_support::{FakeBins, Response};
+//!
+//! let fakes = FakeBins::get();
+//! // In a test: `mtgoupdater::set_mtgogetter_bin(fakes.mtgogetter_path().into())`
+//! let session = fakes.session();
+//! session
+//!     .mtgogetter()
+//!     .respond(&["--version"], Response::new().stdout("mtgogetter version 0.1.0\n"));
+//!
+//! let out = std::process::Command::new(fakes.mtgogetter_path())
+//!     .arg("--version")
+//!     .output()
+//!     .unwrap();
+//! assert_eq!(out.stdout, b"mtgogetter version 0.1.0\n");
+//! assert_eq!(session.mtgogetter().calls(), [["--version"]]);
+//! ```
+
+pub mod script;
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::{Mutex, MutexGuard, OnceLock},
+    time::{Duration, SystemTime},
+};
+
+pub use script::{CopyFile, Response, Rule, FAKE_ERROR_EXIT_CODE, NOT_SCRIPTED_EXIT_CODE};
+
+/// The fake binary compiled by the build script
+const FAKE_BIN: &str = env!("MCM_FAKE_BIN");
+
+/// Prefix of the temporary directories the fake binaries are installed in, followed by the process ID
+const FAKE_BINS_DIR_PREFIX: &str = "mcm-fake-bins-";
+/// How long after its last use the directory of another test process is removed
+///
+/// Statics are never dropped, so a test process can't remove its own directory when it exits.
+const STALE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+static FAKE_BINS: OnceLock<FakeBins> = OnceLock::new();
+
+/// Path to a fixture in `test/test-data`
+///
+/// # Arguments
+///
+/// * `relative_path` - Path relative to `test/test-data`, e.g. `mtgo/Full Trade List-small-5cards.dek`
+pub fn fixture(relative_path: impl AsRef<Path>) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../test/test-data")
+        .join(relative_path)
+}
+
+/// The fake binaries installed for the test process
+#[derive(Debug)]
+pub struct FakeBins {
+    mtgogetter: PathBuf,
+    mtgo_preprocessor: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FakeBins {
+    /// The fake binaries, installed in a temporary directory unique to the test process on first use
+    ///
+    /// The directories left behind by test processes that haven't used them in a day are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fake binaries cannot be installed
+    pub fn get() -> &'static FakeBins {
+        FAKE_BINS.get_or_init(|| {
+            let dir = env::temp_dir().join(format!("{FAKE_BINS_DIR_PREFIX}{}", process::id()));
+            remove_stale_dirs(&env::temp_dir(), &dir, STALE_AFTER);
+            Self::install(&dir)
+                .unwrap_or_else(|e| panic!("Failed to install fake binaries in {dir:?}: {e}"))
+        })
+    }
+
+    fn install(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let mtgogetter = dir.join(format!("mtgogetter{}", env::consts::EXE_SUFFIX));
+        let mtgo_preprocessor = dir.join(format!("mtgo_preprocessor{}", env::consts::EXE_SUFFIX));
+        fs::copy(FAKE_BIN, &mtgogetter)?;
+        fs::copy(FAKE_BIN, &mtgo_preprocessor)?;
+        Ok(Self {
+            mtgogetter,
+            mtgo_preprocessor,
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Path to the fake `MTGO Getter`, to pass to `mtgoupdater::set_mtgogetter_bin`
+    pub fn mtgogetter_path(&self) -> &Path {
+        &self.mtgogetter
+    }
+
+    /// Path to the fake `MTGO Preprocessor`, to pass to `mtgoupdater::set_mtgoparser_bin`
+    pub fn mtgo_preprocessor_path(&self) -> &Path {
+        &self.mtgo_preprocessor
+    }
+
+    /// Start a session with no scripted responses and no recorded invocations
+    ///
+    /// Blocks until the previous session ends, a session ends when it's dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scripts or invocation logs cannot be removed
+    pub fn session(&self) -> FakeSession<'_> {
+        // A test that panicked while holding the lock doesn't affect the next session, as it starts from scratch
+        let guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let session = FakeSession {
+            bins: self,
+            _guard: guard,
+        };
+        session.mtgogetter().reset();
+        session.mtgo_preprocessor().reset();
+        session
+    }
+}
+
+/// Remove the fake binary directories in `parent` that were last modified more than `stale_after` ago, except `keep`
+///
+/// Failures are ignored, e.g. a directory that is still in use can't be removed on Windows.
+fn remove_stale_dirs(parent: &Path, keep: &Path, stale_after: Duration) {
+    let Ok(entries) = fs::read_dir(parent) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_fake_bins_dir = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with(FAKE_BINS_DIR_PREFIX));
+        if !is_fake_bins_dir || path == keep || !path.is_dir() {
+            continue;
+        }
+        let is_stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| {
+                SystemTime::now()
+                    .duration_since(modified)
+                    .is_ok_and(|age| age >= stale_after)
+            });
+        if is_stale {
+            let _ = fs::remove_dir_all(&path);
+        }
+    }
+}
+
+/// Exclusive use of the fake binaries, see [FakeBins::session]
+#[derive(Debug)]
+pub struct FakeSession<'b> {
+    bins: &'b FakeBins,
+    _guard: MutexGuard<'b, ()>,
+}
+
+impl FakeSession<'_> {
+    /// The fake `MTGO Getter`
+    pub fn mtgogetter(&self) -> FakeBin<'_> {
+        FakeBin {
+            path: &self.bins.mtgogetter,
+        }
+    }
+
+    /// The fake `MTGO Preprocessor`
+    pub fn mtgo_preprocessor(&self) -> FakeBin<'_> {
+        FakeBin {
+            path: &self.bins.mtgo_preprocessor,
+        }
+    }
+}
+
+/// Scripts the responses of a fake binary and reads its invocations
+#[derive(Debug, Clone, Copy)]
+pub struct FakeBin<'s> {
+    path: &'s Path,
+}
+
+impl FakeBin<'_> {
+    /// Path to the fake binary
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// Respond to invocations whose arguments start with `args`
+    ///
+    /// Rules are tried in the order they were added, an empty `args` matches every invocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the script cannot be read or written
+    pub fn respond(&self, args: &[&str], response: Response) -> &Self {
+        let script_path = script::script_path(self.path);
+        let mut rules = match fs::read_to_string(&script_path) {
+            Ok(text) => script::decode(&text).expect("The script was written by FakeBin"),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => panic!("Failed to read {script_path:?}: {e}"),
+        };
+        rules.push(Rule {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            response,
+        });
+        fs::write(&script_path, script::encode(&rules))
+            .unwrap_or_else(|e| panic!("Failed to write {script_path:?}: {e}"));
+        self
+    }
+
+    /// The arguments of every invocation since the session started, oldest first
+    ///
+    /// # Panics
+    ///
+    /// Panics if the log of invocations cannot be read
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        let calls_path = script::calls_path(self.path);
+        match fs::read_to_string(&calls_path) {
+            Ok(calls) => script::decode_calls(&calls),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => panic!("Failed to read {calls_path:?}: {e}"),
+        }
+    }
+
+    fn reset(&self) {
+        for path in [
+            script::script_path(self.path),
+            script::calls_path(self.path),
+        ] {
+            match fs::remove_file(&path) {
+                Ok(()) => (),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+                Err(e) => panic!("Failed to remove {path:?}: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_stale_dirs() {
+        let parent = env::temp_dir().join(format!("mcm-test-stale-dirs-{}", process::id()));
+        let stale = parent.join(format!("{FAKE_BINS_DIR_PREFIX}1"));
+        let current = parent.join(format!("{FAKE_BINS_DIR_PREFIX}2"));
+        let other = parent.join("other-dir");
+        for dir in [&stale, &current, &other] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("mtgogetter"), "").unwrap();
+        }
+
+        remove_stale_dirs(&parent, &current, STALE_AFTER);
+        assert!(stale.exists());
+
+        remove_stale_dirs(&parent, &current, Duration::ZERO);
+        assert!(!stale.exists());
+        assert!(current.exists());
+        assert!(other.exists());
+
+        fs::remove_dir_all(&parent).unwrap();
+    }
+}
@@ -0,0 +1,350 @@
+//! Scripts that tell a fake binary how to respond to an invocation.
+//!
+//! A script is a list of [Rule]s, an invocation gets the [Response] of the first rule whose arguments are a
+//! prefix of the invocation's arguments. The script is stored as a text file next to the fake binary,
+//! one tab-separated directive per line.
+//!
+//! This module is shared with the fake binary, so it only uses `std`.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Exit code of a fake binary that was invoked with arguments no rule matches
+pub const NOT_SCRIPTED_EXIT_CODE: i32 = 101;
+/// Exit code of a fake binary that failed to carry out its response, e.g. a fixture could not be copied
+pub const FAKE_ERROR_EXIT_CODE: i32 = 102;
+
+/// Path of the script of the fake binary
+pub fn script_path(bin: &Path) -> PathBuf {
+    bin.with_extension("script")
+}
+
+/// Path of the log of invocations of the fake binary
+pub fn calls_path(bin: &Path) -> PathBuf {
+    bin.with_extension("calls")
+}
+
+/// Copy a file to a path given as an argument of the invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyFile {
+    /// The file to copy, typically a fixture from `test/test-data`
+    pub src: PathBuf,
+    /// The flag that is followed by the destination, e.g. `--save-to-dir`
+    pub flag: String,
+    /// If set, the destination is a directory and the file is copied into it with this name
+    pub file_name: Option<String>,
+}
+
+impl CopyFile {
+    /// The destination of the copy in the arguments, or [None] if the flag is missing
+    pub fn dest(&self, args: &[String]) -> Option<PathBuf> {
+        let pos = args.iter().position(|arg| *arg == self.flag)?;
+        let dest = PathBuf::from(args.get(pos + 1)?);
+        Some(match &self.file_name {
+            Some(name) => dest.join(name),
+            None => dest,
+        })
+    }
+}
+
+/// What a fake binary does when invoked, in order: hang, copy files, write stdout and stderr and exit
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Response {
+    pub stdout: String,
+    /// A file whose contents are written to stdout after [Response::stdout]
+    pub stdout_file: Option<PathBuf>,
+    pub stderr: String,
+    pub exit_code: i32,
+    pub hang: Option<Duration>,
+    pub copies: Vec<CopyFile>,
+}
+
+impl Response {
+    /// A response that exits successfully without output
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the text to stdout
+    pub fn stdout(mut self, text: impl Into<String>) -> Self {
+        self.stdout = text.into();
+        self
+    }
+
+    /// Write the contents of the file to stdout
+    pub fn stdout_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.stdout_file = Some(path.into());
+        self
+    }
+
+    /// Write the text to stderr
+    pub fn stderr(mut self, text: impl Into<String>) -> Self {
+        self.stderr = text.into();
+        self
+    }
+
+    /// Exit with the code
+    pub fn exit_code(mut self, code: i32) -> Self {
+        self.exit_code = code;
+        self
+    }
+
+    /// Sleep before responding, to simulate a binary that hangs on e.g. a slow download
+    pub fn hang(mut self, duration: Duration) -> Self {
+        self.hang = Some(duration);
+        self
+    }
+
+    /// Copy the file into the directory that follows the flag, with the given file name
+    pub fn copy_to_dir(
+        mut self,
+        src: impl Into<PathBuf>,
+        flag: impl Into<String>,
+        file_name: impl Into<String>,
+    ) -> Self {
+        self.copies.push(CopyFile {
+            src: src.into(),
+            flag: flag.into(),
+            file_name: Some(file_name.into()),
+        });
+        self
+    }
+
+    /// Copy the file to the path that follows the flag
+    pub fn copy_to_path(mut self, src: impl Into<PathBuf>, flag: impl Into<String>) -> Self {
+        self.copies.push(CopyFile {
+            src: src.into(),
+            flag: flag.into(),
+            file_name: None,
+        });
+        self
+    }
+}
+
+/// A [Response] to invocations whose arguments start with [Rule::args]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub args: Vec<String>,
+    pub response: Response,
+}
+
+impl Rule {
+    /// Returns true if the rule applies to an invocation with the arguments
+    pub fn matches(&self, args: &[String]) -> bool {
+        args.starts_with(&self.args)
+    }
+}
+
+/// The first rule that applies to an invocation with the arguments
+pub fn find_rule<'r>(rules: &'r [Rule], args: &[String]) -> Option<&'r Rule> {
+    rules.iter().find(|rule| rule.matches(args))
+}
+
+/// Encode the rules as a script
+pub fn encode(rules: &[Rule]) -> String {
+    let mut script = String::new();
+    for rule in rules {
+        let response = &rule.response;
+        push_line(&mut script, "rule", &rule.args);
+        push_line(&mut script, "stdout", [&response.stdout]);
+        if let Some(path) = &response.stdout_file {
+            push_line(&mut script, "stdout_file", [&path.to_string_lossy()]);
+        }
+        push_line(&mut script, "stderr", [&response.stderr]);
+        push_line(&mut script, "exit", [&response.exit_code.to_string()]);
+        if let Some(hang) = response.hang {
+            push_line(&mut script, "hang_ms", [&hang.as_millis().to_string()]);
+        }
+        for copy in &response.copies {
+            push_line(
+                &mut script,
+                "copy",
+                [
+                    copy.src.to_string_lossy().as_ref(),
+                    copy.flag.as_str(),
+                    copy.file_name.as_deref().unwrap_or_default(),
+                ],
+            );
+        }
+    }
+    script
+}
+
+/// Decode a script encoded by [encode]
+///
+/// # Errors
+///
+/// Returns a description of the first invalid line
+pub fn decode(script: &str) -> Result<Vec<Rule>, String> {
+    let mut rules: Vec<Rule> = Vec::new();
+    for (line_no, line) in script.lines().enumerate() {
+        let mut fields = split_fields(line);
+        let directive = fields.remove(0);
+        let invalid = || format!("Invalid script line {}: {line:?}", line_no + 1);
+        if directive == "rule" {
+            rules.push(Rule {
+                args: fields,
+                response: Response::default(),
+            });
+            continue;
+        }
+        let response = &mut rules.last_mut().ok_or_else(invalid)?.response;
+        match (directive.as_str(), fields.as_slice()) {
+            ("stdout", [text]) => response.stdout = text.clone(),
+            ("stdout_file", [path]) => response.stdout_file = Some(path.into()),
+            ("stderr", [text]) => response.stderr = text.clone(),
+            ("exit", [code]) => response.exit_code = code.parse().map_err(|_| invalid())?,
+            ("hang_ms", [ms]) => {
+                response.hang = Some(Duration::from_millis(ms.parse().map_err(|_| invalid())?))
+            }
+            ("copy", [src, flag, file_name]) => response.copies.push(CopyFile {
+                src: src.into(),
+                flag: flag.clone(),
+                file_name: (!file_name.is_empty()).then(|| file_name.clone()),
+            }),
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(rules)
+}
+
+/// Encode the arguments of an invocation as a line in the log of invocations
+pub fn encode_call(args: &[String]) -> String {
+    let mut line = String::new();
+    push_line(&mut line, "call", args);
+    line
+}
+
+/// Decode the log of invocations, one list of arguments per invocation
+pub fn decode_calls(calls: &str) -> Vec<Vec<String>> {
+    calls
+        .lines()
+        .map(|line| split_fields(line).into_iter().skip(1).collect())
+        .collect()
+}
+
+fn push_line<S: AsRef<str>>(
+    out: &mut String,
+    directive: &str,
+    fields: impl IntoIterator<Item = S>,
+) {
+    out.push_str(directive);
+    for field in fields {
+        out.push('\t');
+        out.push_str(&escape(field.as_ref()));
+    }
+    out.push('\n');
+}
+
+fn split_fields(line: &str) -> Vec<String> {
+    line.split('\t').map(unescape).collect()
+}
+
+fn escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let rules = vec![
+            Rule {
+                args: args(&["--version"]),
+                response: Response::new().stdout("mtgogetter version 0.1.0\n"),
+            },
+            Rule {
+                args: args(&["update"]),
+                response: Response::new()
+                    .stderr("line 1\n\tindented \\ backslash")
+                    .exit_code(2)
+                    .hang(Duration::from_millis(50))
+                    .stdout_file("test-data/out.json")
+                    .copy_to_dir("a.json", "--save-to-dir", "price-history.json")
+                    .copy_to_path("b.json", "--save-as"),
+            },
+            Rule {
+                args: Vec::new(),
+                response: Response::new(),
+            },
+        ];
+
+        assert_eq!(decode(&encode(&rules)), Ok(rules));
+        assert!(decode("stdout\torphan\n").is_err());
+        assert!(decode("rule\nexit\tnot-a-number\n").is_err());
+    }
+
+    #[test]
+    fn test_find_rule_and_copy_dest() {
+        let rules = vec![
+            Rule {
+                args: args(&["download", "custom"]),
+                response: Response::new().copy_to_path("a.json", "--save-as"),
+            },
+            Rule {
+                args: args(&["download"]),
+                response: Response::new().exit_code(1),
+            },
+        ];
+        let invocation = args(&[
+            "download",
+            "custom",
+            "url-raw",
+            "url",
+            "--save-as",
+            "out.json",
+        ]);
+
+        let rule = find_rule(&rules, &invocation).unwrap();
+        assert_eq!(rule.response.exit_code, 0);
+        assert_eq!(
+            rule.response.copies[0].dest(&invocation),
+            Some(PathBuf::from("out.json"))
+        );
+        assert_eq!(rule.response.copies[0].dest(&args(&["--save-as"])), None);
+        assert_eq!(
+            find_rule(&rules, &args(&["download", "goatbots-price-history"]))
+                .unwrap()
+                .response
+                .exit_code,
+            1
+        );
+        assert_eq!(find_rule(&rules, &args(&["--version"])), None);
+        assert_eq!(
+            decode_calls(&encode_call(&invocation)),
+            vec![invocation.clone()]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Tests of the MTGO Getter and MTGO Preprocessor APIs against fake binaries, runs offline without the Go and C++ builds
+
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use mtgoupdater::{
+    bin_check::{MTGOGETTER_COMPATIBLE, MTGO_PREPROCESSOR_COMPATIBLE},
+    mtgo_preprocessor_api, mtgogetter_api,
+};
+use mtgoupdater_test_support::{fixture, FakeBins, FakeSession, Response, NOT_SCRIPTED_EXIT_CODE};
+use pretty_assertions::assert_eq;
+use temp_dir::TempDir;
+
+/// Point MTGO Updater to the fake binaries and start a session
+fn fake_session() -> FakeSession<'static> {
+    let fakes = FakeBins::get();
+    // Only the first test to run sets the paths
+    _ = mtgoupdater::set_mtgogetter_bin(fakes.mtgogetter_path().into());
+    _ = mtgoupdater::set_mtgoparser_bin(fakes.mtgo_preprocessor_path().into());
+    fakes.session()
+}
+
+fn parse_full_args(save_json_to_dir: Option<&Path>) -> Result<usize, std::io::Error> {
+    mtgo_preprocessor_api::run_mtgo_preprocessor_parse_full(
+        fixture("mtgo/Full Trade List-small-5cards.dek").as_os_str(),
+        fixture("mtgogetter-out/scryfall-small-5cards.json").as_os_str(),
+        fixture("goatbots/card-defs-small-5cards.json").as_os_str(),
+        fixture("goatbots/price-hist-small-5cards.json").as_os_str(),
+        save_json_to_dir.map(Path::as_os_str),
+    )
+    .map(|cards| cards.len())
+}
+
+#[test]
+fn test_versions_are_compatible() {
+    let session = fake_session();
+    session.mtgogetter().respond(
+        &["--version"],
+        Response::new().stdout("mtgogetter version 0.1.0\n"),
+    );
+    session
+        .mtgo_preprocessor()
+        .respond(&["--version"], Response::new().stdout("v0.1.0\n"));
+
+    let getter_out = mtgogetter_api::mtgogetter_version().unwrap();
+    let preproc_out = mtgo_preprocessor_api::run_mtgo_preprocessor_version().unwrap();
+
+    let getter_version = String::from_utf8_lossy(&getter_out.stdout);
+    assert!(MTGOGETTER_COMPATIBLE
+        .check(
+            "MTGO Getter",
+            getter_version
+                .trim()
+                .trim_start_matches("mtgogetter version ")
+        )
+        .is_ok());
+    assert!(MTGO_PREPROCESSOR_COMPATIBLE
+        .check(
+            "MTGO Preprocessor",
+            &String::from_utf8_lossy(&preproc_out.stdout)
+        )
+        .is_ok());
+    assert_eq!(session.mtgogetter().calls(), [["--version"]]);
+}
+
+#[test]
+fn test_mtgogetter_update_all_saves_fixtures() {
+    let session = fake_session();
+    session.mtgogetter().respond(
+        &["update"],
+        Response::new()
+            .copy_to_dir(
+                fixture("goatbots/price-hist-small-5cards.json"),
+                "--save-to-dir",
+                "price-history.json",
+            )
+            .copy_to_dir(
+                fixture("goatbots/card-defs-small-5cards.json"),
+                "--save-to-dir",
+                "card-definitions.json",
+            )
+            .copy_to_dir(
+                fixture("mtgogetter-out/scryfall-small-5cards.json"),
+                "--save-to-dir",
+                "scryfall-bulk.json",
+            ),
+    );
+    let appdata = TempDir::new().unwrap();
+
+    let out = mtgogetter_api::mtgogetter_update_all(appdata.path().as_os_str()).unwrap();
+
+    assert!(out.status.success());
+    for file in [
+        "price-history.json",
+        "card-definitions.json",
+        "scryfall-bulk.json",
+    ] {
+        assert!(appdata.child(file).is_file(), "{file} was not saved");
+    }
+    assert_eq!(
+        session.mtgogetter().calls(),
+        [["update", "--save-to-dir", appdata.path().to_str().unwrap()]]
+    );
+}
+
+#[test]
+fn test_mtgogetter_downloads() {
+    let session = fake_session();
+    session
+        .mtgogetter()
+        .respond(
+            &[
+                "download",
+                "custom",
+                "url-raw",
+                "https://example.com/card.json",
+            ],
+            Response::new().stdout_file(fixture("mtgogetter-out/scryfall-card.json")),
+        )
+        .respond(
+            &["download", "custom"],
+            Response::new().copy_to_path(fixture("mtgogetter-out/state_log.toml"), "--save-as"),
+        )
+        .respond(
+            &["download", "goatbots-price-history"],
+            Response::new()
+                .stderr("Failed to download: 503 Service Unavailable\n")
+                .exit_code(1),
+        );
+    let tmp_dir = TempDir::new().unwrap();
+    let save_as = tmp_dir.child("state_log.toml");
+
+    let to_stdout =
+        mtgoupdater::get_custom_url("https://example.com/card.json", false, None).unwrap();
+    let saved = mtgoupdater::get_custom_url(
+        "https://example.com/state_log.toml",
+        false,
+        save_as.to_str(),
+    )
+    .unwrap();
+    let failed = mtgoupdater::get_goatbots_price_history().unwrap();
+
+    assert_eq!(
+        String::from_utf8_lossy(&to_stdout.stdout),
+        include_str!("../../test/test-data/mtgogetter-out/scryfall-card.json")
+    );
+    assert!(saved.status.success());
+    assert_eq!(
+        fs::read_to_string(&save_as).unwrap(),
+        include_str!("../../test/test-data/mtgogetter-out/state_log.toml")
+    );
+    assert_eq!(failed.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&failed.stderr).contains("503"));
+}
+
+#[test]
+fn test_mtgo_preprocessor_parse_full() {
+    let session = fake_session();
+    session.mtgo_preprocessor().respond(
+        &["run"],
+        Response::new().stdout_file(fixture("mtgoparser-out/collection-small-5cards.json")),
+    );
+    let appdata = TempDir::new().unwrap();
+
+    assert_eq!(parse_full_args(Some(appdata.path())).unwrap(), 5);
+    let calls = session.mtgo_preprocessor().calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0][..2], ["run", "-u"]);
+    assert!(calls[0].ends_with(&[
+        "--appdata-dir".to_string(),
+        appdata.path().to_str().unwrap().to_string()
+    ]));
+}
+
+#[test]
+fn test_mtgo_preprocessor_parse_full_error() {
+    let session = fake_session();
+    session.mtgo_preprocessor().respond(
+        &["run"],
+        Response::new()
+            .stderr("Could not parse the full trade list\n")
+            .exit_code(1),
+    );
+
+    let err = parse_full_args(None).unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains("MTGO Preprocessor errored: Could not parse the full trade list"));
+}
+
+#[test]
+fn test_hanging_binary_is_waited_for() {
+    let session = fake_session();
+    let hang = Duration::from_millis(300);
+    session.mtgogetter().respond(
+        &["--version"],
+        Response::new()
+            .hang(hang)
+            .stdout("mtgogetter version 0.1.0\n"),
+    );
+
+    let start = Instant::now();
+    let out = mtgogetter_api::mtgogetter_version().unwrap();
+
+    assert!(start.elapsed() >= hang);
+    assert!(out.status.success());
+}
+
+#[test]
+fn test_unscripted_invocation_fails() {
+    let _session = fake_session();
+
+    let out = mtgo_preprocessor_api::run_mtgo_preprocessor_version().unwrap();
+
+    assert_eq!(out.status.code(), Some(NOT_SCRIPTED_EXIT_CODE));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("no response scripted"));
+}